@@ -0,0 +1,364 @@
+#![no_main]
+
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::{TryFrom, TryInto};
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use sized_chunks::{Chunk, InlineArray, RingBuffer, SparseChunk};
+
+mod assert;
+use assert::assert_panic;
+
+// Boxed, so that a value being dropped twice or not at all doesn't go unnoticed.
+type Value = Box<u32>;
+
+const SMALL: usize = 16;
+const LARGE: usize = 64;
+
+type Inline = InlineArray<Value, [usize; SMALL + 1]>;
+
+/// Everything the values might currently be living in.
+#[derive(Debug)]
+enum Holder {
+    SmallChunk(Chunk<Value, SMALL>),
+    Chunk(Chunk<Value, LARGE>),
+    SmallRing(RingBuffer<Value, SMALL>),
+    Ring(RingBuffer<Value, LARGE>),
+    Inline(Inline),
+    Vec(Vec<Value>),
+    Deque(VecDeque<Value>),
+    Array([Value; SMALL]),
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+enum Target {
+    SmallChunk,
+    Chunk,
+    SmallRing,
+    Ring,
+    Inline,
+    Vec,
+    Deque,
+    Array,
+}
+
+#[derive(Debug)]
+enum SparseHolder {
+    Small(SparseChunk<Value, SMALL>),
+    Large(SparseChunk<Value, LARGE>),
+    Map(BTreeMap<usize, Value>),
+}
+
+#[derive(Arbitrary, Debug, Clone, Copy)]
+enum SparseTarget {
+    Small,
+    Large,
+    Map,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    Convert(Target),
+    Append(Vec<Value>),
+    ConvertSparse(SparseTarget),
+    AppendSparse(BTreeMap<u8, Value>),
+}
+
+enum Outcome<A> {
+    Converted(A),
+    /// The conversion was refused and handed back its input.
+    Refused(A),
+    /// There's no direct conversion to the target.
+    Unsupported(A),
+}
+
+use Outcome::*;
+
+fn refused<A, B>(
+    result: Result<A, B>,
+    wrap: impl FnOnce(A) -> Holder,
+    unwrap: impl FnOnce(B) -> Holder,
+) -> Outcome<Holder> {
+    match result {
+        Ok(value) => Converted(wrap(value)),
+        Err(value) => Refused(unwrap(value)),
+    }
+}
+
+fn capacity(target: Target) -> usize {
+    match target {
+        Target::SmallChunk | Target::SmallRing => SMALL,
+        Target::Chunk | Target::Ring => LARGE,
+        Target::Inline => Inline::CAPACITY,
+        Target::Vec | Target::Deque => usize::MAX,
+        Target::Array => SMALL,
+    }
+}
+
+fn convert(holder: Holder, target: Target) -> Outcome<Holder> {
+    use Holder as H;
+    match (holder, target) {
+        (H::SmallChunk(chunk), Target::Chunk) => {
+            refused(chunk.into_capacity(), H::Chunk, H::SmallChunk)
+        }
+        (H::Chunk(chunk), Target::SmallChunk) => {
+            refused(chunk.into_capacity(), H::SmallChunk, H::Chunk)
+        }
+        (H::SmallRing(buffer), Target::Ring) => {
+            refused(buffer.into_capacity(), H::Ring, H::SmallRing)
+        }
+        (H::Ring(buffer), Target::SmallRing) => {
+            refused(buffer.into_capacity(), H::SmallRing, H::Ring)
+        }
+        (H::SmallChunk(chunk), Target::SmallRing) => Converted(H::SmallRing(chunk.into())),
+        (H::SmallRing(buffer), Target::SmallChunk) => Converted(H::SmallChunk(buffer.into())),
+        (H::Chunk(chunk), Target::Ring) => Converted(H::Ring(chunk.into())),
+        (H::Ring(buffer), Target::Chunk) => Converted(H::Chunk(buffer.into())),
+        (H::Chunk(chunk), Target::Inline) => refused(Inline::try_from(chunk), H::Inline, H::Chunk),
+        (H::SmallChunk(chunk), Target::Inline) => {
+            refused(Inline::try_from(chunk), H::Inline, H::SmallChunk)
+        }
+        (H::Inline(array), Target::Chunk) => Converted(H::Chunk(array.into())),
+        (H::Inline(array), Target::SmallChunk) => Converted(H::SmallChunk(array.into())),
+        (H::Chunk(chunk), Target::Vec) => Converted(H::Vec(chunk.into())),
+        (H::Ring(buffer), Target::Vec) => Converted(H::Vec(buffer.into())),
+        (H::Ring(buffer), Target::Deque) => Converted(H::Deque(buffer.into())),
+        (H::Vec(vec), Target::Chunk) => refused(Chunk::try_from(vec), H::Chunk, H::Vec),
+        (H::Vec(vec), Target::Ring) => refused(RingBuffer::try_from(vec), H::Ring, H::Vec),
+        (H::Vec(vec), Target::SmallChunk) => match Chunk::try_from(vec.as_slice()) {
+            Ok(chunk) => Converted(H::SmallChunk(chunk)),
+            Err(_) => Refused(H::Vec(vec)),
+        },
+        (H::Vec(vec), Target::SmallRing) => match RingBuffer::try_from(vec.as_slice()) {
+            Ok(buffer) => Converted(H::SmallRing(buffer)),
+            Err(_) => Refused(H::Vec(vec)),
+        },
+        (H::Vec(vec), Target::Deque) => Converted(H::Deque(vec.into())),
+        (H::Deque(deque), Target::Vec) => Converted(H::Vec(deque.into())),
+        (H::Deque(deque), Target::Ring) => refused(RingBuffer::try_from(deque), H::Ring, H::Deque),
+        (H::Deque(deque), Target::SmallRing) => {
+            refused(RingBuffer::try_from(deque), H::SmallRing, H::Deque)
+        }
+        (H::SmallChunk(chunk), Target::Array) => {
+            refused(<[Value; SMALL]>::try_from(chunk), H::Array, H::SmallChunk)
+        }
+        (H::SmallRing(buffer), Target::Array) => {
+            refused(<[Value; SMALL]>::try_from(buffer), H::Array, H::SmallRing)
+        }
+        (H::Array(array), Target::SmallChunk) => Converted(H::SmallChunk(array.into())),
+        (H::Array(array), Target::Ring) => Converted(H::Ring(array.into())),
+        (H::Array(array), Target::Inline) => Converted(H::Inline(array.into())),
+        (holder, _) => Unsupported(holder),
+    }
+}
+
+fn sparse_convert(holder: SparseHolder, target: SparseTarget) -> Outcome<SparseHolder> {
+    use SparseHolder as H;
+    let result = match (holder, target) {
+        (H::Small(chunk), SparseTarget::Large) => {
+            chunk.into_capacity().map(H::Large).map_err(H::Small)
+        }
+        (H::Large(chunk), SparseTarget::Small) => {
+            chunk.into_capacity().map(H::Small).map_err(H::Large)
+        }
+        (H::Small(chunk), SparseTarget::Map) => Ok(H::Map(chunk.into())),
+        (H::Large(chunk), SparseTarget::Map) => Ok(H::Map(chunk.into())),
+        (H::Map(map), SparseTarget::Small) => {
+            SparseChunk::try_from(map).map(H::Small).map_err(H::Map)
+        }
+        (H::Map(map), SparseTarget::Large) => {
+            SparseChunk::try_from(map).map(H::Large).map_err(H::Map)
+        }
+        (holder, _) => return Unsupported(holder),
+    };
+    match result {
+        Ok(holder) => Converted(holder),
+        Err(holder) => Refused(holder),
+    }
+}
+
+fn values<'a, I: Iterator<Item = &'a Value>>(iter: I) -> Vec<u32> {
+    iter.map(|value| **value).collect()
+}
+
+impl Holder {
+    fn values(&self) -> Vec<u32> {
+        match self {
+            Holder::SmallChunk(chunk) => values(chunk.iter()),
+            Holder::Chunk(chunk) => values(chunk.iter()),
+            Holder::SmallRing(buffer) => values(buffer.iter()),
+            Holder::Ring(buffer) => values(buffer.iter()),
+            Holder::Inline(array) => values(array.iter()),
+            Holder::Vec(vec) => values(vec.iter()),
+            Holder::Deque(deque) => values(deque.iter()),
+            Holder::Array(array) => values(array.iter()),
+        }
+    }
+
+    /// Move `other` onto the end of the holder using `append_from` with a
+    /// container of a different capacity.
+    fn append(&mut self, other: Vec<Value>) {
+        match self {
+            Holder::SmallChunk(chunk) => {
+                chunk.append_from(&mut Chunk::<_, LARGE>::try_from(other).unwrap())
+            }
+            Holder::Chunk(chunk) => {
+                chunk.append_from(&mut Chunk::<_, SMALL>::try_from(other).unwrap())
+            }
+            Holder::SmallRing(buffer) => {
+                buffer.append_from(&mut RingBuffer::<_, LARGE>::try_from(other).unwrap())
+            }
+            Holder::Ring(buffer) => {
+                buffer.append_from(&mut RingBuffer::<_, SMALL>::try_from(other).unwrap())
+            }
+            Holder::Inline(array) => {
+                let mut other: InlineArray<_, [usize; LARGE + 1]> = other.into_iter().collect();
+                array.append_from(&mut other);
+            }
+            Holder::Vec(_) | Holder::Deque(_) | Holder::Array(_) => unreachable!(),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match self {
+            Holder::SmallChunk(_) => capacity(Target::SmallChunk),
+            Holder::Chunk(_) => capacity(Target::Chunk),
+            Holder::SmallRing(_) => capacity(Target::SmallRing),
+            Holder::Ring(_) => capacity(Target::Ring),
+            Holder::Inline(_) => capacity(Target::Inline),
+            Holder::Vec(_) => capacity(Target::Vec),
+            Holder::Deque(_) => capacity(Target::Deque),
+            Holder::Array(_) => capacity(Target::Array),
+        }
+    }
+}
+
+impl SparseHolder {
+    fn entries(&self) -> BTreeMap<usize, u32> {
+        match self {
+            SparseHolder::Small(chunk) => chunk
+                .entries()
+                .map(|(index, value)| (index, **value))
+                .collect(),
+            SparseHolder::Large(chunk) => chunk
+                .entries()
+                .map(|(index, value)| (index, **value))
+                .collect(),
+            SparseHolder::Map(map) => map.iter().map(|(index, value)| (*index, **value)).collect(),
+        }
+    }
+}
+
+fn sparse_capacity(target: SparseTarget) -> usize {
+    match target {
+        SparseTarget::Small => SMALL,
+        SparseTarget::Large => LARGE,
+        SparseTarget::Map => usize::MAX,
+    }
+}
+
+fn assert_outcome<A, F>(outcome: Outcome<A>, fits: bool, check: F) -> A
+where
+    F: FnOnce(&A),
+{
+    let holder = match outcome {
+        Converted(holder) => {
+            assert!(fits, "conversion should have been refused");
+            holder
+        }
+        Refused(holder) => {
+            assert!(!fits, "conversion shouldn't have been refused");
+            holder
+        }
+        Unsupported(holder) => holder,
+    };
+    check(&holder);
+    holder
+}
+
+fuzz_target!(|input: (Vec<Value>, BTreeMap<u8, Value>, Vec<Action>)| {
+    let (values, entries, actions) = input;
+    let mut guide: Vec<u32> = values.iter().map(|value| **value).collect();
+    let mut holder = Holder::Vec(values);
+    let mut sparse_guide: BTreeMap<usize, u32> = entries
+        .iter()
+        .map(|(index, value)| (*index as usize, **value))
+        .collect();
+    let mut sparse = SparseHolder::Map(
+        entries
+            .into_iter()
+            .map(|(index, value)| (index as usize, value))
+            .collect(),
+    );
+    for action in actions {
+        match action {
+            Action::Convert(target) => {
+                let fits = match target {
+                    Target::Array => guide.len() == SMALL,
+                    _ => guide.len() <= capacity(target),
+                };
+                holder = assert_outcome(convert(holder, target), fits, |holder| {
+                    assert_eq!(guide, holder.values());
+                });
+            }
+            Action::Append(other) => {
+                if let Holder::Vec(_) | Holder::Deque(_) | Holder::Array(_) = holder {
+                    continue;
+                }
+                // Keep the values that fit in the temporary container.
+                let other: Vec<_> = other.into_iter().take(SMALL).collect();
+                let expected: Vec<u32> = other.iter().map(|value| **value).collect();
+                if guide.len() + other.len() <= holder.capacity() {
+                    holder.append(other);
+                    guide.extend(expected);
+                } else {
+                    assert_panic(|| holder.append(other));
+                }
+                assert_eq!(guide, holder.values());
+            }
+            Action::ConvertSparse(target) => {
+                let fits = sparse_guide
+                    .keys()
+                    .next_back()
+                    .map_or(true, |&index| index < sparse_capacity(target));
+                sparse = assert_outcome(sparse_convert(sparse, target), fits, |sparse| {
+                    assert_eq!(sparse_guide, sparse.entries());
+                });
+            }
+            Action::AppendSparse(other) => {
+                let mut other: SparseChunk<Value, 256> = other
+                    .into_iter()
+                    .map(|(index, value)| (index as usize, value))
+                    .collect::<BTreeMap<_, _>>()
+                    .try_into()
+                    .unwrap();
+                let expected: BTreeMap<usize, u32> = other
+                    .entries()
+                    .map(|(index, value)| (index, **value))
+                    .collect();
+                let fits = |capacity: usize| {
+                    expected
+                        .keys()
+                        .next_back()
+                        .map_or(true, |&index| index < capacity)
+                };
+                match &mut sparse {
+                    SparseHolder::Small(chunk) if fits(SMALL) => {
+                        chunk.append_from(&mut other);
+                        sparse_guide.extend(expected);
+                    }
+                    SparseHolder::Large(chunk) if fits(LARGE) => {
+                        chunk.append_from(&mut other);
+                        sparse_guide.extend(expected);
+                    }
+                    SparseHolder::Small(chunk) => assert_panic(|| chunk.append_from(&mut other)),
+                    SparseHolder::Large(chunk) => assert_panic(|| chunk.append_from(&mut other)),
+                    SparseHolder::Map(_) => {}
+                }
+                assert_eq!(sparse_guide, sparse.entries());
+            }
+        }
+    }
+});
@@ -0,0 +1,129 @@
+#![no_main]
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use sized_chunks::{Chunk, InlineArray, RingBuffer};
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    Write(Vec<u8>),
+    WriteAll(Vec<u8>),
+    WriteStr(String),
+    Read(u8),
+    ReadExact(u8),
+    ReadToEnd,
+    Flush,
+}
+
+const CAPACITY: usize = 64;
+
+// One extra word to hold the array's length.
+type Array = InlineArray<u8, [u64; CAPACITY / 8 + 1]>;
+
+fn pop_front(array: &mut Array, count: usize) {
+    for _ in 0..count {
+        array.remove(0);
+    }
+}
+
+fuzz_target!(|input: (Chunk<u8, CAPACITY>, Vec<Action>)| {
+    assert_eq!(CAPACITY, Array::CAPACITY);
+    let (mut chunk, actions) = input;
+    let mut buffer: RingBuffer<u8, CAPACITY> = chunk.iter().cloned().collect();
+    let mut array: Array = chunk.iter().cloned().collect();
+    let mut guide: VecDeque<_> = chunk.iter().cloned().collect();
+    for action in actions {
+        match action {
+            Action::Write(bytes) => {
+                let count = bytes.len().min(CAPACITY - guide.len());
+                guide.extend(&bytes[..count]);
+                assert_eq!(count, chunk.write(&bytes).unwrap());
+                assert_eq!(count, buffer.write(&bytes).unwrap());
+                // `InlineArray` only has `fmt::Write`, so feed it the same bytes.
+                array.extend(bytes[..count].iter().cloned());
+            }
+            Action::WriteAll(bytes) => {
+                if bytes.len() <= CAPACITY - guide.len() {
+                    guide.extend(&bytes);
+                    array.extend(bytes.iter().cloned());
+                    chunk.write_all(&bytes).unwrap();
+                    buffer.write_all(&bytes).unwrap();
+                } else {
+                    // `write_all` fails with `WriteZero` once the container is
+                    // full, having written as much as it could.
+                    let count = CAPACITY - guide.len();
+                    guide.extend(&bytes[..count]);
+                    array.extend(bytes[..count].iter().cloned());
+                    let kind = std::io::ErrorKind::WriteZero;
+                    assert_eq!(kind, chunk.write_all(&bytes).unwrap_err().kind());
+                    assert_eq!(kind, buffer.write_all(&bytes).unwrap_err().kind());
+                }
+            }
+            Action::WriteStr(string) => {
+                // `fmt::Write` is all or nothing.
+                if string.len() <= CAPACITY - guide.len() {
+                    guide.extend(string.as_bytes());
+                    chunk.write_str(&string).unwrap();
+                    buffer.write_str(&string).unwrap();
+                    array.write_str(&string).unwrap();
+                } else {
+                    assert!(chunk.write_str(&string).is_err());
+                    assert!(buffer.write_str(&string).is_err());
+                    assert!(array.write_str(&string).is_err());
+                }
+            }
+            Action::Read(size) => {
+                let count = (size as usize).min(guide.len());
+                let expected: Vec<_> = guide.drain(..count).collect();
+                let mut read = vec![0; size as usize];
+                assert_eq!(count, chunk.read(&mut read).unwrap());
+                assert_eq!(expected, read[..count]);
+                let mut read = vec![0; size as usize];
+                assert_eq!(count, buffer.read(&mut read).unwrap());
+                assert_eq!(expected, read[..count]);
+                pop_front(&mut array, count);
+            }
+            Action::ReadExact(size) => {
+                let size = size as usize;
+                let mut read = vec![0; size];
+                if size <= guide.len() {
+                    let expected: Vec<_> = guide.drain(..size).collect();
+                    chunk.read_exact(&mut read).unwrap();
+                    assert_eq!(expected, read);
+                    buffer.read_exact(&mut read).unwrap();
+                    assert_eq!(expected, read);
+                    pop_front(&mut array, size);
+                } else {
+                    // `read_exact` consumes what's there before giving up.
+                    guide.clear();
+                    array.clear();
+                    let kind = std::io::ErrorKind::UnexpectedEof;
+                    assert_eq!(kind, chunk.read_exact(&mut read).unwrap_err().kind());
+                    assert_eq!(kind, buffer.read_exact(&mut read).unwrap_err().kind());
+                }
+            }
+            Action::ReadToEnd => {
+                let expected: Vec<_> = guide.drain(..).collect();
+                let mut read = Vec::new();
+                assert_eq!(expected.len(), chunk.read_to_end(&mut read).unwrap());
+                assert_eq!(expected, read);
+                read.clear();
+                assert_eq!(expected.len(), buffer.read_to_end(&mut read).unwrap());
+                assert_eq!(expected, read);
+                array.clear();
+            }
+            Action::Flush => {
+                chunk.flush().unwrap();
+                buffer.flush().unwrap();
+            }
+        }
+        assert!(chunk.iter().eq(guide.iter()));
+        assert!(buffer.iter().eq(guide.iter()));
+        assert!(array.iter().eq(guide.iter()));
+    }
+});
@@ -0,0 +1,223 @@
+#![no_main]
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Debug;
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use refpool::{Pool, PoolClone, PoolDefault, PoolRef};
+
+use sized_chunks::{Chunk, RingBuffer, SparseChunk};
+
+// Boxed, so that a value being dropped twice or not at all doesn't go unnoticed.
+type Value = Box<u32>;
+
+const CAPACITY: usize = 16;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    PushFront(u32),
+    PushBack(u32),
+    PopFront,
+    PopBack,
+    Insert(u8, u32),
+    Remove(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+enum Action {
+    Default,
+    /// Share an existing container.
+    Share(u8),
+    /// Deep clone an existing container into the pool.
+    Cloned(u8),
+    CloneFrom(u8),
+    /// Modify a container, cloning it first if it's shared.
+    MakeMut(u8, Op),
+    UnwrapOrClone(u8),
+    Drop(u8),
+}
+
+/// A pooled container type along with a model of its contents.
+trait Subject: PoolClone + PoolDefault {
+    type Model: Clone + Debug + Default + PartialEq;
+
+    fn model(&self) -> Self::Model;
+    fn apply(&mut self, model: &mut Self::Model, op: &Op);
+}
+
+impl Subject for Chunk<Value, CAPACITY> {
+    type Model = VecDeque<u32>;
+
+    fn model(&self) -> Self::Model {
+        self.iter().map(|value| **value).collect()
+    }
+
+    fn apply(&mut self, model: &mut Self::Model, op: &Op) {
+        match *op {
+            Op::PushFront(value) if !self.is_full() => {
+                model.push_front(value);
+                self.push_front(Box::new(value));
+            }
+            Op::PushBack(value) if !self.is_full() => {
+                model.push_back(value);
+                self.push_back(Box::new(value));
+            }
+            Op::PopFront if !self.is_empty() => {
+                assert_eq!(model.pop_front(), Some(*self.pop_front()));
+            }
+            Op::PopBack if !self.is_empty() => {
+                assert_eq!(model.pop_back(), Some(*self.pop_back()));
+            }
+            Op::Insert(index, value) if !self.is_full() && index as usize <= self.len() => {
+                model.insert(index as usize, value);
+                self.insert(index as usize, Box::new(value));
+            }
+            Op::Remove(index) if (index as usize) < self.len() => {
+                assert_eq!(
+                    model.remove(index as usize),
+                    Some(*self.remove(index as usize))
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Subject for RingBuffer<Value, CAPACITY> {
+    type Model = VecDeque<u32>;
+
+    fn model(&self) -> Self::Model {
+        self.iter().map(|value| **value).collect()
+    }
+
+    fn apply(&mut self, model: &mut Self::Model, op: &Op) {
+        match *op {
+            Op::PushFront(value) if !self.is_full() => {
+                model.push_front(value);
+                self.push_front(Box::new(value));
+            }
+            Op::PushBack(value) if !self.is_full() => {
+                model.push_back(value);
+                self.push_back(Box::new(value));
+            }
+            Op::PopFront => assert_eq!(model.pop_front(), self.pop_front().map(|value| *value)),
+            Op::PopBack => assert_eq!(model.pop_back(), self.pop_back().map(|value| *value)),
+            Op::Insert(index, value) if !self.is_full() && index as usize <= model.len() => {
+                model.insert(index as usize, value);
+                self.insert(index as usize, Box::new(value));
+            }
+            Op::Remove(index) if (index as usize) < model.len() => {
+                assert_eq!(
+                    model.remove(index as usize),
+                    Some(*self.remove(index as usize))
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Subject for SparseChunk<Value, CAPACITY> {
+    type Model = BTreeMap<usize, u32>;
+
+    fn model(&self) -> Self::Model {
+        self.entries()
+            .map(|(index, value)| (index, **value))
+            .collect()
+    }
+
+    fn apply(&mut self, model: &mut Self::Model, op: &Op) {
+        // There are no ends to a sparse chunk, so pushing and popping work on
+        // the first and last free and occupied indices.
+        match *op {
+            Op::PushFront(value) | Op::PushBack(value) => {
+                let mut free = (0..CAPACITY).filter(|index| self.get(*index).is_none());
+                let index = match op {
+                    Op::PushFront(_) => free.next(),
+                    _ => free.next_back(),
+                };
+                if let Some(index) = index {
+                    assert_eq!(None, model.insert(index, value));
+                    assert!(self.insert(index, Box::new(value)).is_none());
+                }
+            }
+            Op::PopFront => {
+                if let Some(index) = self.first_index() {
+                    assert_eq!(model.remove(&index), self.remove(index).map(|value| *value));
+                }
+            }
+            Op::PopBack => {
+                if let Some(index) = self.indices().last() {
+                    assert_eq!(model.remove(&index), self.remove(index).map(|value| *value));
+                }
+            }
+            Op::Insert(index, value) => {
+                let index = index as usize % CAPACITY;
+                let old = self.insert(index, Box::new(value)).map(|value| *value);
+                assert_eq!(model.insert(index, value), old);
+            }
+            Op::Remove(index) => {
+                let index = index as usize % CAPACITY;
+                assert_eq!(model.remove(&index), self.remove(index).map(|value| *value));
+            }
+        }
+    }
+}
+
+fn run<A: Subject>(actions: &[Action]) {
+    // Keep the pool small, so that some allocations come from the pool and
+    // some don't.
+    let pool: Pool<A> = Pool::new(4);
+    let mut refs: Vec<(PoolRef<A>, A::Model)> = Vec::new();
+    for action in actions {
+        let pick = |index: u8, len: usize| index as usize % len.max(1);
+        match *action {
+            Action::Default => refs.push((PoolRef::default(&pool), A::Model::default())),
+            _ if refs.is_empty() => continue,
+            Action::Share(index) => {
+                let (this, model) = &refs[pick(index, refs.len())];
+                let (shared, model) = (this.clone(), model.clone());
+                refs.push((shared, model));
+            }
+            Action::Cloned(index) => {
+                let (this, model) = &refs[pick(index, refs.len())];
+                let clone = PoolRef::cloned(&pool, this);
+                assert!(!PoolRef::ptr_eq(this, &clone));
+                let model = model.clone();
+                refs.push((clone, model));
+            }
+            Action::CloneFrom(index) => {
+                let (this, model) = &refs[pick(index, refs.len())];
+                let clone = PoolRef::clone_from(&pool, &**this);
+                assert!(!PoolRef::ptr_eq(this, &clone));
+                let model = model.clone();
+                refs.push((clone, model));
+            }
+            Action::MakeMut(index, ref op) => {
+                let index = pick(index, refs.len());
+                let (this, model) = &mut refs[index];
+                PoolRef::make_mut(&pool, this).apply(model, op);
+                assert_eq!(1, PoolRef::strong_count(this));
+            }
+            Action::UnwrapOrClone(index) => {
+                let (this, model) = refs.swap_remove(pick(index, refs.len()));
+                let value = PoolRef::unwrap_or_clone(this);
+                assert_eq!(model, value.model());
+                refs.push((PoolRef::new(&pool, value), model));
+            }
+            Action::Drop(index) => {
+                refs.swap_remove(pick(index, refs.len()));
+            }
+        }
+        for (this, model) in &refs {
+            assert_eq!(*model, this.model());
+        }
+    }
+}
+
+fuzz_target!(|actions: Vec<Action>| {
+    run::<Chunk<Value, CAPACITY>>(&actions);
+    run::<RingBuffer<Value, CAPACITY>>(&actions);
+    run::<SparseChunk<Value, CAPACITY>>(&actions);
+});
@@ -0,0 +1,278 @@
+#![no_main]
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::ops::Range;
+
+use arbitrary::Arbitrary;
+use array_ops::{Array, ArrayMut, HasLength};
+use libfuzzer_sys::fuzz_target;
+
+use sized_chunks::ring_buffer::{RingBuffer, Slice, SliceMut};
+
+mod assert;
+use assert::assert_panic;
+
+/// A chain of subslicing operations, applied one after the other to a slice
+/// of the whole buffer.
+#[derive(Arbitrary, Debug)]
+enum Step {
+    Slice(u8, u8),
+    SplitLeft(u8),
+    SplitRight(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+enum Read {
+    Get(u8),
+    First,
+    Last,
+    Contains(u32),
+    BinarySearch(u32),
+    StartsWith(Vec<u32>),
+    EndsWith(Vec<u32>),
+    ToOwned,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Write<A> {
+    Set(u8, A),
+    IndexMut(u8, A),
+    FirstMut(A),
+    LastMut(A),
+    Swap(u8, u8),
+    MapPair(u8, u8),
+    // No `sort_unstable` here: `array_ops`'s quicksort can step outside the
+    // array it's sorting, which is its bug rather than ours.
+    IterMut,
+}
+
+#[derive(Arbitrary, Debug)]
+enum Action<A> {
+    Read(Vec<Step>, Read),
+    Write(Vec<Step>, Write<A>),
+    /// Mutate the buffer between slicing actions, to move the origin around.
+    PushFront(A),
+    PushBack(A),
+    PopFront,
+    PopBack,
+}
+
+/// Apply a step to a range of the model, or return `None` if it should panic.
+fn step_range(range: &Range<usize>, step: &Step) -> Option<Range<usize>> {
+    let len = range.end - range.start;
+    match *step {
+        Step::Slice(start, end) => {
+            let (start, end) = (start as usize, end as usize);
+            if start > end || end > len {
+                None
+            } else {
+                Some(range.start + start..range.start + end)
+            }
+        }
+        Step::SplitLeft(index) if index as usize <= len => {
+            Some(range.start..range.start + index as usize)
+        }
+        Step::SplitRight(index) if index as usize <= len => {
+            Some(range.start + index as usize..range.end)
+        }
+        _ => None,
+    }
+}
+
+fn step_slice<'a, A, const N: usize>(slice: Slice<'a, A, N>, step: &Step) -> Slice<'a, A, N> {
+    match *step {
+        Step::Slice(start, end) => slice.slice(start as usize..end as usize),
+        Step::SplitLeft(index) => slice.split_at(index as usize).0,
+        Step::SplitRight(index) => slice.split_at(index as usize).1,
+    }
+}
+
+fn step_slice_mut<'a, A, const N: usize>(
+    slice: SliceMut<'a, A, N>,
+    step: &Step,
+) -> SliceMut<'a, A, N> {
+    match *step {
+        Step::Slice(start, end) => slice.slice(start as usize..end as usize),
+        Step::SplitLeft(index) => slice.split_at(index as usize).0,
+        Step::SplitRight(index) => slice.split_at(index as usize).1,
+    }
+}
+
+/// Work out which range of the model a chain of steps ends up at. If one of
+/// the steps should panic, check that it does, and return `None`.
+fn resolve<A, const N: usize>(
+    buffer: &mut RingBuffer<A, N>,
+    steps: &[Step],
+) -> Option<Range<usize>> {
+    let mut range = 0..buffer.len();
+    for (index, step) in steps.iter().enumerate() {
+        match step_range(&range, step) {
+            Some(next) => range = next,
+            None => {
+                let steps = &steps[..=index];
+                assert_panic(|| steps.iter().fold(buffer.slice(..), step_slice));
+                assert_panic(|| steps.iter().fold(buffer.slice_mut(..), step_slice_mut));
+                return None;
+            }
+        }
+    }
+    Some(range)
+}
+
+fn assert_slice_eq<A, S>(slice: &S, model: &[A])
+where
+    A: Debug + PartialEq,
+    S: Array<Output = A>,
+{
+    assert_eq!(model.len(), slice.len());
+    for (index, value) in model.iter().enumerate() {
+        assert_eq!(Some(value), slice.get(index));
+    }
+    assert_eq!(None, slice.get(model.len()));
+}
+
+fuzz_target!(|input: (RingBuffer<u32, 64>, Vec<Action<u32>>)| {
+    let (mut buffer, actions) = input;
+    let mut guide: VecDeque<_> = buffer.iter().cloned().collect();
+    for action in actions {
+        match action {
+            Action::Read(steps, read) => {
+                let range = match resolve(&mut buffer, &steps) {
+                    Some(range) => range,
+                    None => continue,
+                };
+                let slice = steps.iter().fold(buffer.slice(..), step_slice);
+                let model: Vec<_> = guide.range(range).cloned().collect();
+                assert_slice_eq(&slice, &model);
+                assert!(slice.iter().eq(model.iter()));
+                match read {
+                    Read::Get(index) => {
+                        assert_eq!(model.get(index as usize), slice.get(index as usize));
+                    }
+                    Read::First => assert_eq!(model.first(), slice.first()),
+                    Read::Last => assert_eq!(model.last(), slice.last()),
+                    Read::Contains(value) => {
+                        assert_eq!(model.contains(&value), slice.contains(&value));
+                    }
+                    Read::BinarySearch(value) => {
+                        let mut sorted = model.clone();
+                        sorted.sort_unstable();
+                        if sorted == model {
+                            // Either result is fine for duplicate values, so
+                            // check that it points at an equal value instead.
+                            match (sorted.binary_search(&value), slice.binary_search(&value)) {
+                                (Ok(_), Ok(index)) => assert_eq!(value, model[index]),
+                                (Err(expected), Err(index)) => assert_eq!(expected, index),
+                                (expected, result) => panic!("{:?} != {:?}", expected, result),
+                            }
+                        }
+                    }
+                    Read::StartsWith(prefix) => {
+                        assert_eq!(model.starts_with(&prefix), slice.starts_with(&prefix));
+                    }
+                    Read::EndsWith(suffix) => {
+                        assert_eq!(model.ends_with(&suffix), slice.ends_with(&suffix));
+                    }
+                    Read::ToOwned => {
+                        let owned = slice.to_owned();
+                        assert!(owned.iter().eq(model.iter()));
+                    }
+                }
+            }
+            Action::Write(steps, write) => {
+                let range = match resolve(&mut buffer, &steps) {
+                    Some(range) => range,
+                    None => continue,
+                };
+                let mut slice = steps.iter().fold(buffer.slice_mut(..), step_slice_mut);
+                let start = range.start;
+                let model = &mut guide.make_contiguous()[range];
+                assert_slice_eq(&slice, model);
+                match write {
+                    Write::Set(index, value) => {
+                        let index = index as usize;
+                        let expected = model.get_mut(index).map(|p| std::mem::replace(p, value));
+                        assert_eq!(expected, slice.set(index, value));
+                    }
+                    Write::IndexMut(index, value) => {
+                        let index = index as usize;
+                        if index < model.len() {
+                            model[index] = value;
+                            slice[index] = value;
+                        } else {
+                            assert_panic(|| slice[index] = value);
+                        }
+                    }
+                    Write::FirstMut(value) => {
+                        if let Some(first) = model.first_mut() {
+                            *first = value;
+                        }
+                        if let Some(first) = slice.first_mut() {
+                            *first = value;
+                        }
+                    }
+                    Write::LastMut(value) => {
+                        if let Some(last) = model.last_mut() {
+                            *last = value;
+                        }
+                        if let Some(last) = slice.last_mut() {
+                            *last = value;
+                        }
+                    }
+                    Write::Swap(left, right) => {
+                        let (left, right) = (left as usize, right as usize);
+                        if left == right {
+                            // `array_ops` doesn't check the index when swapping
+                            // an element with itself, so this is always a no-op.
+                            slice.swap(left, right);
+                        } else if left < model.len() && right < model.len() {
+                            model.swap(left, right);
+                            slice.swap(left, right);
+                        } else {
+                            assert_panic(|| slice.swap(left, right));
+                        }
+                    }
+                    Write::MapPair(left, right) => {
+                        let (left, right) = (left as usize, right as usize);
+                        if left != right && left < model.len() && right < model.len() {
+                            let sum = model[left].wrapping_add(model[right]);
+                            model[left] = sum;
+                            model[right] = sum;
+                            slice.map_pair(left, right, |left, right| {
+                                *left = left.wrapping_add(*right);
+                                *right = *left;
+                            });
+                        } else {
+                            assert_panic(|| slice.map_pair(left, right, |_, _| ()));
+                        }
+                    }
+                    Write::IterMut => {
+                        for (index, value) in model.iter_mut().enumerate() {
+                            *value = value.wrapping_add((start + index) as u32);
+                        }
+                        for (index, value) in slice.iter_mut().enumerate() {
+                            *value = value.wrapping_add((start + index) as u32);
+                        }
+                    }
+                }
+                assert_slice_eq(&slice, model);
+            }
+            Action::PushFront(value) => {
+                if !buffer.is_full() {
+                    buffer.push_front(value);
+                    guide.push_front(value);
+                }
+            }
+            Action::PushBack(value) => {
+                if !buffer.is_full() {
+                    buffer.push_back(value);
+                    guide.push_back(value);
+                }
+            }
+            Action::PopFront => assert_eq!(guide.pop_front(), buffer.pop_front()),
+            Action::PopBack => assert_eq!(guide.pop_back(), buffer.pop_back()),
+        }
+        assert!(buffer.iter().eq(guide.iter()));
+    }
+});
@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use bitmaps::{Bits, BitsImpl};
+use bitmaps::{Bitmap, Bits, BitsImpl};
 
 use ::arbitrary::{size_hint, Arbitrary, Result, Unstructured};
 
@@ -11,23 +11,59 @@ use crate::{Chunk, InlineArray, SparseChunk};
 #[cfg(feature = "ringbuffer")]
 use crate::RingBuffer;
 
+/// Move a chunk's contents `offset` slots away from the front of its storage,
+/// wrapped to fit the free space, so that fuzzers get to see chunks which
+/// don't start at index 0.
+fn offset_chunk<A, const N: usize>(mut chunk: Chunk<A, N>, offset: usize) -> Chunk<A, N> {
+    chunk.reserve_front(offset % (N - chunk.len() + 1));
+    chunk
+}
+
+/// Take one bit per index from the input to decide which indices of a sparse
+/// chunk hold values, so that the input controls how sparse the chunk is.
+///
+/// If the input runs out, the remaining indices are left empty.
+fn arbitrary_bitmap<const N: usize>(u: &mut Unstructured<'_>) -> Result<Bitmap<N>>
+where
+    BitsImpl<N>: Bits,
+{
+    let bytes = u.bytes(((N + 7) / 8).min(u.len()))?;
+    let mut bitmap = Bitmap::new();
+    for index in 0..N.min(bytes.len() * 8) {
+        if bytes[index / 8] & (1 << (index % 8)) != 0 {
+            bitmap.set(index, true);
+        }
+    }
+    Ok(bitmap)
+}
+
 impl<'a, A, const N: usize> Arbitrary<'a> for Chunk<A, N>
 where
     A: Arbitrary<'a>,
     BitsImpl<N>: Bits,
 {
     fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
-        u.arbitrary_iter()?.take(Self::CAPACITY).collect()
+        let offset = u.arbitrary()?;
+        let chunk = u.arbitrary_iter()?.take(Self::CAPACITY).collect::<Result<_>>()?;
+        Ok(offset_chunk(chunk, offset))
     }
 
-    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
-        u.arbitrary_take_rest_iter()?.take(Self::CAPACITY).collect()
+    fn arbitrary_take_rest(mut u: Unstructured<'a>) -> Result<Self> {
+        let offset = u.arbitrary()?;
+        let chunk = u
+            .arbitrary_take_rest_iter()?
+            .take(Self::CAPACITY)
+            .collect::<Result<_>>()?;
+        Ok(offset_chunk(chunk, offset))
     }
 
     fn size_hint(depth: usize) -> (usize, Option<usize>) {
         size_hint::recursion_guard(depth, |depth| {
             let (_, upper) = A::size_hint(depth);
-            (0, upper.map(|upper| upper * Self::CAPACITY))
+            size_hint::and(
+                usize::size_hint(depth),
+                (0, upper.map(|upper| upper * Self::CAPACITY)),
+            )
         })
     }
 }
@@ -39,39 +75,49 @@ where
     BitsImpl<N>: Bits,
 {
     fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
-        u.arbitrary_iter()?.take(Self::CAPACITY).collect()
+        let mut buffer = Self::with_origin(u.arbitrary()?);
+        for value in u.arbitrary_iter()?.take(Self::CAPACITY) {
+            buffer.push_back(value?);
+        }
+        Ok(buffer)
     }
 
-    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
-        u.arbitrary_take_rest_iter()?.take(Self::CAPACITY).collect()
+    fn arbitrary_take_rest(mut u: Unstructured<'a>) -> Result<Self> {
+        let mut buffer = Self::with_origin(u.arbitrary()?);
+        for value in u.arbitrary_take_rest_iter()?.take(Self::CAPACITY) {
+            buffer.push_back(value?);
+        }
+        Ok(buffer)
     }
 
     fn size_hint(depth: usize) -> (usize, Option<usize>) {
         size_hint::recursion_guard(depth, |depth| {
             let (_, upper) = A::size_hint(depth);
-            (0, upper.map(|upper| upper * Self::CAPACITY))
+            size_hint::and(
+                usize::size_hint(depth),
+                (0, upper.map(|upper| upper * Self::CAPACITY)),
+            )
         })
     }
 }
 
 impl<'a, A, const N: usize> Arbitrary<'a> for SparseChunk<A, N>
 where
-    A: Clone,
-    Option<A>: Arbitrary<'a>,
+    A: Arbitrary<'a>,
     BitsImpl<N>: Bits,
 {
     fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
-        u.arbitrary_iter()?.take(Self::CAPACITY).collect()
-    }
-
-    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
-        u.arbitrary_take_rest_iter()?.take(Self::CAPACITY).collect()
+        let mut chunk = Self::new();
+        for index in &arbitrary_bitmap::<N>(u)? {
+            chunk.insert(index, u.arbitrary()?);
+        }
+        Ok(chunk)
     }
 
     fn size_hint(depth: usize) -> (usize, Option<usize>) {
         size_hint::recursion_guard(depth, |depth| {
-            let (_, upper) = Option::<A>::size_hint(depth);
-            (0, upper.map(|upper| upper * Self::CAPACITY))
+            let (_, upper) = A::size_hint(depth);
+            (0, upper.map(|upper| (N + 7) / 8 + upper * Self::CAPACITY))
         })
     }
 }
@@ -96,3 +142,59 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Some deterministic noise to generate values from.
+    fn noise(seed: u64, len: usize) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chunk_layouts() {
+        let mut offset = false;
+        for seed in 0..64 {
+            let data = noise(seed, 64);
+            let chunk = Chunk::<u8, 16>::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            chunk.check_invariants();
+            offset |= chunk.front_capacity() > 0;
+        }
+        assert!(offset);
+    }
+
+    #[cfg(feature = "ringbuffer")]
+    #[test]
+    fn ring_buffer_layouts() {
+        let mut wrapped = false;
+        for seed in 0..64 {
+            let data = noise(seed, 64);
+            let mut buffer = RingBuffer::<u8, 16>::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            buffer.check_invariants();
+            // With the origin at 0, the free space would never wrap around.
+            wrapped |= !buffer.is_full() && !buffer.spare_capacity_mut().1.is_empty();
+        }
+        assert!(wrapped);
+    }
+
+    #[test]
+    fn sparse_chunk_density() {
+        let empty = SparseChunk::<u8, 16>::arbitrary(&mut Unstructured::new(&[0; 32])).unwrap();
+        assert!(empty.is_empty());
+        let full = SparseChunk::<u8, 16>::arbitrary(&mut Unstructured::new(&[0xff; 32])).unwrap();
+        assert!(full.is_full());
+        let data = [0b1000_0001, 0b0100_0000, 1, 2, 3];
+        let chunk = SparseChunk::<u8, 16>::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert_eq!(vec![0, 7, 14], chunk.indices().collect::<Vec<_>>());
+        assert_eq!(vec![1, 2, 3], chunk.iter().cloned().collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,47 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use core::iter::FusedIterator;
+
+use super::ChunkHeap;
+
+/// A draining iterator over a `ChunkHeap`, which yields its items from the
+/// greatest to the least.
+///
+/// Whatever is left in the heap when the iterator is dropped gets discarded,
+/// so the heap is always empty afterwards.
+pub struct DrainSorted<'a, A, const N: usize>
+where
+    A: Ord,
+{
+    pub(crate) heap: &'a mut ChunkHeap<A, N>,
+}
+
+impl<'a, A, const N: usize> Iterator for DrainSorted<'a, A, N>
+where
+    A: Ord,
+{
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<'a, A, const N: usize> ExactSizeIterator for DrainSorted<'a, A, N> where A: Ord {}
+
+impl<'a, A, const N: usize> FusedIterator for DrainSorted<'a, A, N> where A: Ord {}
+
+impl<'a, A, const N: usize> Drop for DrainSorted<'a, A, N>
+where
+    A: Ord,
+{
+    fn drop(&mut self) {
+        self.heap.clear();
+    }
+}
@@ -0,0 +1,577 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A fixed capacity binary heap.
+//!
+//! See [`ChunkHeap`](struct.ChunkHeap.html)
+
+use core::fmt::{Debug, Error, Formatter};
+use core::iter::FromIterator;
+use core::mem::{forget, replace};
+use core::ops::{Deref, DerefMut};
+use core::slice::Iter as SliceIter;
+
+use crate::sized_chunk::{Chunk, Iter as ChunkIter};
+
+mod iter;
+pub use self::iter::DrainSorted;
+
+/// A fixed capacity binary heap.
+///
+/// A priority queue stored inline in a [`Chunk`][Chunk], which works like
+/// `std::collections::BinaryHeap`: [`pop`][pop] always gives you the greatest
+/// item in the heap. If you need the least item instead, wrap your items in
+/// [`core::cmp::Reverse`][Reverse].
+///
+/// Like the rest of the crate, it never allocates, and it panics rather than
+/// grows when you push more than it can hold. Use [`try_push`][try_push] if
+/// you'd rather be told, or [`push_bounded`][push_bounded] if you'd rather keep
+/// only the `N` greatest items you've pushed.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sized_chunks::ChunkHeap;
+/// let mut heap = ChunkHeap::<i32, 4>::new();
+/// for value in &[5, 1, 8, 3, 9, 2] {
+///     heap.push_bounded(*value);
+/// }
+/// // Only the four greatest values are left.
+/// assert_eq!(Some(&9), heap.peek());
+/// assert_eq!(&[3, 5, 8, 9], heap.into_sorted_chunk().as_slice());
+/// ```
+///
+/// [Chunk]: ../sized_chunk/type.Chunk.html
+/// [pop]: #method.pop
+/// [try_push]: #method.try_push
+/// [push_bounded]: #method.push_bounded
+/// [Reverse]: https://doc.rust-lang.org/core/cmp/struct.Reverse.html
+pub struct ChunkHeap<A, const N: usize> {
+    chunk: Chunk<A, N>,
+}
+
+impl<A, const N: usize> ChunkHeap<A, N> {
+    /// The maximum number of items this `ChunkHeap` can contain.
+    pub const CAPACITY: usize = N;
+
+    /// Construct a new empty heap.
+    pub fn new() -> Self {
+        ChunkHeap {
+            chunk: Chunk::new(),
+        }
+    }
+
+    /// Get the length of the heap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.chunk.len()
+    }
+
+    /// Test if the heap is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunk.is_empty()
+    }
+
+    /// Test if the heap is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.chunk.is_full()
+    }
+
+    /// Get the items in the heap as a slice, in no particular order.
+    #[inline]
+    pub fn as_slice(&self) -> &[A] {
+        self.chunk.as_slice()
+    }
+
+    /// Get an iterator over the items in the heap, in no particular order.
+    pub fn iter(&self) -> SliceIter<'_, A> {
+        self.chunk.iter()
+    }
+
+    /// Turn the heap into the `Chunk` it's stored in, with its items in no
+    /// particular order.
+    pub fn into_chunk(self) -> Chunk<A, N> {
+        self.chunk
+    }
+
+    /// Discard the contents of the heap.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        self.chunk.clear();
+    }
+}
+
+impl<A, const N: usize> ChunkHeap<A, N>
+where
+    A: Ord,
+{
+    /// Check the heap's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of heaps.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the heap.
+    ///
+    /// Time: O(n)
+    pub fn check_invariants(&self) {
+        self.chunk.check_invariants();
+        for index in 1..self.len() {
+            assert!(
+                self.chunk[(index - 1) / 2] >= self.chunk[index],
+                "ChunkHeap::check_invariants: item {} is greater than its parent",
+                index
+            );
+        }
+    }
+
+    /// Get a reference to the greatest item in the heap.
+    ///
+    /// Time: O(1)
+    pub fn peek(&self) -> Option<&A> {
+        self.chunk.first()
+    }
+
+    /// Get a mutable reference to the greatest item in the heap.
+    ///
+    /// The item is moved to its proper place in the heap when the
+    /// [`PeekMut`][PeekMut] is dropped, so you can change it in ways which
+    /// change its ordering.
+    ///
+    /// Time: O(1), plus O(log n) when the `PeekMut` is dropped
+    ///
+    /// [PeekMut]: struct.PeekMut.html
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, A, N>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut { heap: self })
+        }
+    }
+
+    /// Push an item onto the heap.
+    ///
+    /// Panics if the heap is full.
+    ///
+    /// Time: O(log n)
+    pub fn push(&mut self, value: A) {
+        if self.try_push(value).is_err() {
+            panic!("ChunkHeap::push: heap is full");
+        }
+    }
+
+    /// Push an item onto the heap.
+    ///
+    /// If the heap is full, you get the item back as the error.
+    ///
+    /// Time: O(log n)
+    pub fn try_push(&mut self, value: A) -> Result<(), A> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.chunk.push_back(value);
+        let index = self.len() - 1;
+        sift_up(&mut self.chunk, index);
+        debug_invariants!(self);
+        Ok(())
+    }
+
+    /// Push an item onto the heap, making room for it by discarding the least
+    /// item if the heap is full.
+    ///
+    /// This keeps the `N` greatest items out of all the ones you've pushed,
+    /// which makes it the tool for collecting the top `N` results in fixed
+    /// memory. Whichever item didn't make the cut is handed back, which is the
+    /// new item itself if it's no greater than anything in the heap.
+    ///
+    /// Time: O(log n), or O(n) if the heap is full
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::ChunkHeap;
+    /// let mut heap = ChunkHeap::<i32, 2>::new();
+    /// assert_eq!(None, heap.push_bounded(3));
+    /// assert_eq!(None, heap.push_bounded(1));
+    /// assert_eq!(Some(1), heap.push_bounded(2));
+    /// assert_eq!(Some(0), heap.push_bounded(0));
+    /// assert_eq!(&[2, 3], heap.into_sorted_chunk().as_slice());
+    /// ```
+    pub fn push_bounded(&mut self, value: A) -> Option<A> {
+        let value = match self.try_push(value) {
+            Ok(()) => return None,
+            Err(value) => value,
+        };
+        // The least item in a heap is always one of the leaves, which make up
+        // the second half of it.
+        let len = self.len();
+        let least = match (len / 2..len).min_by(|&a, &b| self.chunk[a].cmp(&self.chunk[b])) {
+            Some(index) => index,
+            None => return Some(value),
+        };
+        if value <= self.chunk[least] {
+            return Some(value);
+        }
+        let removed = replace(&mut self.chunk[least], value);
+        sift_up(&mut self.chunk, least);
+        debug_invariants!(self);
+        Some(removed)
+    }
+
+    /// Remove the greatest item from the heap.
+    ///
+    /// Returns `None` if the heap is empty.
+    ///
+    /// Time: O(log n)
+    pub fn pop(&mut self) -> Option<A> {
+        if self.is_empty() {
+            return None;
+        }
+        let last = self.len() - 1;
+        self.chunk.swap(0, last);
+        let value = self.chunk.pop_back();
+        sift_down(&mut self.chunk, 0);
+        debug_invariants!(self);
+        Some(value)
+    }
+
+    /// Turn the heap into a `Chunk` with its items sorted from the least to
+    /// the greatest.
+    ///
+    /// Time: O(n log n)
+    pub fn into_sorted_chunk(self) -> Chunk<A, N> {
+        let mut chunk = self.chunk;
+        for end in (1..chunk.len()).rev() {
+            chunk.swap(0, end);
+            sift_down(&mut chunk[..end], 0);
+        }
+        chunk
+    }
+
+    /// Remove every item from the heap, from the greatest to the least.
+    ///
+    /// The heap is empty once the iterator is dropped, even if you didn't
+    /// consume all of it.
+    ///
+    /// Time: O(log n) per item
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, A, N> {
+        DrainSorted { heap: self }
+    }
+}
+
+/// Move the item at `index` towards the root until its parent is no less than
+/// it.
+fn sift_up<A: Ord>(data: &mut [A], mut index: usize) {
+    while index > 0 {
+        let parent = (index - 1) / 2;
+        if data[index] <= data[parent] {
+            break;
+        }
+        data.swap(index, parent);
+        index = parent;
+    }
+}
+
+/// Move the item at `index` towards the leaves until neither of its children
+/// is greater than it.
+fn sift_down<A: Ord>(data: &mut [A], mut index: usize) {
+    loop {
+        let mut child = 2 * index + 1;
+        if child >= data.len() {
+            break;
+        }
+        if child + 1 < data.len() && data[child + 1] > data[child] {
+            child += 1;
+        }
+        if data[index] >= data[child] {
+            break;
+        }
+        data.swap(index, child);
+        index = child;
+    }
+}
+
+/// A mutable reference to the greatest item in a `ChunkHeap`.
+///
+/// When it's dropped, the item is moved to wherever it now belongs in the
+/// heap.
+///
+/// This is returned by [`ChunkHeap::peek_mut`][peek_mut].
+///
+/// [peek_mut]: struct.ChunkHeap.html#method.peek_mut
+pub struct PeekMut<'a, A, const N: usize>
+where
+    A: Ord,
+{
+    heap: &'a mut ChunkHeap<A, N>,
+}
+
+impl<'a, A, const N: usize> PeekMut<'a, A, N>
+where
+    A: Ord,
+{
+    /// Remove the peeked at item from the heap.
+    pub fn pop(this: Self) -> A {
+        let value = this.heap.pop().unwrap();
+        // `pop` has already put the heap in order.
+        forget(this);
+        value
+    }
+}
+
+impl<'a, A, const N: usize> Deref for PeekMut<'a, A, N>
+where
+    A: Ord,
+{
+    type Target = A;
+
+    fn deref(&self) -> &Self::Target {
+        &self.heap.chunk[0]
+    }
+}
+
+impl<'a, A, const N: usize> DerefMut for PeekMut<'a, A, N>
+where
+    A: Ord,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.heap.chunk[0]
+    }
+}
+
+impl<'a, A, const N: usize> Drop for PeekMut<'a, A, N>
+where
+    A: Ord,
+{
+    fn drop(&mut self) {
+        sift_down(&mut self.heap.chunk, 0);
+        debug_invariants!(self.heap);
+    }
+}
+
+impl<A, const N: usize> Default for ChunkHeap<A, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A, const N: usize> Clone for ChunkHeap<A, N>
+where
+    A: Clone,
+{
+    fn clone(&self) -> Self {
+        ChunkHeap {
+            chunk: self.chunk.clone(),
+        }
+    }
+}
+
+impl<A, const N: usize> Debug for ChunkHeap<A, N>
+where
+    A: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("ChunkHeap")?;
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A, const N: usize> From<Chunk<A, N>> for ChunkHeap<A, N>
+where
+    A: Ord,
+{
+    /// Turn a `Chunk` into a heap, by reordering its items in place.
+    ///
+    /// Time: O(n)
+    fn from(mut chunk: Chunk<A, N>) -> Self {
+        for index in (0..chunk.len() / 2).rev() {
+            sift_down(&mut chunk, index);
+        }
+        let heap = ChunkHeap { chunk };
+        debug_invariants!(heap);
+        heap
+    }
+}
+
+impl<A, const N: usize> From<ChunkHeap<A, N>> for Chunk<A, N> {
+    /// Turn a heap into the `Chunk` it's stored in, with its items in no
+    /// particular order.
+    fn from(heap: ChunkHeap<A, N>) -> Self {
+        heap.into_chunk()
+    }
+}
+
+impl<A, const N: usize> FromIterator<A> for ChunkHeap<A, N>
+where
+    A: Ord,
+{
+    /// Collect the items into a heap.
+    ///
+    /// Panics if the iterator has more than `N` items.
+    ///
+    /// Time: O(n)
+    fn from_iter<I>(it: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+    {
+        Chunk::from_iter(it).into()
+    }
+}
+
+impl<A, const N: usize> Extend<A> for ChunkHeap<A, N>
+where
+    A: Ord,
+{
+    /// Push the contents of the iterator onto the heap.
+    ///
+    /// Panics if the heap exceeds its capacity.
+    ///
+    /// Time: O(n log n) for the length of the iterator
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = A>,
+    {
+        for item in it {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, A, const N: usize> IntoIterator for &'a ChunkHeap<A, N> {
+    type Item = &'a A;
+    type IntoIter = SliceIter<'a, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<A, const N: usize> IntoIterator for ChunkHeap<A, N> {
+    type Item = A;
+    type IntoIter = ChunkIter<A, N>;
+
+    /// Consume the heap, yielding its items in no particular order.
+    ///
+    /// Use [`into_sorted_chunk`][into_sorted_chunk] if you need them in
+    /// order.
+    ///
+    /// [into_sorted_chunk]: #method.into_sorted_chunk
+    fn into_iter(self) -> Self::IntoIter {
+        self.chunk.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cmp::Reverse;
+
+    #[test]
+    fn push_and_pop() {
+        let values = [5, 3, 9, 1, 7, 3, 8, 0];
+        let mut heap = ChunkHeap::<i32, 8>::new();
+        for value in &values {
+            heap.push(*value);
+            heap.check_invariants();
+        }
+        assert!(heap.is_full());
+        assert_eq!(Err(4), heap.try_push(4));
+        let mut expected = values.to_vec();
+        expected.sort_unstable();
+        while let Some(value) = heap.pop() {
+            assert_eq!(expected.pop(), Some(value));
+            heap.check_invariants();
+        }
+        assert!(expected.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkHeap::push: heap is full")]
+    fn push_when_full() {
+        let mut heap: ChunkHeap<i32, 2> = (0..2).collect();
+        heap.push(2);
+    }
+
+    #[test]
+    fn min_heap() {
+        let mut heap: ChunkHeap<Reverse<i32>, 8> = [4, 2, 6].iter().map(|v| Reverse(*v)).collect();
+        assert_eq!(Some(Reverse(2)), heap.pop());
+        assert_eq!(Some(Reverse(4)), heap.pop());
+    }
+
+    #[test]
+    fn heapify() {
+        let mut chunk = Chunk::<i32, 16>::new();
+        chunk.extend((0..13).map(|i| (i * 7) % 13));
+        chunk.pop_front();
+        let heap = ChunkHeap::from(chunk);
+        heap.check_invariants();
+        assert_eq!(12, heap.len());
+        assert!(heap
+            .into_sorted_chunk()
+            .iter()
+            .eq([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12].iter()));
+    }
+
+    #[test]
+    fn peek_mut() {
+        let mut heap: ChunkHeap<i32, 8> = (0..6).collect();
+        *heap.peek_mut().unwrap() = 2;
+        heap.check_invariants();
+        assert_eq!(Some(&4), heap.peek());
+        {
+            let mut top = heap.peek_mut().unwrap();
+            *top += 10;
+        }
+        assert_eq!(Some(&14), heap.peek());
+        assert_eq!(14, PeekMut::pop(heap.peek_mut().unwrap()));
+        heap.check_invariants();
+        assert_eq!(Some(&3), heap.peek());
+        heap.clear();
+        assert!(heap.peek_mut().is_none());
+    }
+
+    #[test]
+    fn push_bounded_keeps_the_greatest() {
+        let mut heap = ChunkHeap::<u32, 5>::new();
+        let mut expected = Vec::new();
+        for index in 0..100u32 {
+            let value = (index * 37) % 101;
+            let removed = heap.push_bounded(value);
+            heap.check_invariants();
+            expected.push(value);
+            expected.sort_unstable();
+            if expected.len() > 5 {
+                assert_eq!(Some(expected.remove(0)), removed);
+            } else {
+                assert_eq!(None, removed);
+            }
+        }
+        assert_eq!(expected, heap.into_sorted_chunk().as_slice());
+    }
+
+    #[test]
+    fn drain_sorted() {
+        let mut heap: ChunkHeap<i32, 8> = [3, 1, 4, 1, 5].iter().cloned().collect();
+        let mut drain = heap.drain_sorted();
+        assert_eq!(5, drain.len());
+        assert_eq!(Some(5), drain.next());
+        assert_eq!(Some(4), drain.next());
+        drop(drain);
+        assert!(heap.is_empty());
+        heap.extend(vec![2, 9, 7]);
+        assert_eq!(vec![9, 7, 2], heap.drain_sorted().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkHeap::check_invariants: item 1 is greater than its parent")]
+    fn check_invariants() {
+        let heap = ChunkHeap {
+            chunk: Chunk::<i32, 4>::pair(1, 2),
+        };
+        heap.check_invariants();
+    }
+}
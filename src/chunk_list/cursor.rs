@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::ChunkList;
+
+/// A cursor over a `ChunkList`.
+///
+/// A cursor points at an item in the list, or at a "ghost" position past the
+/// end, which sits between the last item and the first. Moving it is O(1).
+///
+/// This is returned by [`ChunkList::cursor_front`][cursor_front] and
+/// [`ChunkList::cursor_back`][cursor_back].
+///
+/// [cursor_front]: struct.ChunkList.html#method.cursor_front
+/// [cursor_back]: struct.ChunkList.html#method.cursor_back
+pub struct Cursor<'a, A, const N: usize> {
+    pub(crate) list: &'a ChunkList<A, N>,
+    pub(crate) index: usize,
+    pub(crate) block: usize,
+    pub(crate) offset: usize,
+}
+
+impl<'a, A, const N: usize> Cursor<'a, A, N> {
+    /// Get the index of the item the cursor points at, or `None` if it's at
+    /// the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        if self.index == self.list.len() {
+            None
+        } else {
+            Some(self.index)
+        }
+    }
+
+    /// Get a reference to the item the cursor points at, or `None` if it's at
+    /// the ghost position.
+    pub fn current(&self) -> Option<&'a A> {
+        let list = self.list;
+        list.blocks.get(self.block).map(|block| &block[self.offset])
+    }
+
+    /// Move the cursor to the next item. From the last item it moves to the
+    /// ghost position, and from there it moves to the first item.
+    pub fn move_next(&mut self) {
+        let (block, offset) = self.list.next_location(self.block, self.offset);
+        self.block = block;
+        self.offset = offset;
+        self.index = (self.index + 1) % (self.list.len() + 1);
+    }
+
+    /// Move the cursor to the previous item. From the first item it moves to
+    /// the ghost position, and from there it moves to the last item.
+    pub fn move_prev(&mut self) {
+        let (block, offset) = self.list.prev_location(self.block, self.offset);
+        self.block = block;
+        self.offset = offset;
+        self.index = (self.index + self.list.len()) % (self.list.len() + 1);
+    }
+}
+
+/// A cursor over a `ChunkList` which can change the list.
+///
+/// Like a [`Cursor`][Cursor], it points at an item in the list, or at a
+/// "ghost" position past the end, which sits between the last item and the
+/// first. Inserting and removing items at the cursor is O(N) for the block
+/// size `N`, and doesn't have to search for the position.
+///
+/// This is returned by [`ChunkList::cursor_front_mut`][cursor_front_mut] and
+/// [`ChunkList::cursor_back_mut`][cursor_back_mut].
+///
+/// [Cursor]: struct.Cursor.html
+/// [cursor_front_mut]: struct.ChunkList.html#method.cursor_front_mut
+/// [cursor_back_mut]: struct.ChunkList.html#method.cursor_back_mut
+pub struct CursorMut<'a, A, const N: usize> {
+    pub(crate) list: &'a mut ChunkList<A, N>,
+    pub(crate) index: usize,
+    pub(crate) block: usize,
+    pub(crate) offset: usize,
+}
+
+impl<'a, A, const N: usize> CursorMut<'a, A, N> {
+    /// Get the index of the item the cursor points at, or `None` if it's at
+    /// the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        if self.index == self.list.len() {
+            None
+        } else {
+            Some(self.index)
+        }
+    }
+
+    /// Get a mutable reference to the item the cursor points at, or `None` if
+    /// it's at the ghost position.
+    pub fn current(&mut self) -> Option<&mut A> {
+        let offset = self.offset;
+        self.list
+            .blocks
+            .get_mut(self.block)
+            .map(|block| &mut block[offset])
+    }
+
+    /// Move the cursor to the next item. From the last item it moves to the
+    /// ghost position, and from there it moves to the first item.
+    pub fn move_next(&mut self) {
+        let (block, offset) = self.list.next_location(self.block, self.offset);
+        self.block = block;
+        self.offset = offset;
+        self.index = (self.index + 1) % (self.list.len() + 1);
+    }
+
+    /// Move the cursor to the previous item. From the first item it moves to
+    /// the ghost position, and from there it moves to the last item.
+    pub fn move_prev(&mut self) {
+        let (block, offset) = self.list.prev_location(self.block, self.offset);
+        self.block = block;
+        self.offset = offset;
+        self.index = (self.index + self.list.len()) % (self.list.len() + 1);
+    }
+
+    /// Insert a value before the cursor. At the ghost position, this pushes
+    /// it to the back of the list.
+    ///
+    /// The cursor keeps pointing at the same item.
+    ///
+    /// Time: O(N)
+    pub fn insert_before(&mut self, value: A) {
+        let (block, offset) = self.list.insert_at(self.block, self.offset, value);
+        let (block, offset) = self.list.next_location(block, offset);
+        self.block = block;
+        self.offset = offset;
+        self.index += 1;
+    }
+
+    /// Insert a value after the cursor. At the ghost position, this pushes it
+    /// to the front of the list.
+    ///
+    /// The cursor keeps pointing at the same item.
+    ///
+    /// Time: O(N)
+    pub fn insert_after(&mut self, value: A) {
+        if self.block == self.list.blocks.len() {
+            self.list.push_front(value);
+            self.block = self.list.blocks.len();
+            self.index += 1;
+        } else {
+            // Inserting after the cursor never moves the item it points at.
+            let (block, offset) = self.list.next_location(self.block, self.offset);
+            self.list.insert_at(block, offset, value);
+        }
+    }
+
+    /// Remove the item the cursor points at and return it, moving the cursor
+    /// to the next item.
+    ///
+    /// Returns `None` if the cursor is at the ghost position.
+    ///
+    /// Time: O(N)
+    pub fn remove_current(&mut self) -> Option<A> {
+        if self.block == self.list.blocks.len() {
+            return None;
+        }
+        let (value, block, offset) = self.list.remove_at(self.block, self.offset);
+        self.block = block;
+        self.offset = offset;
+        Some(value)
+    }
+}
@@ -0,0 +1,161 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use alloc::boxed::Box;
+use alloc::collections::vec_deque;
+use core::iter::FusedIterator;
+
+use super::ChunkList;
+use crate::ring_buffer::{Iter as RingIter, IterMut as RingIterMut, RingBuffer};
+
+/// A reference iterator over a `ChunkList`.
+pub struct Iter<'a, A, const N: usize> {
+    pub(crate) blocks: vec_deque::Iter<'a, Box<RingBuffer<A, N>>>,
+    pub(crate) front: Option<RingIter<'a, A, N>>,
+    pub(crate) back: Option<RingIter<'a, A, N>>,
+    pub(crate) remaining: usize,
+}
+
+impl<'a, A, const N: usize> Iterator for Iter<'a, A, N> {
+    type Item = &'a A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.front.as_mut().and_then(Iterator::next) {
+                self.remaining -= 1;
+                return Some(item);
+            }
+            match self.blocks.next() {
+                Some(block) => self.front = Some(block.iter()),
+                None => {
+                    let item = self.back.as_mut().and_then(Iterator::next)?;
+                    self.remaining -= 1;
+                    return Some(item);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, A, const N: usize> DoubleEndedIterator for Iter<'a, A, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                self.remaining -= 1;
+                return Some(item);
+            }
+            match self.blocks.next_back() {
+                Some(block) => self.back = Some(block.iter()),
+                None => {
+                    let item = self
+                        .front
+                        .as_mut()
+                        .and_then(DoubleEndedIterator::next_back)?;
+                    self.remaining -= 1;
+                    return Some(item);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, A, const N: usize> ExactSizeIterator for Iter<'a, A, N> {}
+
+impl<'a, A, const N: usize> FusedIterator for Iter<'a, A, N> {}
+
+/// A mutable reference iterator over a `ChunkList`.
+pub struct IterMut<'a, A, const N: usize> {
+    pub(crate) blocks: vec_deque::IterMut<'a, Box<RingBuffer<A, N>>>,
+    pub(crate) front: Option<RingIterMut<'a, A, N>>,
+    pub(crate) back: Option<RingIterMut<'a, A, N>>,
+    pub(crate) remaining: usize,
+}
+
+impl<'a, A, const N: usize> Iterator for IterMut<'a, A, N>
+where
+    A: 'a,
+{
+    type Item = &'a mut A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.front.as_mut().and_then(Iterator::next) {
+                self.remaining -= 1;
+                return Some(item);
+            }
+            match self.blocks.next() {
+                Some(block) => self.front = Some(block.iter_mut()),
+                None => {
+                    let item = self.back.as_mut().and_then(Iterator::next)?;
+                    self.remaining -= 1;
+                    return Some(item);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, A, const N: usize> DoubleEndedIterator for IterMut<'a, A, N>
+where
+    A: 'a,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                self.remaining -= 1;
+                return Some(item);
+            }
+            match self.blocks.next_back() {
+                Some(block) => self.back = Some(block.iter_mut()),
+                None => {
+                    let item = self
+                        .front
+                        .as_mut()
+                        .and_then(DoubleEndedIterator::next_back)?;
+                    self.remaining -= 1;
+                    return Some(item);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, A, const N: usize> ExactSizeIterator for IterMut<'a, A, N> where A: 'a {}
+
+impl<'a, A, const N: usize> FusedIterator for IterMut<'a, A, N> where A: 'a {}
+
+/// A consuming iterator over a `ChunkList`.
+pub struct OwnedIter<A, const N: usize> {
+    pub(crate) list: ChunkList<A, N>,
+}
+
+impl<A, const N: usize> Iterator for OwnedIter<A, N> {
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<A, const N: usize> DoubleEndedIterator for OwnedIter<A, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<A, const N: usize> ExactSizeIterator for OwnedIter<A, N> {}
+
+impl<A, const N: usize> FusedIterator for OwnedIter<A, N> {}
@@ -0,0 +1,955 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A growable list of fixed capacity blocks.
+//!
+//! See [`ChunkList`](struct.ChunkList.html)
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Error, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{Index, IndexMut};
+
+use array_ops::HasLength;
+
+use crate::ring_buffer::RingBuffer;
+
+mod cursor;
+pub use self::cursor::{Cursor, CursorMut};
+
+mod iter;
+pub use self::iter::{Iter, IterMut, OwnedIter};
+
+/// A growable list of fixed capacity blocks.
+///
+/// An unrolled list: a double ended queue which stores its items in
+/// heap allocated [`RingBuffer`][RingBuffer]s of `N` items each, kept in a
+/// `VecDeque`. Where the rest of the crate's data structures panic when they
+/// run out of room, this one adds another block, so it's the next step up
+/// when your data outgrows a single chunk but you want to keep its cache
+/// friendly block layout.
+///
+/// Every block except the first and the last is kept at least half full, by
+/// merging a block with its neighbour or moving items over from it when it
+/// runs low, so there are never more than about `2n / N` blocks.
+///
+/// Pushing and popping at either end is O(1). Finding an item by index means
+/// walking the blocks from the nearer end, which is O(n / N).
+/// [`split_off`][split_off] and [`append`][append] move whole blocks rather
+/// than items, except for the blocks where the lists are cut or joined, and a
+/// [`CursorMut`][CursorMut] can insert and remove items in the middle without
+/// searching for them each time.
+///
+/// It needs both the `alloc` and the `ringbuffer` feature flags.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sized_chunks::ChunkList;
+/// let mut list: ChunkList<i32, 4> = (0..10).collect();
+/// list.push_front(-1);
+/// assert_eq!(11, list.len());
+/// assert_eq!(4, list[5]);
+/// let tail = list.split_off(8);
+/// assert!(tail.iter().eq(&[7, 8, 9]));
+/// let mut cursor = list.cursor_front_mut();
+/// cursor.move_next();
+/// assert_eq!(Some(0), cursor.remove_current());
+/// assert_eq!(Some(&mut 1), cursor.current());
+/// ```
+///
+/// [RingBuffer]: ../ring_buffer/struct.RingBuffer.html
+/// [split_off]: #method.split_off
+/// [append]: #method.append
+/// [CursorMut]: struct.CursorMut.html
+pub struct ChunkList<A, const N: usize> {
+    /// The blocks, none of which are ever empty, and all but the first and
+    /// the last of which hold at least `MIN_BLOCK_LEN` items.
+    blocks: VecDeque<Box<RingBuffer<A, N>>>,
+    len: usize,
+}
+
+impl<A, const N: usize> ChunkList<A, N> {
+    /// The number of items in each block.
+    pub const BLOCK_CAPACITY: usize = N;
+
+    /// The fewest items a block other than the first or the last may hold.
+    const MIN_BLOCK_LEN: usize = N / 2;
+
+    /// Construct a new empty list.
+    ///
+    /// This doesn't allocate until you push the first item.
+    pub fn new() -> Self {
+        if N == 0 {
+            panic!("ChunkList::new: blocks can't have a capacity of zero");
+        }
+        ChunkList {
+            blocks: VecDeque::new(),
+            len: 0,
+        }
+    }
+
+    /// Get the length of the list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Test if the list is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the number of blocks the list is currently made of.
+    #[inline]
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Check the list's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of lists.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the list.
+    ///
+    /// Time: O(n / N)
+    pub fn check_invariants(&self) {
+        let mut len = 0;
+        for (index, block) in self.blocks.iter().enumerate() {
+            block.check_invariants();
+            assert!(
+                !block.is_empty(),
+                "ChunkList::check_invariants: block {} is empty",
+                index
+            );
+            if index > 0 && index + 1 < self.blocks.len() {
+                assert!(
+                    block.len() >= Self::MIN_BLOCK_LEN,
+                    "ChunkList::check_invariants: block {} is less than half full",
+                    index
+                );
+            }
+            len += block.len();
+        }
+        assert_eq!(
+            self.len, len,
+            "ChunkList::check_invariants: length doesn't match the blocks"
+        );
+    }
+
+    /// Push an item to the back of the list.
+    ///
+    /// Time: O(1)
+    pub fn push_back(&mut self, value: A) {
+        match self.blocks.back_mut() {
+            Some(block) if !block.is_full() => block.push_back(value),
+            _ => {
+                let mut block = RingBuffer::new_boxed();
+                block.push_back(value);
+                self.blocks.push_back(block);
+            }
+        }
+        self.len += 1;
+        debug_invariants!(self);
+    }
+
+    /// Push an item to the front of the list.
+    ///
+    /// Time: O(1)
+    pub fn push_front(&mut self, value: A) {
+        match self.blocks.front_mut() {
+            Some(block) if !block.is_full() => block.push_front(value),
+            _ => {
+                let mut block = RingBuffer::new_boxed();
+                block.push_front(value);
+                self.blocks.push_front(block);
+            }
+        }
+        self.len += 1;
+        debug_invariants!(self);
+    }
+
+    /// Pop an item off the back of the list.
+    ///
+    /// Returns `None` if the list is empty.
+    ///
+    /// Time: O(1)
+    pub fn pop_back(&mut self) -> Option<A> {
+        let block = self.blocks.back_mut()?;
+        let value = block.pop_back();
+        if block.is_empty() {
+            self.blocks.pop_back();
+        }
+        self.len -= 1;
+        debug_invariants!(self);
+        value
+    }
+
+    /// Pop an item off the front of the list.
+    ///
+    /// Returns `None` if the list is empty.
+    ///
+    /// Time: O(1)
+    pub fn pop_front(&mut self) -> Option<A> {
+        let block = self.blocks.front_mut()?;
+        let value = block.pop_front();
+        if block.is_empty() {
+            self.blocks.pop_front();
+        }
+        self.len -= 1;
+        debug_invariants!(self);
+        value
+    }
+
+    /// Get a reference to the item at the front of the list.
+    pub fn front(&self) -> Option<&A> {
+        self.blocks.front().map(|block| &block[0])
+    }
+
+    /// Get a reference to the item at the back of the list.
+    pub fn back(&self) -> Option<&A> {
+        self.blocks.back().map(|block| &block[block.len() - 1])
+    }
+
+    /// Get a mutable reference to the item at the front of the list.
+    pub fn front_mut(&mut self) -> Option<&mut A> {
+        self.blocks.front_mut().map(|block| &mut block[0])
+    }
+
+    /// Get a mutable reference to the item at the back of the list.
+    pub fn back_mut(&mut self) -> Option<&mut A> {
+        self.blocks.back_mut().map(|block| {
+            let last = block.len() - 1;
+            &mut block[last]
+        })
+    }
+
+    /// Find the block holding the item at `index`, and the item's offset in
+    /// it. An `index` of the list's length maps to the end of the list,
+    /// one block past the last.
+    fn locate(&self, index: usize) -> (usize, usize) {
+        if index >= self.len {
+            return (self.blocks.len(), 0);
+        }
+        if index < self.len / 2 {
+            let mut index = index;
+            for (block_index, block) in self.blocks.iter().enumerate() {
+                if index < block.len() {
+                    return (block_index, index);
+                }
+                index -= block.len();
+            }
+        } else {
+            let mut from_back = self.len - index;
+            for (block_index, block) in self.blocks.iter().enumerate().rev() {
+                if from_back <= block.len() {
+                    return (block_index, block.len() - from_back);
+                }
+                from_back -= block.len();
+            }
+        }
+        unreachable!("ChunkList::locate: blocks are shorter than the list")
+    }
+
+    /// Get the location after `(block, offset)`, wrapping around from the end
+    /// of the list to the front.
+    pub(crate) fn next_location(&self, block: usize, offset: usize) -> (usize, usize) {
+        if block == self.blocks.len() {
+            (0, 0)
+        } else if offset + 1 < self.blocks[block].len() {
+            (block, offset + 1)
+        } else {
+            (block + 1, 0)
+        }
+    }
+
+    /// Get the location before `(block, offset)`, wrapping around from the
+    /// front of the list to the end.
+    pub(crate) fn prev_location(&self, block: usize, offset: usize) -> (usize, usize) {
+        if offset > 0 {
+            (block, offset - 1)
+        } else if block > 0 {
+            (block - 1, self.blocks[block - 1].len() - 1)
+        } else if self.blocks.is_empty() {
+            (0, 0)
+        } else {
+            (self.blocks.len(), 0)
+        }
+    }
+
+    /// Move every item from the block after `index` onto the back of the
+    /// block at `index` and remove the emptied block, if they fit in one
+    /// block.
+    ///
+    /// Returns whether the blocks were merged.
+    fn merge_next(&mut self, index: usize) -> bool {
+        if index + 1 >= self.blocks.len()
+            || self.blocks[index].len() + self.blocks[index + 1].len() > N
+        {
+            return false;
+        }
+        let mut pair = self.blocks.range_mut(index..index + 2);
+        let (block, next) = (pair.next().unwrap(), pair.next().unwrap());
+        block.append(next);
+        self.blocks.remove(index + 1);
+        true
+    }
+
+    /// Bring the block at `index` back up to `MIN_BLOCK_LEN` items if it's
+    /// run low, unless it's the first or the last block.
+    ///
+    /// It's merged with the block after it if they fit in one block, and
+    /// otherwise takes items from the front of that block until the two are
+    /// even. Either way, the items already in the block stay where they are.
+    fn rebalance(&mut self, index: usize) {
+        while index > 0
+            && index + 1 < self.blocks.len()
+            && self.blocks[index].len() < Self::MIN_BLOCK_LEN
+        {
+            if !self.merge_next(index) {
+                let mut pair = self.blocks.range_mut(index..index + 2);
+                let (block, next) = (pair.next().unwrap(), pair.next().unwrap());
+                let count = (block.len() + next.len()) / 2 - block.len();
+                block.drain_from_front(next, count);
+            }
+        }
+    }
+
+    /// Insert a value at a location, returning the location it ends up at.
+    ///
+    /// If the block it belongs in is full, it goes at the back of the block
+    /// before if that has room, and otherwise the full block is split, and
+    /// the halves rebalanced.
+    pub(crate) fn insert_at(&mut self, block: usize, offset: usize, value: A) -> (usize, usize) {
+        if block == self.blocks.len() {
+            self.push_back(value);
+            return (
+                self.blocks.len() - 1,
+                self.blocks[self.blocks.len() - 1].len() - 1,
+            );
+        }
+        let location = if !self.blocks[block].is_full() {
+            self.blocks[block].insert(offset, value);
+            (block, offset)
+        } else if offset == 0 {
+            match block.checked_sub(1) {
+                Some(before) if !self.blocks[before].is_full() => {
+                    self.blocks[before].push_back(value);
+                    (before, self.blocks[before].len() - 1)
+                }
+                _ => {
+                    let mut new_block = RingBuffer::new_boxed();
+                    new_block.push_back(value);
+                    self.blocks.insert(block, new_block);
+                    self.rebalance(block);
+                    (block, 0)
+                }
+            }
+        } else {
+            let tail = self.blocks[block].split_off_boxed(offset);
+            self.blocks[block].push_back(value);
+            self.blocks.insert(block + 1, tail);
+            self.rebalance(block);
+            self.rebalance(block + 1);
+            (block, offset)
+        };
+        self.len += 1;
+        debug_invariants!(self);
+        location
+    }
+
+    /// Remove the value at a location, returning it and the location of the
+    /// item after it.
+    pub(crate) fn remove_at(&mut self, block: usize, offset: usize) -> (A, usize, usize) {
+        let value = self.blocks[block].remove(offset);
+        self.len -= 1;
+        let len = self.blocks[block].len();
+        let next = if len == 0 {
+            self.blocks.remove(block);
+            (block, 0)
+        } else {
+            self.rebalance(block);
+            if offset < len {
+                (block, offset)
+            } else if self.blocks[block].len() > len {
+                // The next item was moved over from the block after.
+                (block, len)
+            } else {
+                (block + 1, 0)
+            }
+        };
+        debug_invariants!(self);
+        (value, next.0, next.1)
+    }
+
+    /// Get a reference to the value at index `index`.
+    ///
+    /// Time: O(n / N)
+    pub fn get(&self, index: usize) -> Option<&A> {
+        if index >= self.len {
+            return None;
+        }
+        let (block, offset) = self.locate(index);
+        Some(&self.blocks[block][offset])
+    }
+
+    /// Get a mutable reference to the value at index `index`.
+    ///
+    /// Time: O(n / N)
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut A> {
+        if index >= self.len {
+            return None;
+        }
+        let (block, offset) = self.locate(index);
+        Some(&mut self.blocks[block][offset])
+    }
+
+    /// Insert a value at index `index`, shifting the items after it along
+    /// within their block.
+    ///
+    /// Panics if the index is out of bounds.
+    ///
+    /// Time: O(N + n / N)
+    pub fn insert(&mut self, index: usize, value: A) {
+        if index > self.len {
+            panic!("ChunkList::insert: index out of bounds");
+        }
+        let (block, offset) = self.locate(index);
+        self.insert_at(block, offset, value);
+    }
+
+    /// Remove the value at index `index`, shifting the items after it along
+    /// within their block.
+    ///
+    /// Panics if the index is out of bounds.
+    ///
+    /// Time: O(N + n / N)
+    pub fn remove(&mut self, index: usize) -> A {
+        if index >= self.len {
+            panic!("ChunkList::remove: index out of bounds");
+        }
+        let (block, offset) = self.locate(index);
+        self.remove_at(block, offset).0
+    }
+
+    /// Split the list into two, the original list containing everything up
+    /// to `index` and the returned list containing everything from `index`
+    /// onwards.
+    ///
+    /// Only the block `index` falls inside gets split. The blocks after it
+    /// are moved over whole, and the blocks either side of the cut are merged
+    /// with their neighbours if they fit.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(N + n / N)
+    pub fn split_off(&mut self, index: usize) -> Self {
+        if index > self.len {
+            panic!("ChunkList::split_off: index out of bounds");
+        }
+        let (block, offset) = self.locate(index);
+        let blocks = if offset == 0 {
+            self.blocks.split_off(block)
+        } else {
+            let mut blocks = self.blocks.split_off(block + 1);
+            let tail = self.blocks[block].split_off_boxed(offset);
+            blocks.push_front(tail);
+            blocks
+        };
+        let mut right = ChunkList {
+            blocks,
+            len: self.len - index,
+        };
+        self.len = index;
+        if let Some(last) = self.blocks.len().checked_sub(2) {
+            self.merge_next(last);
+        }
+        right.merge_next(0);
+        debug_invariants!(self, right);
+        right
+    }
+
+    /// Move every item from `other` onto the back of `self`, leaving `other`
+    /// empty.
+    ///
+    /// This moves whole blocks, apart from rebalancing the two blocks where
+    /// the lists meet.
+    ///
+    /// Time: O(N + n / N) for the number of blocks in `other`
+    pub fn append(&mut self, other: &mut Self) {
+        let join = self.blocks.len();
+        self.blocks.append(&mut other.blocks);
+        if join > 0 {
+            self.rebalance(join - 1);
+            self.rebalance(join);
+        }
+        self.len += other.len;
+        other.len = 0;
+        debug_invariants!(self, other);
+    }
+
+    /// Discard the contents of the list, and free its blocks.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+        self.len = 0;
+        debug_invariants!(self);
+    }
+
+    /// Get an iterator over references to the items in the list, from front
+    /// to back.
+    pub fn iter(&self) -> Iter<'_, A, N> {
+        Iter {
+            blocks: self.blocks.iter(),
+            front: None,
+            back: None,
+            remaining: self.len,
+        }
+    }
+
+    /// Get an iterator over mutable references to the items in the list,
+    /// from front to back.
+    pub fn iter_mut(&mut self) -> IterMut<'_, A, N> {
+        IterMut {
+            blocks: self.blocks.iter_mut(),
+            front: None,
+            back: None,
+            remaining: self.len,
+        }
+    }
+
+    /// Get a cursor pointing at the front of the list, or at the ghost
+    /// position if the list is empty.
+    pub fn cursor_front(&self) -> Cursor<'_, A, N> {
+        Cursor {
+            list: self,
+            index: 0,
+            block: 0,
+            offset: 0,
+        }
+    }
+
+    /// Get a cursor pointing at the back of the list, or at the ghost
+    /// position if the list is empty.
+    pub fn cursor_back(&self) -> Cursor<'_, A, N> {
+        let (block, offset) = self.prev_location(self.blocks.len(), 0);
+        Cursor {
+            list: self,
+            index: self.len.saturating_sub(1),
+            block,
+            offset,
+        }
+    }
+
+    /// Get a mutable cursor pointing at the front of the list, or at the
+    /// ghost position if the list is empty.
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, A, N> {
+        CursorMut {
+            list: self,
+            index: 0,
+            block: 0,
+            offset: 0,
+        }
+    }
+
+    /// Get a mutable cursor pointing at the back of the list, or at the
+    /// ghost position if the list is empty.
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, A, N> {
+        let (block, offset) = self.prev_location(self.blocks.len(), 0);
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            list: self,
+            block,
+            offset,
+        }
+    }
+}
+
+impl<A, const N: usize> Default for ChunkList<A, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A, const N: usize> Clone for ChunkList<A, N>
+where
+    A: Clone,
+{
+    fn clone(&self) -> Self {
+        ChunkList {
+            blocks: self
+                .blocks
+                .iter()
+                .map(|block| block.clone_boxed())
+                .collect(),
+            len: self.len,
+        }
+    }
+}
+
+impl<A, const N: usize> Index<usize> for ChunkList<A, N> {
+    type Output = A;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!(
+                "ChunkList::index: index out of bounds {} >= {}",
+                index, self.len
+            ),
+        }
+    }
+}
+
+impl<A, const N: usize> IndexMut<usize> for ChunkList<A, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let len = self.len;
+        match self.get_mut(index) {
+            Some(value) => value,
+            None => panic!(
+                "ChunkList::index_mut: index out of bounds {} >= {}",
+                index, len
+            ),
+        }
+    }
+}
+
+impl<A, const N: usize> Debug for ChunkList<A, N>
+where
+    A: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("ChunkList")?;
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A, const N: usize> Hash for ChunkList<A, N>
+where
+    A: Hash,
+{
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        for item in self {
+            item.hash(hasher)
+        }
+    }
+}
+
+impl<A, const N: usize> PartialEq for ChunkList<A, N>
+where
+    A: PartialEq,
+{
+    /// Compare the items of two lists, however they're split into blocks.
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<A, const N: usize> Eq for ChunkList<A, N> where A: Eq {}
+
+impl<A, const N: usize> PartialOrd for ChunkList<A, N>
+where
+    A: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<A, const N: usize> Ord for ChunkList<A, N>
+where
+    A: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<A, const N: usize> FromIterator<A> for ChunkList<A, N> {
+    fn from_iter<I>(it: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+    {
+        let mut list = Self::new();
+        list.extend(it);
+        list
+    }
+}
+
+impl<A, const N: usize> Extend<A> for ChunkList<A, N> {
+    /// Push the contents of the iterator onto the back of the list.
+    ///
+    /// Time: O(n) for the length of the iterator
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = A>,
+    {
+        for item in it {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<'a, A, const N: usize> Extend<&'a A> for ChunkList<A, N>
+where
+    A: 'a + Clone,
+{
+    /// Push clones of the contents of the iterator onto the back of the list.
+    ///
+    /// Time: O(n) for the length of the iterator
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = &'a A>,
+    {
+        for item in it {
+            self.push_back(item.clone());
+        }
+    }
+}
+
+impl<'a, A, const N: usize> IntoIterator for &'a ChunkList<A, N> {
+    type Item = &'a A;
+    type IntoIter = Iter<'a, A, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, A, const N: usize> IntoIterator for &'a mut ChunkList<A, N> {
+    type Item = &'a mut A;
+    type IntoIter = IterMut<'a, A, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<A, const N: usize> IntoIterator for ChunkList<A, N> {
+    type Item = A;
+    type IntoIter = OwnedIter<A, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        OwnedIter { list: self }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::DropTest;
+    use std::collections::VecDeque;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn push_and_pop() {
+        let mut list = ChunkList::<i32, 4>::new();
+        let mut model = VecDeque::new();
+        for step in 0..100 {
+            match step % 7 {
+                0 | 3 => {
+                    list.push_front(step);
+                    model.push_front(step);
+                }
+                1 | 4 | 5 => {
+                    list.push_back(step);
+                    model.push_back(step);
+                }
+                2 => assert_eq!(model.pop_front(), list.pop_front()),
+                _ => assert_eq!(model.pop_back(), list.pop_back()),
+            }
+            list.check_invariants();
+            assert!(list.iter().eq(model.iter()));
+            assert!(list.iter().rev().eq(model.iter().rev()));
+            assert_eq!(model.front(), list.front());
+            assert_eq!(model.back(), list.back());
+        }
+        for (index, value) in model.iter().enumerate() {
+            assert_eq!(value, &list[index]);
+        }
+        assert_eq!(None, list.get(model.len()));
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut list: ChunkList<i32, 4> = (0..10).collect();
+        let mut model: Vec<i32> = (0..10).collect();
+        for step in 0..60 {
+            let index = (step * 7) % (model.len() + 1);
+            if step % 3 == 2 && index < model.len() {
+                assert_eq!(model.remove(index), list.remove(index));
+            } else {
+                list.insert(index, 100 + step as i32);
+                model.insert(index, 100 + step as i32);
+            }
+            list.check_invariants();
+            assert!(list.iter().eq(model.iter()));
+        }
+    }
+
+    #[test]
+    fn split_off_and_append() {
+        for index in 0..=13 {
+            let mut left: ChunkList<usize, 4> = (0..13).collect();
+            left.pop_front();
+            left.push_front(0);
+            let mut right = left.split_off(index);
+            left.check_invariants();
+            right.check_invariants();
+            assert!(left.iter().eq((0..index).collect::<Vec<_>>().iter()));
+            assert!(right.iter().eq((index..13).collect::<Vec<_>>().iter()));
+            left.append(&mut right);
+            left.check_invariants();
+            assert!(right.is_empty());
+            assert!(left.into_iter().eq(0..13));
+        }
+    }
+
+    #[test]
+    fn blocks_stay_half_full() {
+        // Thinning the list out in the middle, or cutting and rejoining it,
+        // used to leave behind lots of nearly empty blocks.
+        let mut list: ChunkList<usize, 8> = (0..1000).collect();
+        {
+            let mut cursor = list.cursor_front_mut();
+            for index in 0..1000 {
+                if index % 8 == 0 {
+                    cursor.move_next();
+                } else {
+                    cursor.remove_current();
+                }
+            }
+        }
+        list.check_invariants();
+        assert!(list.iter().copied().eq((0..1000).step_by(8)));
+        assert!(list.block_count() <= 2 * list.len() / 8 + 2);
+
+        let mut list: ChunkList<usize, 8> = (0..1000).collect();
+        for index in (1..1000).step_by(7) {
+            let mut right = list.split_off(index);
+            list.append(&mut right);
+            list.check_invariants();
+        }
+        assert!(list.iter().copied().eq(0..1000));
+        assert!(list.block_count() <= 2 * list.len() / 8 + 2);
+    }
+
+    #[test]
+    fn cursor() {
+        let list: ChunkList<i32, 3> = (0..7).collect();
+        let mut cursor = list.cursor_front();
+        for index in 0..7 {
+            assert_eq!(Some(index as usize), cursor.index());
+            assert_eq!(Some(&index), cursor.current());
+            cursor.move_next();
+        }
+        assert_eq!(None, cursor.index());
+        assert_eq!(None, cursor.current());
+        cursor.move_next();
+        assert_eq!(Some(&0), cursor.current());
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(Some(6), cursor.index());
+        assert_eq!(Some(&6), list.cursor_back().current());
+    }
+
+    #[test]
+    fn cursor_mut() {
+        let mut list: ChunkList<i32, 3> = (0..6).collect();
+        let mut model: Vec<i32> = (0..6).collect();
+        {
+            let mut cursor = list.cursor_front_mut();
+            let mut position = 0;
+            for step in 0..40 {
+                match step % 5 {
+                    0 => {
+                        cursor.insert_before(100 + step);
+                        model.insert(position, 100 + step);
+                        position += 1;
+                    }
+                    1 => {
+                        cursor.insert_after(200 + step);
+                        if position == model.len() {
+                            model.insert(0, 200 + step);
+                            position += 1;
+                        } else {
+                            model.insert(position + 1, 200 + step);
+                        }
+                    }
+                    2 => {
+                        let expected = if position < model.len() {
+                            Some(model.remove(position))
+                        } else {
+                            None
+                        };
+                        assert_eq!(expected, cursor.remove_current());
+                    }
+                    3 => {
+                        cursor.move_next();
+                        position = (position + 1) % (model.len() + 1);
+                    }
+                    _ => {
+                        cursor.move_next();
+                        cursor.move_next();
+                        position = (position + 2) % (model.len() + 1);
+                    }
+                }
+                assert_eq!(model.get(position), cursor.current().map(|value| &*value));
+                let expected_index = if position == model.len() {
+                    None
+                } else {
+                    Some(position)
+                };
+                assert_eq!(expected_index, cursor.index());
+            }
+        }
+        list.check_invariants();
+        assert!(list.iter().eq(model.iter()));
+    }
+
+    #[test]
+    fn dropping() {
+        let counter = AtomicUsize::new(0);
+        {
+            let mut list = ChunkList::<DropTest<'_>, 4>::new();
+            for _ in 0..10 {
+                list.push_back(DropTest::new(&counter));
+            }
+            let right = list.split_off(3);
+            assert_eq!(10, counter.load(Ordering::Relaxed));
+            list.remove(1);
+            assert_eq!(9, counter.load(Ordering::Relaxed));
+            let mut iter = right.into_iter();
+            iter.next();
+            assert_eq!(8, counter.load(Ordering::Relaxed));
+        }
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkList::check_invariants: block 0 is empty")]
+    fn check_invariants() {
+        let mut list = ChunkList::<i32, 4>::new();
+        list.blocks.push_back(RingBuffer::new_boxed());
+        list.check_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkList::check_invariants: block 1 is less than half full")]
+    fn check_invariants_half_full() {
+        let mut list: ChunkList<i32, 4> = (0..8).collect();
+        list.blocks.insert(1, Box::new(RingBuffer::unit(0)));
+        list.len += 1;
+        list.check_invariants();
+    }
+}
@@ -0,0 +1,190 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use core::mem::replace;
+
+use super::ChunkMap;
+
+/// A view into a single entry in a `ChunkMap`, which may either be vacant or
+/// occupied.
+///
+/// This is returned by [`ChunkMap::entry`][entry].
+///
+/// [entry]: struct.ChunkMap.html#method.entry
+pub enum Entry<'a, K, V, const N: usize>
+where
+    K: Ord,
+{
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V, N>),
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V, N>),
+}
+
+impl<'a, K, V, const N: usize> Entry<'a, K, V, N>
+where
+    K: Ord,
+{
+    /// Get the key of this entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(entry) => entry.key(),
+            Entry::Occupied(entry) => entry.key(),
+        }
+    }
+
+    /// Insert `default` if the entry is vacant, and return a mutable
+    /// reference to the value.
+    ///
+    /// Panics if the entry is vacant and the map is full.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Insert the result of calling `default` if the entry is vacant, and
+    /// return a mutable reference to the value.
+    ///
+    /// Panics if the entry is vacant and the map is full.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Vacant(entry) => entry.insert(default()),
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Insert the default value if the entry is vacant, and return a mutable
+    /// reference to the value.
+    ///
+    /// Panics if the entry is vacant and the map is full.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(Default::default)
+    }
+
+    /// Call `f` on the value if the entry is occupied.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            entry => entry,
+        }
+    }
+}
+
+/// A view into a vacant entry in a `ChunkMap`.
+pub struct VacantEntry<'a, K, V, const N: usize>
+where
+    K: Ord,
+{
+    pub(crate) map: &'a mut ChunkMap<K, V, N>,
+    pub(crate) key: K,
+    pub(crate) index: usize,
+}
+
+impl<'a, K, V, const N: usize> VacantEntry<'a, K, V, N>
+where
+    K: Ord,
+{
+    /// Get the key of this entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take back ownership of the key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Insert a value into the entry, and return a mutable reference to it.
+    ///
+    /// Panics if the map is full.
+    ///
+    /// Time: O(n)
+    pub fn insert(self, value: V) -> &'a mut V {
+        match self.try_insert(value) {
+            Ok(value) => value,
+            Err(_) => panic!("ChunkMap::insert: map is full"),
+        }
+    }
+
+    /// Insert a value into the entry, and return a mutable reference to it.
+    ///
+    /// If the map is full, you get the key and the value back as the error.
+    ///
+    /// Time: O(n)
+    pub fn try_insert(self, value: V) -> Result<&'a mut V, (K, V)> {
+        if self.map.is_full() {
+            return Err((self.key, value));
+        }
+        self.map.chunk.insert(self.index, (self.key, value));
+        debug_invariants!(self.map);
+        Ok(&mut self.map.chunk[self.index].1)
+    }
+}
+
+/// A view into an occupied entry in a `ChunkMap`.
+pub struct OccupiedEntry<'a, K, V, const N: usize>
+where
+    K: Ord,
+{
+    pub(crate) map: &'a mut ChunkMap<K, V, N>,
+    pub(crate) index: usize,
+}
+
+impl<'a, K, V, const N: usize> OccupiedEntry<'a, K, V, N>
+where
+    K: Ord,
+{
+    /// Get the key of this entry.
+    pub fn key(&self) -> &K {
+        &self.map.chunk[self.index].0
+    }
+
+    /// Get a reference to the value in this entry.
+    pub fn get(&self) -> &V {
+        &self.map.chunk[self.index].1
+    }
+
+    /// Get a mutable reference to the value in this entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.chunk[self.index].1
+    }
+
+    /// Turn the entry into a mutable reference to its value, which lives as
+    /// long as the map does.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.chunk[self.index].1
+    }
+
+    /// Replace the value in this entry, returning the old value.
+    pub fn insert(&mut self, value: V) -> V {
+        replace(self.get_mut(), value)
+    }
+
+    /// Remove the entry from the map, returning its value.
+    ///
+    /// Time: O(n)
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Remove the entry from the map, returning its key and value.
+    ///
+    /// Time: O(n)
+    pub fn remove_entry(self) -> (K, V) {
+        let entry = self.map.chunk.remove(self.index);
+        debug_invariants!(self.map);
+        entry
+    }
+}
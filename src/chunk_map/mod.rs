@@ -0,0 +1,759 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A fixed capacity sorted map.
+//!
+//! See [`ChunkMap`](struct.ChunkMap.html)
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Error, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::mem::replace;
+use core::ops::{Bound, Index, IndexMut, RangeBounds};
+
+use crate::sized_chunk::{Chunk, Iter as ChunkIter};
+
+mod entry;
+pub use self::entry::{Entry, OccupiedEntry, VacantEntry};
+
+mod iter;
+pub use self::iter::{Iter, IterMut, Keys, Values, ValuesMut};
+
+/// A fixed capacity sorted map.
+///
+/// A map of up to `N` entries, stored inline in a [`Chunk`][Chunk] as a
+/// sorted array of key and value pairs, which it searches with a binary
+/// search. Its API follows `std::collections::BTreeMap`, but as a flat array,
+/// lookups are O(log n) while inserts and removals are O(n), which is usually
+/// a good trade for small maps, and it's what you want for the leaves of a
+/// B-tree.
+///
+/// Like the rest of the crate, it panics rather than grows when you insert
+/// more than it can hold. Use [`try_insert`][try_insert] if you'd rather be
+/// told. Inserting a key which is already in the map only replaces the value,
+/// so it never panics.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sized_chunks::ChunkMap;
+/// let mut map = ChunkMap::<&str, i32, 8>::new();
+/// map.insert("two", 2);
+/// map.insert("one", 1);
+/// map.insert("three", 3);
+/// assert_eq!(Some(&2), map.get("two"));
+/// *map.entry("one").or_insert(0) += 10;
+/// let keys: Vec<_> = map.range("p"..).map(|(key, _)| *key).collect();
+/// assert_eq!(vec!["three", "two"], keys);
+/// assert_eq!(Some((&"one", &11)), map.first_key_value());
+/// ```
+///
+/// [Chunk]: ../sized_chunk/type.Chunk.html
+/// [try_insert]: #method.try_insert
+pub struct ChunkMap<K, V, const N: usize> {
+    chunk: Chunk<(K, V), N>,
+}
+
+impl<K, V, const N: usize> ChunkMap<K, V, N> {
+    /// The maximum number of entries this `ChunkMap` can contain.
+    pub const CAPACITY: usize = N;
+
+    /// Construct a new empty map.
+    pub fn new() -> Self {
+        ChunkMap {
+            chunk: Chunk::new(),
+        }
+    }
+
+    /// Get the number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.chunk.len()
+    }
+
+    /// Test if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunk.is_empty()
+    }
+
+    /// Test if the map is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.chunk.is_full()
+    }
+
+    /// Get the entry with the least key.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.chunk.first().map(|(key, value)| (key, value))
+    }
+
+    /// Get the entry with the greatest key.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.chunk.last().map(|(key, value)| (key, value))
+    }
+
+    /// Remove and return the entry with the least key.
+    ///
+    /// Time: O(1)
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.chunk.pop_front())
+        }
+    }
+
+    /// Remove and return the entry with the greatest key.
+    ///
+    /// Time: O(1)
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.chunk.pop_back())
+        }
+    }
+
+    /// Get an iterator over the entries of the map, in key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.chunk.iter(),
+        }
+    }
+
+    /// Get an iterator over the entries of the map, in key order, with
+    /// mutable references to the values.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.chunk.iter_mut(),
+        }
+    }
+
+    /// Get an iterator over the keys of the map, in order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            inner: self.chunk.iter(),
+        }
+    }
+
+    /// Get an iterator over the values of the map, in key order.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            inner: self.chunk.iter(),
+        }
+    }
+
+    /// Get an iterator over mutable references to the values of the map, in
+    /// key order.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.chunk.iter_mut(),
+        }
+    }
+
+    /// Get the entries of the map as a slice of key and value pairs, in key
+    /// order.
+    #[inline]
+    pub fn as_slice(&self) -> &[(K, V)] {
+        self.chunk.as_slice()
+    }
+
+    /// Turn the map into the `Chunk` of key and value pairs it's stored in,
+    /// in key order.
+    pub fn into_chunk(self) -> Chunk<(K, V), N> {
+        self.chunk
+    }
+
+    /// Discard the contents of the map.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        self.chunk.clear();
+    }
+}
+
+impl<K, V, const N: usize> ChunkMap<K, V, N>
+where
+    K: Ord,
+{
+    /// Check the map's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of maps.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the map.
+    ///
+    /// Time: O(n)
+    pub fn check_invariants(&self) {
+        self.chunk.check_invariants();
+        for index in 1..self.len() {
+            assert!(
+                self.chunk[index - 1].0 < self.chunk[index].0,
+                "ChunkMap::check_invariants: key {} is not greater than the key before it",
+                index
+            );
+        }
+    }
+
+    /// Find the index of `key`, or the index where it would be inserted.
+    fn search<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.chunk
+            .binary_search_by(|(probe, _)| probe.borrow().cmp(key))
+    }
+
+    /// Get a reference to the value for `key`.
+    ///
+    /// Time: O(log n)
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Get references to the key and the value for `key`.
+    ///
+    /// Time: O(log n)
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(index) => {
+                let (key, value) = &self.chunk[index];
+                Some((key, value))
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Get a mutable reference to the value for `key`.
+    ///
+    /// Time: O(log n)
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(index) => Some(&mut self.chunk[index].1),
+            Err(_) => None,
+        }
+    }
+
+    /// Test if the map contains `key`.
+    ///
+    /// Time: O(log n)
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(key).is_ok()
+    }
+
+    /// Insert a value for `key`, returning the previous value if the key was
+    /// already in the map.
+    ///
+    /// Panics if the key isn't in the map and the map is full.
+    ///
+    /// Time: O(n)
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.try_insert(key, value) {
+            Ok(previous) => previous,
+            Err(_) => panic!("ChunkMap::insert: map is full"),
+        }
+    }
+
+    /// Insert a value for `key`, returning the previous value if the key was
+    /// already in the map.
+    ///
+    /// If the key isn't in the map and the map is full, you get the key and
+    /// the value back as the error.
+    ///
+    /// Time: O(n)
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        match self.search(&key) {
+            Ok(index) => Ok(Some(replace(&mut self.chunk[index].1, value))),
+            Err(_) if self.is_full() => Err((key, value)),
+            Err(index) => {
+                self.chunk.insert(index, (key, value));
+                debug_invariants!(self);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Remove `key` from the map, returning its value if it was there.
+    ///
+    /// Time: O(n)
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Remove `key` from the map, returning the stored key and its value if
+    /// it was there.
+    ///
+    /// Time: O(n)
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.search(key) {
+            Ok(index) => {
+                let entry = self.chunk.remove(index);
+                debug_invariants!(self);
+                Some(entry)
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Get the entry for `key`, for in place updates.
+    ///
+    /// Time: O(log n)
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, N> {
+        match self.search(&key) {
+            Ok(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            Err(index) => Entry::Vacant(VacantEntry {
+                map: self,
+                key,
+                index,
+            }),
+        }
+    }
+
+    /// Find the indices of the entries in `range`.
+    ///
+    /// Panics if the range starts after it ends, or if it starts and ends at
+    /// the same key and excludes both.
+    fn range_indices<Q, R>(&self, range: &R) -> (usize, usize)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let start_key = match range.start_bound() {
+            Bound::Included(key) | Bound::Excluded(key) => Some(key),
+            Bound::Unbounded => None,
+        };
+        let end_key = match range.end_bound() {
+            Bound::Included(key) | Bound::Excluded(key) => Some(key),
+            Bound::Unbounded => None,
+        };
+        if let (Some(start), Some(end)) = (start_key, end_key) {
+            match start.cmp(end) {
+                Ordering::Greater => {
+                    panic!("ChunkMap::range: range start is greater than range end")
+                }
+                Ordering::Equal => {
+                    if let (Bound::Excluded(_), Bound::Excluded(_)) =
+                        (range.start_bound(), range.end_bound())
+                    {
+                        panic!("ChunkMap::range: range start and end are equal and excluded")
+                    }
+                }
+                Ordering::Less => {}
+            }
+        }
+        let start = match range.start_bound() {
+            Bound::Included(key) => self.search(key).unwrap_or_else(|index| index),
+            Bound::Excluded(key) => match self.search(key) {
+                Ok(index) => index + 1,
+                Err(index) => index,
+            },
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => match self.search(key) {
+                Ok(index) => index + 1,
+                Err(index) => index,
+            },
+            Bound::Excluded(key) => self.search(key).unwrap_or_else(|index| index),
+            Bound::Unbounded => self.len(),
+        };
+        (start, end)
+    }
+
+    /// Get an iterator over the entries with keys in `range`, in key order.
+    ///
+    /// Panics if the range starts after it ends, or if it starts and ends at
+    /// the same key and excludes both.
+    ///
+    /// Time: O(log n)
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = self.range_indices(&range);
+        Iter {
+            inner: self.chunk[start..end].iter(),
+        }
+    }
+
+    /// Get an iterator over the entries with keys in `range`, in key order,
+    /// with mutable references to the values.
+    ///
+    /// Panics if the range starts after it ends, or if it starts and ends at
+    /// the same key and excludes both.
+    ///
+    /// Time: O(log n)
+    pub fn range_mut<Q, R>(&mut self, range: R) -> IterMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let (start, end) = self.range_indices(&range);
+        IterMut {
+            inner: self.chunk[start..end].iter_mut(),
+        }
+    }
+
+    /// Split the map in two at `key`, leaving the entries with lesser keys in
+    /// `self` and returning a new map with the rest, including `key` itself.
+    ///
+    /// Time: O(n) for the number of entries in the new map
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.search(key).unwrap_or_else(|index| index);
+        ChunkMap {
+            chunk: self.chunk.split_off(index),
+        }
+    }
+}
+
+impl<K, V, const N: usize> Default for ChunkMap<K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const N: usize> Clone for ChunkMap<K, V, N>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        ChunkMap {
+            chunk: self.chunk.clone(),
+        }
+    }
+}
+
+impl<K, V, const N: usize> Debug for ChunkMap<K, V, N>
+where
+    K: Debug,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("ChunkMap")?;
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, const N: usize> Hash for ChunkMap<K, V, N>
+where
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        for item in self.chunk.iter() {
+            item.hash(hasher)
+        }
+    }
+}
+
+impl<K, V, const N: usize> PartialEq for ChunkMap<K, V, N>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.chunk.as_slice() == other.chunk.as_slice()
+    }
+}
+
+impl<K, V, const N: usize> Eq for ChunkMap<K, V, N>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V, const N: usize> PartialOrd for ChunkMap<K, V, N>
+where
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.chunk.iter().partial_cmp(other.chunk.iter())
+    }
+}
+
+impl<K, V, const N: usize> Ord for ChunkMap<K, V, N>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.chunk.iter().cmp(other.chunk.iter())
+    }
+}
+
+impl<K, Q, V, const N: usize> Index<&Q> for ChunkMap<K, V, N>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    /// Get a reference to the value for `key`.
+    ///
+    /// Panics if the key isn't in the map.
+    fn index(&self, key: &Q) -> &Self::Output {
+        match self.get(key) {
+            Some(value) => value,
+            None => panic!("ChunkMap::index: key not found"),
+        }
+    }
+}
+
+impl<K, Q, V, const N: usize> IndexMut<&Q> for ChunkMap<K, V, N>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    /// Get a mutable reference to the value for `key`.
+    ///
+    /// Panics if the key isn't in the map.
+    fn index_mut(&mut self, key: &Q) -> &mut Self::Output {
+        match self.get_mut(key) {
+            Some(value) => value,
+            None => panic!("ChunkMap::index_mut: key not found"),
+        }
+    }
+}
+
+impl<K, V, const N: usize> FromIterator<(K, V)> for ChunkMap<K, V, N>
+where
+    K: Ord,
+{
+    /// Collect the entries into a map. Like `BTreeMap`, a key which appears
+    /// more than once ends up with the last value it was paired with.
+    ///
+    /// Panics if there are more than `N` distinct keys.
+    fn from_iter<I>(it: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = Self::new();
+        map.extend(it);
+        map
+    }
+}
+
+impl<K, V, const N: usize> Extend<(K, V)> for ChunkMap<K, V, N>
+where
+    K: Ord,
+{
+    /// Insert the contents of the iterator into the map.
+    ///
+    /// Panics if the map exceeds its capacity.
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in it {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V, const N: usize> IntoIterator for &'a ChunkMap<K, V, N> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, const N: usize> IntoIterator for &'a mut ChunkMap<K, V, N> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, const N: usize> IntoIterator for ChunkMap<K, V, N> {
+    type Item = (K, V);
+    type IntoIter = ChunkIter<(K, V), N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.chunk.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = ChunkMap::<i32, i32, 8>::new();
+        let mut model = BTreeMap::new();
+        for index in 0..40 {
+            let key = (index * 5) % 11;
+            if index % 3 == 0 {
+                assert_eq!(model.remove(&key), map.remove(&key));
+            } else if model.len() < 8 || model.contains_key(&key) {
+                assert_eq!(model.insert(key, index), map.insert(key, index));
+            } else {
+                assert_eq!(Err((key, index)), map.try_insert(key, index));
+            }
+            map.check_invariants();
+            assert!(map.iter().eq(model.iter()));
+            for key in 0..11 {
+                assert_eq!(model.get(&key), map.get(&key));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkMap::insert: map is full")]
+    fn insert_when_full() {
+        let mut map: ChunkMap<i32, (), 2> = vec![(1, ()), (2, ())].into_iter().collect();
+        // Replacing a value is fine.
+        map.insert(1, ());
+        map.insert(3, ());
+    }
+
+    #[test]
+    fn first_and_last() {
+        let mut map: ChunkMap<i32, char, 8> =
+            vec![(3, 'c'), (1, 'a'), (2, 'b')].into_iter().collect();
+        assert_eq!(Some((&1, &'a')), map.first_key_value());
+        assert_eq!(Some((&3, &'c')), map.last_key_value());
+        assert_eq!(Some((1, 'a')), map.pop_first());
+        assert_eq!(Some((3, 'c')), map.pop_last());
+        assert_eq!(Some((2, 'b')), map.pop_last());
+        assert_eq!(None, map.pop_first());
+    }
+
+    #[test]
+    fn range() {
+        let map: ChunkMap<i32, i32, 16> = (0..10).map(|i| (i * 2, i)).collect();
+        let model: BTreeMap<i32, i32> = map.clone().into_iter().collect();
+        for start in -1..21 {
+            for end in start..21 {
+                assert!(map.range(start..end).eq(model.range(start..end)));
+                assert!(map.range(start..=end).eq(model.range(start..=end)));
+                let bounds = (Bound::Excluded(start), Bound::Included(end));
+                assert!(map.range(bounds).eq(model.range(bounds)));
+            }
+            assert!(map.range(start..).eq(model.range(start..)));
+            assert!(map.range(..start).rev().eq(model.range(..start).rev()));
+        }
+        assert_eq!(10, map.range::<i32, _>(..).len());
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkMap::range: range start is greater than range end")]
+    fn range_backwards() {
+        let map = ChunkMap::<i32, i32, 4>::new();
+        map.range((Bound::Included(3), Bound::Excluded(2)));
+    }
+
+    #[test]
+    fn range_mut() {
+        let mut map: ChunkMap<i32, i32, 8> = (0..6).map(|i| (i, i)).collect();
+        for (_, value) in map.range_mut(2..4) {
+            *value *= 10;
+        }
+        assert!(map.values().eq(&[0, 1, 20, 30, 4, 5]));
+    }
+
+    #[test]
+    fn entry() {
+        let mut map = ChunkMap::<&str, i32, 4>::new();
+        for word in "a b a c b a".split(' ') {
+            *map.entry(word).or_default() += 1;
+        }
+        assert!(map.iter().eq(vec![(&"a", &3), (&"b", &2), (&"c", &1)]));
+        match map.entry("b") {
+            Entry::Occupied(entry) => assert_eq!(("b", 2), entry.remove_entry()),
+            Entry::Vacant(_) => panic!("b should be in the map"),
+        }
+        map.entry("c").and_modify(|value| *value = 7).or_insert(0);
+        map.entry("d").and_modify(|value| *value = 7).or_insert(0);
+        map.entry("e").or_insert(5);
+        assert!(map.is_full());
+        match map.entry("f") {
+            Entry::Vacant(entry) => assert_eq!(Err(("f", 6)), entry.try_insert(6)),
+            Entry::Occupied(_) => panic!("f shouldn't be in the map"),
+        }
+        assert!(map
+            .iter()
+            .eq(vec![(&"a", &3), (&"c", &7), (&"d", &0), (&"e", &5)]));
+    }
+
+    #[test]
+    fn split_off() {
+        let mut map: ChunkMap<i32, i32, 8> = (0..6).map(|i| (i * 2, i)).collect();
+        let right = map.split_off(&5);
+        assert!(map.keys().eq(&[0, 2, 4]));
+        assert!(right.keys().eq(&[6, 8, 10]));
+        let mut right = right;
+        let rest = right.split_off(&6);
+        assert!(right.is_empty());
+        assert_eq!(3, rest.len());
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let mut map = ChunkMap::<String, usize, 4>::new();
+        map.insert("hello".to_string(), 1);
+        map["hello"] += 1;
+        assert_eq!(2, map["hello"]);
+        assert!(map.contains_key("hello"));
+        assert_eq!(Some(2), map.remove("hello"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "ChunkMap::check_invariants: key 1 is not greater than the key before it"
+    )]
+    fn check_invariants() {
+        let map = ChunkMap {
+            chunk: Chunk::<(i32, ()), 4>::pair((1, ()), (1, ())),
+        };
+        map.check_invariants();
+    }
+}
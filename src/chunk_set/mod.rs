@@ -0,0 +1,544 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A fixed capacity sorted set.
+//!
+//! See [`ChunkSet`](struct.ChunkSet.html)
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Error, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::RangeBounds;
+
+use crate::chunk_map::{ChunkMap, Keys};
+use crate::sized_chunk::Iter as ChunkIter;
+
+/// A fixed capacity sorted set.
+///
+/// A set of up to `N` values, stored in order in a
+/// [`ChunkMap<K, (), N>`][ChunkMap], which is in turn a sorted
+/// [`Chunk`][Chunk]. Its API follows `std::collections::BTreeSet`, with O(log n)
+/// lookups and O(n) inserts and removals.
+///
+/// Like the rest of the crate, it panics rather than grows when you insert
+/// more than it can hold. Use [`try_insert`][try_insert] if you'd rather be
+/// told.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sized_chunks::ChunkSet;
+/// let mut set: ChunkSet<i32, 8> = vec![5, 1, 3].into_iter().collect();
+/// assert!(set.insert(2));
+/// assert!(!set.insert(3));
+/// assert!(set.iter().eq(&[1, 2, 3, 5]));
+/// assert!(set.range(2..5).eq(&[2, 3]));
+/// let high = set.split_off(&3);
+/// assert!(high.iter().eq(&[3, 5]));
+/// ```
+///
+/// [Chunk]: ../sized_chunk/type.Chunk.html
+/// [ChunkMap]: ../chunk_map/struct.ChunkMap.html
+/// [try_insert]: #method.try_insert
+pub struct ChunkSet<K, const N: usize> {
+    map: ChunkMap<K, (), N>,
+}
+
+impl<K, const N: usize> ChunkSet<K, N> {
+    /// The maximum number of values this `ChunkSet` can contain.
+    pub const CAPACITY: usize = N;
+
+    /// Construct a new empty set.
+    pub fn new() -> Self {
+        ChunkSet {
+            map: ChunkMap::new(),
+        }
+    }
+
+    /// Get the number of values in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Test if the set is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Test if the set is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.map.is_full()
+    }
+
+    /// Get the least value in the set.
+    pub fn first(&self) -> Option<&K> {
+        self.map.first_key_value().map(|(key, _)| key)
+    }
+
+    /// Get the greatest value in the set.
+    pub fn last(&self) -> Option<&K> {
+        self.map.last_key_value().map(|(key, _)| key)
+    }
+
+    /// Remove and return the least value in the set.
+    ///
+    /// Time: O(1)
+    pub fn pop_first(&mut self) -> Option<K> {
+        self.map.pop_first().map(|(key, _)| key)
+    }
+
+    /// Remove and return the greatest value in the set.
+    ///
+    /// Time: O(1)
+    pub fn pop_last(&mut self) -> Option<K> {
+        self.map.pop_last().map(|(key, _)| key)
+    }
+
+    /// Get an iterator over the values in the set, in order.
+    pub fn iter(&self) -> Iter<'_, K> {
+        Iter {
+            inner: self.map.keys(),
+        }
+    }
+
+    /// Discard the contents of the set.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl<K, const N: usize> ChunkSet<K, N>
+where
+    K: Ord,
+{
+    /// Check the set's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of sets.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the set.
+    ///
+    /// Time: O(n)
+    pub fn check_invariants(&self) {
+        self.map.check_invariants();
+    }
+
+    /// Test if the set contains `value`.
+    ///
+    /// Time: O(log n)
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Get a reference to the value in the set which is equal to `value`.
+    ///
+    /// Time: O(log n)
+    pub fn get<Q>(&self, value: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.get_key_value(value).map(|(key, _)| key)
+    }
+
+    /// Insert a value into the set, returning `false` if it was already
+    /// there.
+    ///
+    /// Panics if the value isn't in the set and the set is full.
+    ///
+    /// Time: O(n)
+    pub fn insert(&mut self, value: K) -> bool {
+        match self.try_insert(value) {
+            Ok(inserted) => inserted,
+            Err(_) => panic!("ChunkSet::insert: set is full"),
+        }
+    }
+
+    /// Insert a value into the set, returning `false` if it was already
+    /// there.
+    ///
+    /// If the value isn't in the set and the set is full, you get the value
+    /// back as the error.
+    ///
+    /// Time: O(n)
+    pub fn try_insert(&mut self, value: K) -> Result<bool, K> {
+        match self.map.try_insert(value, ()) {
+            Ok(previous) => Ok(previous.is_none()),
+            Err((value, ())) => Err(value),
+        }
+    }
+
+    /// Remove `value` from the set, returning `true` if it was there.
+    ///
+    /// Time: O(n)
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Remove `value` from the set, returning the stored value if it was
+    /// there.
+    ///
+    /// Time: O(n)
+    pub fn take<Q>(&mut self, value: &Q) -> Option<K>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove_entry(value).map(|(key, _)| key)
+    }
+
+    /// Get an iterator over the values in `range`, in order.
+    ///
+    /// Panics if the range starts after it ends, or if it starts and ends at
+    /// the same value and excludes both.
+    ///
+    /// Time: O(log n)
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Iter {
+            inner: Keys {
+                inner: self.map.range(range).inner,
+            },
+        }
+    }
+
+    /// Split the set in two at `value`, leaving the lesser values in `self`
+    /// and returning a new set with the rest, including `value` itself.
+    ///
+    /// Time: O(n) for the number of values in the new set
+    pub fn split_off<Q>(&mut self, value: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        ChunkSet {
+            map: self.map.split_off(value),
+        }
+    }
+
+    /// Test if `self` and `other` have no values in common.
+    ///
+    /// Time: O(n + m)
+    pub fn is_disjoint<const M: usize>(&self, other: &ChunkSet<K, M>) -> bool {
+        let mut left = self.iter().peekable();
+        let mut right = other.iter().peekable();
+        while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+            match a.cmp(b) {
+                Ordering::Less => {
+                    left.next();
+                }
+                Ordering::Greater => {
+                    right.next();
+                }
+                Ordering::Equal => return false,
+            }
+        }
+        true
+    }
+
+    /// Test if every value in `self` is also in `other`.
+    ///
+    /// Time: O(n + m)
+    pub fn is_subset<const M: usize>(&self, other: &ChunkSet<K, M>) -> bool {
+        let mut right = other.iter();
+        'values: for value in self.iter() {
+            for candidate in &mut right {
+                match value.cmp(candidate) {
+                    Ordering::Greater => continue,
+                    Ordering::Equal => continue 'values,
+                    Ordering::Less => return false,
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    /// Test if every value in `other` is also in `self`.
+    ///
+    /// Time: O(n + m)
+    pub fn is_superset<const M: usize>(&self, other: &ChunkSet<K, M>) -> bool {
+        other.is_subset(self)
+    }
+}
+
+impl<K, const N: usize> Default for ChunkSet<K, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, const N: usize> Clone for ChunkSet<K, N>
+where
+    K: Clone,
+{
+    fn clone(&self) -> Self {
+        ChunkSet {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<K, const N: usize> Debug for ChunkSet<K, N>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("ChunkSet")?;
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<K, const N: usize> Hash for ChunkSet<K, N>
+where
+    K: Hash,
+{
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        for value in self {
+            value.hash(hasher)
+        }
+    }
+}
+
+impl<K, const N: usize> PartialEq for ChunkSet<K, N>
+where
+    K: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K, const N: usize> Eq for ChunkSet<K, N> where K: Eq {}
+
+impl<K, const N: usize> PartialOrd for ChunkSet<K, N>
+where
+    K: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K, const N: usize> Ord for ChunkSet<K, N>
+where
+    K: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K, const N: usize> FromIterator<K> for ChunkSet<K, N>
+where
+    K: Ord,
+{
+    /// Collect the values into a set.
+    ///
+    /// Panics if there are more than `N` distinct values.
+    fn from_iter<I>(it: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut set = Self::new();
+        set.extend(it);
+        set
+    }
+}
+
+impl<K, const N: usize> Extend<K> for ChunkSet<K, N>
+where
+    K: Ord,
+{
+    /// Insert the contents of the iterator into the set.
+    ///
+    /// Panics if the set exceeds its capacity.
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = K>,
+    {
+        for value in it {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, K, const N: usize> IntoIterator for &'a ChunkSet<K, N> {
+    type Item = &'a K;
+    type IntoIter = Iter<'a, K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, const N: usize> IntoIterator for ChunkSet<K, N> {
+    type Item = K;
+    type IntoIter = IntoIter<K, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the values of a `ChunkSet`, in order.
+pub struct Iter<'a, K> {
+    inner: Keys<'a, K, ()>,
+}
+
+impl<'a, K> Iterator for Iter<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K> DoubleEndedIterator for Iter<'a, K> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<'a, K> ExactSizeIterator for Iter<'a, K> {}
+
+impl<'a, K> FusedIterator for Iter<'a, K> {}
+
+/// A consuming iterator over the values of a `ChunkSet`, in order.
+pub struct IntoIter<K, const N: usize> {
+    inner: ChunkIter<(K, ()), N>,
+}
+
+impl<K, const N: usize> Iterator for IntoIter<K, N> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, const N: usize> DoubleEndedIterator for IntoIter<K, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K, const N: usize> ExactSizeIterator for IntoIter<K, N> {}
+
+impl<K, const N: usize> FusedIterator for IntoIter<K, N> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set = ChunkSet::<u8, 8>::new();
+        let mut model = BTreeSet::new();
+        for index in 0..50 {
+            let value = ((index * 7) % 13) as u8;
+            if index % 4 == 0 {
+                assert_eq!(model.remove(&value), set.remove(&value));
+            } else if model.len() < 8 || model.contains(&value) {
+                assert_eq!(model.insert(value), set.insert(value));
+            } else {
+                assert_eq!(Err(value), set.try_insert(value));
+            }
+            set.check_invariants();
+            assert!(set.iter().eq(model.iter()));
+            assert_eq!(model.iter().next(), set.first());
+            assert_eq!(model.iter().next_back(), set.last());
+        }
+        assert!(set.into_iter().rev().eq(model.into_iter().rev()));
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkSet::insert: set is full")]
+    fn insert_when_full() {
+        let mut set: ChunkSet<i32, 2> = vec![1, 2].into_iter().collect();
+        set.insert(3);
+    }
+
+    #[test]
+    fn subsets() {
+        let small: ChunkSet<i32, 4> = vec![2, 4].into_iter().collect();
+        let large: ChunkSet<i32, 8> = vec![1, 2, 3, 4, 5].into_iter().collect();
+        let other: ChunkSet<i32, 4> = vec![0, 3, 6].into_iter().collect();
+        assert!(small.is_subset(&large));
+        assert!(!large.is_subset(&small));
+        assert!(large.is_superset(&small));
+        assert!(!other.is_subset(&large));
+        assert!(small.is_disjoint(&other));
+        assert!(!large.is_disjoint(&other));
+        assert!(ChunkSet::<i32, 1>::new().is_subset(&small));
+    }
+
+    #[test]
+    fn take_and_get() {
+        let mut set: ChunkSet<String, 4> =
+            vec!["a".to_string(), "b".to_string()].into_iter().collect();
+        assert_eq!(Some(&"a".to_string()), set.get("a"));
+        assert!(set.contains("b"));
+        assert_eq!(Some("b".to_string()), set.take("b"));
+        assert_eq!(None, set.take("b"));
+        assert_eq!(1, set.len());
+    }
+
+    #[test]
+    fn range() {
+        let set: ChunkSet<i32, 16> = (0..10).map(|i| i * 2).collect();
+        let model: BTreeSet<i32> = set.clone().into_iter().collect();
+        for start in -1..21 {
+            for end in start..21 {
+                assert!(set.range(start..end).eq(model.range(start..end)));
+                assert!(set.range(start..=end).eq(model.range(start..=end)));
+            }
+            assert!(set.range(..start).rev().eq(model.range(..start).rev()));
+        }
+    }
+
+    #[test]
+    fn split_off() {
+        let mut set: ChunkSet<i32, 8> = (0..6).map(|i| i * 2).collect();
+        let mut right = set.split_off(&5);
+        assert!(set.iter().eq(&[0, 2, 4]));
+        assert!(right.iter().eq(&[6, 8, 10]));
+        let rest = right.split_off(&6);
+        assert!(right.is_empty());
+        assert!(rest.iter().eq(&[6, 8, 10]));
+    }
+}
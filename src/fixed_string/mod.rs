@@ -0,0 +1,480 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A fixed capacity UTF-8 string.
+//!
+//! See [`FixedString`](struct.FixedString.html)
+
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display, Error, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{Deref, DerefMut};
+use core::str::{from_utf8_unchecked, from_utf8_unchecked_mut};
+
+use crate::Chunk;
+
+/// A fixed capacity UTF-8 string.
+///
+/// A string of up to `N` bytes stored inline in a [`Chunk<u8, N>`][Chunk],
+/// which is guaranteed to always contain valid UTF-8. It dereferences to a
+/// `&str`, and hashes and compares exactly like one, so it can be used as a
+/// key in a map that you look up using `&str`s.
+///
+/// Like the rest of the crate, it panics rather than grows when you push more
+/// than it can hold. Use [`try_push_str`][try_push_str] if you'd rather be
+/// told, or [`push_str_truncated`][push_str_truncated] if you'd rather keep as
+/// much as fits, cut off on a character boundary.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sized_chunks::FixedString;
+/// # use std::fmt::Write;
+/// let mut string = FixedString::<16>::new();
+/// write!(string, "{} + {} = {}", 2, 2, 4).unwrap();
+/// assert_eq!("2 + 2 = 4", string);
+/// // Only the part which fits is written, and the rest is handed back.
+/// assert_eq!("it ø!", string.push_str_truncated(", ålreit ø!"));
+/// assert_eq!("2 + 2 = 4, ålre", string);
+/// ```
+///
+/// [Chunk]: ../sized_chunk/type.Chunk.html
+/// [try_push_str]: #method.try_push_str
+/// [push_str_truncated]: #method.push_str_truncated
+pub struct FixedString<const N: usize> {
+    chunk: Chunk<u8, N>,
+}
+
+impl<const N: usize> FixedString<N> {
+    /// The maximum number of bytes this `FixedString` can contain.
+    pub const CAPACITY: usize = N;
+
+    /// Construct a new empty string.
+    pub fn new() -> Self {
+        FixedString {
+            chunk: Chunk::new(),
+        }
+    }
+
+    /// Get the length of the string in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.chunk.len()
+    }
+
+    /// Test if the string is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunk.is_empty()
+    }
+
+    /// Test if the string is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.chunk.len() == N
+    }
+
+    /// Get the number of bytes which can still be pushed to the string.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        N - self.chunk.len()
+    }
+
+    /// Get a reference to the contents of the string as a `&str`.
+    pub fn as_str(&self) -> &str {
+        unsafe { from_utf8_unchecked(self.chunk.as_slice()) }
+    }
+
+    /// Get a reference to the contents of the string as a `&mut str`.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        unsafe { from_utf8_unchecked_mut(self.chunk.as_mut_slice()) }
+    }
+
+    /// Get a reference to the underlying bytes of the string.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.chunk.as_slice()
+    }
+
+    /// Get a reference to the underlying `Chunk` of the string.
+    pub fn as_chunk(&self) -> &Chunk<u8, N> {
+        &self.chunk
+    }
+
+    /// Discard the string, returning the underlying `Chunk`.
+    pub fn into_chunk(self) -> Chunk<u8, N> {
+        self.chunk
+    }
+
+    /// Append a character to the end of the string.
+    ///
+    /// Panics if the capacity of the string is exceeded.
+    ///
+    /// Time: O(1)
+    pub fn push(&mut self, ch: char) {
+        if self.try_push(ch).is_err() {
+            panic!("FixedString::push: can't push to full string");
+        }
+    }
+
+    /// Append a character to the end of the string.
+    ///
+    /// Returns the character back if there isn't room for it.
+    ///
+    /// Time: O(1)
+    pub fn try_push(&mut self, ch: char) -> Result<(), char> {
+        let mut buf = [0; 4];
+        let encoded = ch.encode_utf8(&mut buf);
+        if encoded.len() > self.remaining() {
+            return Err(ch);
+        }
+        self.chunk.extend(encoded.as_bytes());
+        Ok(())
+    }
+
+    /// Append a string slice to the end of the string.
+    ///
+    /// Panics if the capacity of the string is exceeded.
+    ///
+    /// Time: O(n) for the length of `string`
+    pub fn push_str(&mut self, string: &str) {
+        if self.try_push_str(string).is_err() {
+            panic!("FixedString::push_str: string size overflow");
+        }
+    }
+
+    /// Append a string slice to the end of the string.
+    ///
+    /// If there isn't room for all of `string`, nothing is appended and
+    /// `string` is returned back as the error.
+    ///
+    /// Time: O(n) for the length of `string`
+    pub fn try_push_str<'a>(&mut self, string: &'a str) -> Result<(), &'a str> {
+        if string.len() > self.remaining() {
+            return Err(string);
+        }
+        self.chunk.extend(string.as_bytes());
+        Ok(())
+    }
+
+    /// Append as much of a string slice as fits to the end of the string,
+    /// stopping at the last character boundary which fits.
+    ///
+    /// Returns the part of `string` which didn't fit, which is empty if all of
+    /// it did.
+    ///
+    /// Time: O(n) for the length of `string`
+    pub fn push_str_truncated<'a>(&mut self, string: &'a str) -> &'a str {
+        let mut split = string.len().min(self.remaining());
+        while !string.is_char_boundary(split) {
+            split -= 1;
+        }
+        let (head, tail) = string.split_at(split);
+        self.chunk.extend(head.as_bytes());
+        tail
+    }
+
+    /// Remove the last character from the string and return it.
+    ///
+    /// Returns `None` if the string is empty.
+    ///
+    /// Time: O(1)
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.chunk.drop_right(self.len() - ch.len_utf8());
+        Some(ch)
+    }
+
+    /// Shorten the string to `new_len` bytes.
+    ///
+    /// Does nothing if `new_len` is greater than the string's current length.
+    ///
+    /// Panics if `new_len` does not lie on a character boundary.
+    ///
+    /// Time: O(1)
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            if !self.as_str().is_char_boundary(new_len) {
+                panic!("FixedString::truncate: new_len is not a character boundary");
+            }
+            self.chunk.drop_right(new_len);
+        }
+    }
+
+    /// Discard the contents of the string.
+    ///
+    /// Time: O(1)
+    pub fn clear(&mut self) {
+        self.chunk.clear();
+    }
+}
+
+impl<const N: usize> Default for FixedString<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Clone for FixedString<N> {
+    fn clone(&self) -> Self {
+        FixedString {
+            chunk: self.chunk.clone(),
+        }
+    }
+}
+
+impl<const N: usize> Deref for FixedString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> DerefMut for FixedString<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> Borrow<str> for FixedString<N> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> BorrowMut<str> for FixedString<N> {
+    fn borrow_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for FixedString<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for FixedString<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize> Debug for FixedString<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> Display for FixedString<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> Hash for FixedString<N> {
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        self.as_str().hash(hasher)
+    }
+}
+
+impl<const N: usize> PartialEq for FixedString<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<str> for FixedString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, const N: usize> PartialEq<&'a str> for FixedString<N> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> PartialEq<FixedString<N>> for str {
+    fn eq(&self, other: &FixedString<N>) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<FixedString<N>> for &str {
+    fn eq(&self, other: &FixedString<N>) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for FixedString<N> {}
+
+impl<const N: usize> PartialOrd for FixedString<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for FixedString<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Write for FixedString<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| Error)
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.try_push(c).map_err(|_| Error)
+    }
+}
+
+impl<'a, const N: usize> From<&'a str> for FixedString<N> {
+    /// Construct a string by copying a string slice.
+    ///
+    /// Panics if the string slice doesn't fit.
+    fn from(string: &'a str) -> Self {
+        let mut out = Self::new();
+        out.push_str(string);
+        out
+    }
+}
+
+impl<const N: usize> FromIterator<char> for FixedString<N> {
+    fn from_iter<I>(it: I) -> Self
+    where
+        I: IntoIterator<Item = char>,
+    {
+        let mut out = Self::new();
+        out.extend(it);
+        out
+    }
+}
+
+impl<const N: usize> Extend<char> for FixedString<N> {
+    /// Append the contents of the iterator to the end of the string.
+    ///
+    /// Panics if the string exceeds its capacity.
+    ///
+    /// Time: O(n) for the length of the iterator
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = char>,
+    {
+        for ch in it {
+            self.push(ch);
+        }
+    }
+}
+
+impl<'a, const N: usize> Extend<&'a str> for FixedString<N> {
+    /// Append the contents of the iterator to the end of the string.
+    ///
+    /// Panics if the string exceeds its capacity.
+    ///
+    /// Time: O(n) for the total length of the strings
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        for string in it {
+            self.push_str(string);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::fmt::Write;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+
+    #[test]
+    fn push_and_pop() {
+        let mut string = FixedString::<8>::new();
+        string.push_str("ab");
+        string.push('ç');
+        assert_eq!("abç", string);
+        assert_eq!(4, string.len());
+        assert_eq!(Some('ç'), string.pop());
+        assert_eq!(Some('b'), string.pop());
+        assert_eq!("a", string);
+    }
+
+    #[test]
+    fn try_push_str_is_all_or_nothing() {
+        let mut string = FixedString::<4>::from("abc");
+        assert_eq!(Err("de"), string.try_push_str("de"));
+        assert_eq!("abc", string);
+        assert_eq!(Err('é'), string.try_push('é'));
+        assert_eq!(Ok(()), string.try_push_str("d"));
+        assert!(string.is_full());
+    }
+
+    #[test]
+    #[should_panic(expected = "FixedString::push_str: string size overflow")]
+    fn push_str_overflow() {
+        let mut string = FixedString::<4>::new();
+        string.push_str("abcde");
+    }
+
+    #[test]
+    fn push_str_truncated_on_char_boundary() {
+        let mut string = FixedString::<5>::from("ab");
+        assert_eq!("€", string.push_str_truncated("c€"));
+        assert_eq!("abc", string);
+        assert_eq!("", string.push_str_truncated("d"));
+        assert_eq!("abcd", string);
+    }
+
+    #[test]
+    fn truncate() {
+        let mut string = FixedString::<8>::from("añb");
+        string.truncate(10);
+        assert_eq!("añb", string);
+        string.truncate(3);
+        assert_eq!("añ", string);
+    }
+
+    #[test]
+    #[should_panic(expected = "FixedString::truncate: new_len is not a character boundary")]
+    fn truncate_inside_char() {
+        let mut string = FixedString::<8>::from("añb");
+        string.truncate(2);
+    }
+
+    #[test]
+    fn fmt_write() {
+        let mut string = FixedString::<8>::new();
+        write!(string, "{}:{}", 12, 34).unwrap();
+        assert_eq!("12:34", string);
+        assert!(string.write_str("long").is_err());
+        assert_eq!("12:34", string);
+        assert_eq!("12:34", format!("{}", string));
+        assert_eq!("\"12:34\"", format!("{:?}", string));
+    }
+
+    #[test]
+    fn hash_and_borrow_match_str() {
+        let hash = |value: &dyn Fn(&mut DefaultHasher)| {
+            let mut hasher = DefaultHasher::new();
+            value(&mut hasher);
+            hasher.finish()
+        };
+        let string = FixedString::<16>::from("hello");
+        assert_eq!(hash(&|h| "hello".hash(h)), hash(&|h| string.hash(h)));
+        let mut set = HashSet::new();
+        set.insert(string);
+        assert!(set.contains("hello"));
+    }
+}
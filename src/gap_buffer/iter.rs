@@ -0,0 +1,131 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use core::iter::FusedIterator;
+use core::ptr;
+use core::slice::{Iter as SliceIter, IterMut as SliceIterMut};
+
+use super::GapBuffer;
+
+/// A reference iterator over a `GapBuffer`.
+pub struct Iter<'a, A> {
+    pub(crate) before: SliceIter<'a, A>,
+    pub(crate) after: SliceIter<'a, A>,
+}
+
+impl<'a, A> Iterator for Iter<'a, A> {
+    type Item = &'a A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.before.next().or_else(|| self.after.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.before.len() + self.after.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, A> DoubleEndedIterator for Iter<'a, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.after.next_back().or_else(|| self.before.next_back())
+    }
+}
+
+impl<'a, A> ExactSizeIterator for Iter<'a, A> {}
+
+impl<'a, A> FusedIterator for Iter<'a, A> {}
+
+/// A mutable reference iterator over a `GapBuffer`.
+pub struct IterMut<'a, A> {
+    pub(crate) before: SliceIterMut<'a, A>,
+    pub(crate) after: SliceIterMut<'a, A>,
+}
+
+impl<'a, A> Iterator for IterMut<'a, A> {
+    type Item = &'a mut A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.before.next() {
+            None => self.after.next(),
+            value => value,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.before.len() + self.after.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, A> DoubleEndedIterator for IterMut<'a, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.after.next_back() {
+            None => self.before.next_back(),
+            value => value,
+        }
+    }
+}
+
+impl<'a, A> ExactSizeIterator for IterMut<'a, A> {}
+
+impl<'a, A> FusedIterator for IterMut<'a, A> {}
+
+/// A consuming iterator over a `GapBuffer`.
+///
+/// The gap is moved to the end of the buffer when the iterator is
+/// constructed, so the remaining items are always the ones between `front`
+/// and the cursor.
+pub struct OwnedIter<A, const N: usize> {
+    pub(crate) buffer: GapBuffer<A, N>,
+    pub(crate) front: usize,
+}
+
+impl<A, const N: usize> Iterator for OwnedIter<A, N> {
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.buffer.gap_start {
+            None
+        } else {
+            let value = unsafe { self.buffer.ptr(self.front).read() };
+            self.front += 1;
+            Some(value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.buffer.gap_start - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<A, const N: usize> DoubleEndedIterator for OwnedIter<A, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.buffer.gap_start {
+            None
+        } else {
+            self.buffer.remove_before()
+        }
+    }
+}
+
+impl<A, const N: usize> ExactSizeIterator for OwnedIter<A, N> {}
+
+impl<A, const N: usize> FusedIterator for OwnedIter<A, N> {}
+
+impl<A, const N: usize> Drop for OwnedIter<A, N> {
+    fn drop(&mut self) {
+        let remaining = self.buffer.gap_start - self.front;
+        // Empty the buffer first, so it doesn't drop anything again if one of
+        // the remaining items panics while being dropped.
+        self.buffer.gap_start = 0;
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.buffer.mut_ptr(self.front),
+                remaining,
+            ))
+        }
+    }
+}
@@ -0,0 +1,797 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A fixed capacity gap buffer.
+//!
+//! See [`GapBuffer`](struct.GapBuffer.html)
+
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Error, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::mem::{replace, MaybeUninit};
+use core::ops::{Index, IndexMut};
+use core::ptr;
+use core::slice::{from_raw_parts, from_raw_parts_mut};
+
+mod iter;
+pub use self::iter::{Iter, IterMut, OwnedIter};
+
+/// A fixed capacity gap buffer.
+///
+/// An inline array of items with a variable length but a fixed, preallocated
+/// capacity given by the `N` type, and a cursor which sits between two items
+/// (or at either end).
+///
+/// The free space in the buffer is kept at the cursor, as a gap between the
+/// items before it, which sit at the start of the storage, and the items after
+/// it, which sit at the end. This makes inserting and removing items at the
+/// cursor constant time operations, which is what you want for the
+/// characters or tokens in an editor, where most edits happen right next to
+/// the previous one. Moving the cursor moves the gap with it, which costs
+/// time in proportion to the distance moved.
+///
+/// Because the items are split in two by the gap, a `GapBuffer` can't be
+/// dereferenced into a slice, but [`as_slices`][as_slices] gives you the
+/// items on either side of the cursor as two slices.
+///
+/// Like [`Chunk`][Chunk], it has a fixed capacity and an API geared towards
+/// panics, with `try_` variants for the insertions, which hand the value back
+/// instead of panicking when the buffer is full.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sized_chunks::GapBuffer;
+/// let mut buffer: GapBuffer<char, 64> = "hello world".chars().collect();
+/// // The cursor starts out at the end, after the last item.
+/// assert_eq!(11, buffer.cursor());
+/// buffer.set_cursor(5);
+/// buffer.insert(',');
+/// assert_eq!(6, buffer.cursor());
+/// buffer.remove_after();
+/// buffer.insert_after('_');
+/// let (before, after) = buffer.as_slices();
+/// assert_eq!(&['h', 'e', 'l', 'l', 'o', ','], before);
+/// assert_eq!(&['_', 'w', 'o', 'r', 'l', 'd'], after);
+/// ```
+///
+/// [as_slices]: #method.as_slices
+/// [Chunk]: ../sized_chunk/type.Chunk.html
+pub struct GapBuffer<A, const N: usize> {
+    gap_start: usize,
+    gap_end: usize,
+    data: MaybeUninit<[A; N]>,
+}
+
+impl<A, const N: usize> Drop for GapBuffer<A, N> {
+    fn drop(&mut self) {
+        let (before, after) = self.as_mut_slices();
+        unsafe {
+            ptr::drop_in_place(before);
+            ptr::drop_in_place(after);
+        }
+    }
+}
+
+impl<A, const N: usize> Clone for GapBuffer<A, N>
+where
+    A: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut out = Self::new();
+        // Panic safety: move the edges of the gap past each value as soon as
+        // it's written, so the clone only ever drops what it really holds.
+        // The values after the gap are cloned from the back, so that they
+        // stay at the end of the storage.
+        for index in 0..self.gap_start {
+            unsafe { out.mut_ptr(index).write((*self.ptr(index)).clone()) };
+            out.gap_start = index + 1;
+        }
+        for index in (self.gap_end..N).rev() {
+            unsafe { out.mut_ptr(index).write((*self.ptr(index)).clone()) };
+            out.gap_end = index;
+        }
+        out
+    }
+}
+
+impl<A, const N: usize> GapBuffer<A, N> {
+    /// The maximum number of elements this `GapBuffer` can contain.
+    pub const CAPACITY: usize = N;
+
+    /// Construct a new empty gap buffer.
+    ///
+    /// This is a `const fn`, so you can use it to initialise a `static`.
+    pub const fn new() -> Self {
+        Self {
+            gap_start: 0,
+            gap_end: N,
+            data: MaybeUninit::uninit(),
+        }
+    }
+
+    /// Get the length of the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        N - self.gap_len()
+    }
+
+    /// Test if the buffer is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.gap_len() == N
+    }
+
+    /// Test if the buffer is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.gap_start == self.gap_end
+    }
+
+    /// Get the position of the cursor, which is the number of items before
+    /// it.
+    #[inline]
+    pub fn cursor(&self) -> usize {
+        self.gap_start
+    }
+
+    /// Check the buffer's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of gap
+    /// buffers. With the `debug-invariants` feature enabled, it's called
+    /// after every method which changes the buffer.
+    ///
+    /// Time: O(1)
+    pub fn check_invariants(&self) {
+        assert!(
+            self.gap_start <= self.gap_end,
+            "GapBuffer::check_invariants: gap start {} is past gap end {}",
+            self.gap_start,
+            self.gap_end
+        );
+        assert!(
+            self.gap_end <= N,
+            "GapBuffer::check_invariants: gap end {} is past capacity {}",
+            self.gap_end,
+            N
+        );
+    }
+
+    #[inline]
+    fn gap_len(&self) -> usize {
+        self.gap_end - self.gap_start
+    }
+
+    /// Map a logical index to an index into the storage.
+    #[inline]
+    fn raw(&self, index: usize) -> usize {
+        if index < self.gap_start {
+            index
+        } else {
+            index + self.gap_len()
+        }
+    }
+
+    /// Get a pointer to raw storage index `index`, without bounds checks.
+    #[inline]
+    unsafe fn ptr(&self, index: usize) -> *const A {
+        (&self.data as *const _ as *const A).add(index)
+    }
+
+    /// Get a mutable pointer to raw storage index `index`, without bounds
+    /// checks.
+    #[inline]
+    unsafe fn mut_ptr(&mut self, index: usize) -> *mut A {
+        (&mut self.data as *mut _ as *mut A).add(index)
+    }
+
+    /// Copy a range within the storage.
+    #[inline]
+    unsafe fn force_copy(&mut self, from: usize, to: usize, count: usize) {
+        if count > 0 {
+            let base = self.mut_ptr(0);
+            ptr::copy(base.add(from), base.add(to), count)
+        }
+    }
+
+    /// Move the cursor to `index`, so that it sits before the item currently
+    /// at `index`, or at the end of the buffer if `index` is its length.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(n) for the distance the cursor moves
+    pub fn set_cursor(&mut self, index: usize) {
+        if index > self.len() {
+            panic!("GapBuffer::set_cursor: index out of bounds");
+        }
+        if index < self.gap_start {
+            let count = self.gap_start - index;
+            unsafe { self.force_copy(index, self.gap_end - count, count) };
+            self.gap_start -= count;
+            self.gap_end -= count;
+        } else if index > self.gap_start {
+            let count = index - self.gap_start;
+            unsafe { self.force_copy(self.gap_end, self.gap_start, count) };
+            self.gap_start += count;
+            self.gap_end += count;
+        }
+        debug_invariants!(self);
+    }
+
+    /// Insert a value at the cursor, and move the cursor past it.
+    ///
+    /// Panics if the buffer is full.
+    ///
+    /// Time: O(1)
+    pub fn insert(&mut self, value: A) {
+        if self.try_insert(value).is_err() {
+            panic!("GapBuffer::insert: buffer is full");
+        }
+    }
+
+    /// Insert a value at the cursor, and move the cursor past it.
+    ///
+    /// If the buffer is full, you get the value back as the error.
+    ///
+    /// Time: O(1)
+    pub fn try_insert(&mut self, value: A) -> Result<(), A> {
+        if self.is_full() {
+            return Err(value);
+        }
+        unsafe { self.mut_ptr(self.gap_start).write(value) };
+        self.gap_start += 1;
+        debug_invariants!(self);
+        Ok(())
+    }
+
+    /// Insert a value at the cursor, leaving the cursor before it.
+    ///
+    /// Panics if the buffer is full.
+    ///
+    /// Time: O(1)
+    pub fn insert_after(&mut self, value: A) {
+        if self.try_insert_after(value).is_err() {
+            panic!("GapBuffer::insert_after: buffer is full");
+        }
+    }
+
+    /// Insert a value at the cursor, leaving the cursor before it.
+    ///
+    /// If the buffer is full, you get the value back as the error.
+    ///
+    /// Time: O(1)
+    pub fn try_insert_after(&mut self, value: A) -> Result<(), A> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.gap_end -= 1;
+        unsafe { self.mut_ptr(self.gap_end).write(value) };
+        debug_invariants!(self);
+        Ok(())
+    }
+
+    /// Insert multiple values at the cursor, and move the cursor past them.
+    ///
+    /// Panics if the buffer doesn't have room for all the values.
+    ///
+    /// Time: O(m) for the number of values inserted
+    pub fn insert_from<Iterable, I>(&mut self, iter: Iterable)
+    where
+        Iterable: IntoIterator<Item = A, IntoIter = I>,
+        I: ExactSizeIterator<Item = A>,
+    {
+        let iter = iter.into_iter();
+        let insert_size = iter.len();
+        if insert_size > self.gap_len() {
+            panic!(
+                "GapBuffer::insert_from: buffer cannot fit {} elements",
+                insert_size
+            );
+        }
+        // The buffer is whole after every write, so there's nothing to clean
+        // up if the iterator panics.
+        for value in iter.take(insert_size) {
+            unsafe { self.mut_ptr(self.gap_start).write(value) };
+            self.gap_start += 1;
+        }
+        debug_invariants!(self);
+    }
+
+    /// Remove the value before the cursor, moving the cursor back by one.
+    ///
+    /// Returns `None` if the cursor is at the start of the buffer.
+    ///
+    /// Time: O(1)
+    pub fn remove_before(&mut self) -> Option<A> {
+        if self.gap_start == 0 {
+            return None;
+        }
+        self.gap_start -= 1;
+        let value = unsafe { self.ptr(self.gap_start).read() };
+        debug_invariants!(self);
+        Some(value)
+    }
+
+    /// Remove the value after the cursor.
+    ///
+    /// Returns `None` if the cursor is at the end of the buffer.
+    ///
+    /// Time: O(1)
+    pub fn remove_after(&mut self) -> Option<A> {
+        if self.gap_end == N {
+            return None;
+        }
+        let value = unsafe { self.ptr(self.gap_end).read() };
+        self.gap_end += 1;
+        debug_invariants!(self);
+        Some(value)
+    }
+
+    /// Get a reference to the value at index `index`.
+    ///
+    /// Time: O(1)
+    pub fn get(&self, index: usize) -> Option<&A> {
+        if index >= self.len() {
+            None
+        } else {
+            Some(unsafe { &*self.ptr(self.raw(index)) })
+        }
+    }
+
+    /// Get a mutable reference to the value at index `index`.
+    ///
+    /// Time: O(1)
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut A> {
+        if index >= self.len() {
+            None
+        } else {
+            Some(unsafe { &mut *self.mut_ptr(self.raw(index)) })
+        }
+    }
+
+    /// Update the value at index `index`, returning the old value.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(1)
+    pub fn set(&mut self, index: usize, value: A) -> A {
+        replace(&mut self[index], value)
+    }
+
+    /// Get the items before and after the cursor, as a pair of slices.
+    pub fn as_slices(&self) -> (&[A], &[A]) {
+        unsafe {
+            (
+                from_raw_parts(self.ptr(0), self.gap_start),
+                from_raw_parts(self.ptr(self.gap_end), N - self.gap_end),
+            )
+        }
+    }
+
+    /// Get the items before and after the cursor, as a pair of mutable
+    /// slices.
+    pub fn as_mut_slices(&mut self) -> (&mut [A], &mut [A]) {
+        unsafe {
+            let base = self.mut_ptr(0);
+            (
+                from_raw_parts_mut(base, self.gap_start),
+                from_raw_parts_mut(base.add(self.gap_end), N - self.gap_end),
+            )
+        }
+    }
+
+    /// Get an iterator over references to the items in the buffer, from
+    /// front to back.
+    pub fn iter(&self) -> Iter<'_, A> {
+        let (before, after) = self.as_slices();
+        Iter {
+            before: before.iter(),
+            after: after.iter(),
+        }
+    }
+
+    /// Get an iterator over mutable references to the items in the buffer,
+    /// from front to back.
+    pub fn iter_mut(&mut self) -> IterMut<'_, A> {
+        let (before, after) = self.as_mut_slices();
+        IterMut {
+            before: before.iter_mut(),
+            after: after.iter_mut(),
+        }
+    }
+
+    /// Discard the contents of the buffer.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        let (before, after) = self.as_mut_slices();
+        let (before, after): (*mut [A], *mut [A]) = (before, after);
+        self.gap_start = 0;
+        self.gap_end = N;
+        unsafe {
+            ptr::drop_in_place(before);
+            ptr::drop_in_place(after);
+        }
+        debug_invariants!(self);
+    }
+}
+
+impl<A, const N: usize> Default for GapBuffer<A, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A, const N: usize> Index<usize> for GapBuffer<A, N> {
+    type Output = A;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!(
+                "GapBuffer::index: index out of bounds {} >= {}",
+                index,
+                self.len()
+            ),
+        }
+    }
+}
+
+impl<A, const N: usize> IndexMut<usize> for GapBuffer<A, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let len = self.len();
+        match self.get_mut(index) {
+            Some(value) => value,
+            None => panic!(
+                "GapBuffer::index_mut: index out of bounds {} >= {}",
+                index, len
+            ),
+        }
+    }
+}
+
+impl<A, const N: usize> Debug for GapBuffer<A, N>
+where
+    A: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("GapBuffer")?;
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<A, const N: usize> Hash for GapBuffer<A, N>
+where
+    A: Hash,
+{
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        for item in self {
+            item.hash(hasher)
+        }
+    }
+}
+
+impl<A, const N: usize> PartialEq for GapBuffer<A, N>
+where
+    A: PartialEq,
+{
+    /// Compare the items of two buffers, wherever their cursors are.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<A, const N: usize> Eq for GapBuffer<A, N> where A: Eq {}
+
+impl<A, const N: usize> PartialOrd for GapBuffer<A, N>
+where
+    A: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<A, const N: usize> Ord for GapBuffer<A, N>
+where
+    A: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<const N: usize> fmt::Write for GapBuffer<u8, N> {
+    /// Insert the UTF-8 bytes of a string at the cursor, and move the cursor
+    /// past them.
+    ///
+    /// Fails without writing anything if the buffer doesn't have room for the
+    /// whole string.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.len() > self.gap_len() {
+            return Err(Error);
+        }
+        self.insert_from(s.bytes());
+        Ok(())
+    }
+}
+
+impl<A, const N: usize> FromIterator<A> for GapBuffer<A, N> {
+    /// Collect the items into a buffer, leaving the cursor at the end.
+    ///
+    /// Panics if the iterator has more than `N` items.
+    fn from_iter<I>(it: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+    {
+        let mut buffer = Self::new();
+        buffer.extend(it);
+        buffer
+    }
+}
+
+impl<A, const N: usize> Extend<A> for GapBuffer<A, N> {
+    /// Insert the contents of the iterator at the cursor, moving the cursor
+    /// past them.
+    ///
+    /// Panics if the buffer exceeds its capacity.
+    ///
+    /// Time: O(n) for the length of the iterator
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = A>,
+    {
+        for item in it {
+            self.insert(item);
+        }
+    }
+}
+
+impl<'a, A, const N: usize> Extend<&'a A> for GapBuffer<A, N>
+where
+    A: 'a + Copy,
+{
+    /// Insert the contents of the iterator at the cursor, moving the cursor
+    /// past them.
+    ///
+    /// Panics if the buffer exceeds its capacity.
+    ///
+    /// Time: O(n) for the length of the iterator
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = &'a A>,
+    {
+        for item in it {
+            self.insert(*item);
+        }
+    }
+}
+
+impl<'a, A, const N: usize> IntoIterator for &'a GapBuffer<A, N> {
+    type Item = &'a A;
+    type IntoIter = Iter<'a, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, A, const N: usize> IntoIterator for &'a mut GapBuffer<A, N> {
+    type Item = &'a mut A;
+    type IntoIter = IterMut<'a, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<A, const N: usize> IntoIterator for GapBuffer<A, N> {
+    type Item = A;
+    type IntoIter = OwnedIter<A, N>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let len = self.len();
+        self.set_cursor(len);
+        OwnedIter {
+            buffer: self,
+            front: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::FragileClone;
+    use std::fmt::Write;
+    use std::mem::ManuallyDrop;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn edit_at_cursor() {
+        let mut buffer = GapBuffer::<i32, 8>::new();
+        buffer.insert(1);
+        buffer.insert(2);
+        buffer.insert_after(4);
+        buffer.insert(3);
+        assert_eq!(3, buffer.cursor());
+        assert_eq!((&[1, 2, 3][..], &[4][..]), buffer.as_slices());
+        assert_eq!(Some(3), buffer.remove_before());
+        assert_eq!(Some(4), buffer.remove_after());
+        assert_eq!(None, buffer.remove_after());
+        buffer.set_cursor(0);
+        assert_eq!(None, buffer.remove_before());
+        assert_eq!((&[][..], &[1, 2][..]), buffer.as_slices());
+    }
+
+    #[test]
+    fn move_cursor() {
+        let mut buffer: GapBuffer<i32, 8> = (0..6).collect();
+        for &cursor in &[6, 2, 5, 0, 1, 6, 3] {
+            buffer.set_cursor(cursor);
+            assert_eq!(cursor, buffer.cursor());
+            let (before, after) = buffer.as_slices();
+            assert!(before.iter().chain(after).eq(&[0, 1, 2, 3, 4, 5]));
+            assert_eq!(cursor, before.len());
+            for index in 0..6 {
+                assert_eq!(index as i32, buffer[index]);
+            }
+        }
+        // A full buffer has no gap to move.
+        buffer.extend([6, 7].iter());
+        buffer.set_cursor(1);
+        assert!(buffer.iter().eq(&[0, 1, 2, 6, 7, 3, 4, 5]));
+    }
+
+    #[test]
+    #[should_panic(expected = "GapBuffer::set_cursor: index out of bounds")]
+    fn set_cursor_out_of_bounds() {
+        let mut buffer: GapBuffer<i32, 8> = (0..3).collect();
+        buffer.set_cursor(4);
+    }
+
+    #[test]
+    fn full() {
+        let mut buffer: GapBuffer<i32, 4> = (0..3).collect();
+        buffer.set_cursor(1);
+        buffer.insert_after(5);
+        assert!(buffer.is_full());
+        assert_eq!(Err(6), buffer.try_insert(6));
+        assert_eq!(Err(7), buffer.try_insert_after(7));
+        assert!(buffer.iter().eq(&[0, 5, 1, 2]));
+    }
+
+    #[test]
+    #[should_panic(expected = "GapBuffer::insert: buffer is full")]
+    fn insert_when_full() {
+        let mut buffer: GapBuffer<i32, 2> = (0..2).collect();
+        buffer.insert(2);
+    }
+
+    #[test]
+    fn insert_from() {
+        let mut buffer: GapBuffer<i32, 8> = (0..4).collect();
+        buffer.set_cursor(2);
+        buffer.insert_from(vec![7, 8, 9]);
+        assert_eq!(5, buffer.cursor());
+        assert!(buffer.iter().eq(&[0, 1, 7, 8, 9, 2, 3]));
+    }
+
+    #[test]
+    #[should_panic(expected = "GapBuffer::insert_from: buffer cannot fit 3 elements")]
+    fn insert_from_too_much() {
+        let mut buffer: GapBuffer<i32, 4> = (0..2).collect();
+        buffer.insert_from(vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn index_and_set() {
+        let mut buffer: GapBuffer<i32, 8> = (0..5).collect();
+        buffer.set_cursor(2);
+        assert_eq!(3, buffer.set(3, 30));
+        buffer[0] = 10;
+        assert_eq!(Some(&30), buffer.get(3));
+        assert_eq!(None, buffer.get(5));
+        assert!(buffer.iter().eq(&[10, 1, 2, 30, 4]));
+        for value in &mut buffer {
+            *value += 1;
+        }
+        assert!(buffer.iter().rev().eq(&[5, 31, 3, 2, 11]));
+    }
+
+    #[test]
+    #[should_panic(expected = "GapBuffer::index: index out of bounds 3 >= 3")]
+    fn index_out_of_bounds() {
+        let buffer: GapBuffer<i32, 8> = (0..3).collect();
+        let _ = buffer[3];
+    }
+
+    #[test]
+    fn write_str() {
+        let mut buffer = GapBuffer::<u8, 12>::new();
+        write!(buffer, "held").unwrap();
+        buffer.set_cursor(3);
+        write!(buffer, "lo wor").unwrap();
+        assert!(buffer.iter().eq(b"hello word"));
+        assert!(write!(buffer, "ld!").is_err());
+        assert_eq!(10, buffer.len());
+    }
+
+    #[test]
+    fn compare() {
+        let mut left: GapBuffer<i32, 8> = (0..4).collect();
+        let right: GapBuffer<i32, 8> = (0..4).collect();
+        left.set_cursor(1);
+        assert_eq!(left, right);
+        left.set(3, 4);
+        assert!(left > right);
+    }
+
+    #[test]
+    fn owned_iter() {
+        let mut buffer: GapBuffer<i32, 8> = (0..6).collect();
+        buffer.set_cursor(2);
+        let mut iter = buffer.into_iter();
+        assert_eq!(6, iter.len());
+        assert_eq!(Some(0), iter.next());
+        assert_eq!(Some(5), iter.next_back());
+        assert!(iter.eq(1..5));
+    }
+
+    #[test]
+    fn dropping() {
+        let counter = AtomicUsize::new(0);
+        {
+            let mut buffer: GapBuffer<FragileClone<'_>, 8> = GapBuffer::new();
+            for _ in 0..4 {
+                buffer.insert(FragileClone::new(&counter, false));
+            }
+            buffer.set_cursor(1);
+            buffer.insert_after(FragileClone::new(&counter, false));
+            assert_eq!(5, counter.load(Ordering::Relaxed));
+            buffer.remove_before();
+            assert_eq!(4, counter.load(Ordering::Relaxed));
+            let clone = buffer.clone();
+            assert_eq!(8, counter.load(Ordering::Relaxed));
+            buffer.clear();
+            assert_eq!(4, counter.load(Ordering::Relaxed));
+            let mut iter = clone.into_iter();
+            iter.next();
+            iter.next_back();
+            assert_eq!(2, counter.load(Ordering::Relaxed));
+        }
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn clone_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        {
+            let mut buffer = GapBuffer::<FragileClone<'_>, 8>::new();
+            buffer.insert(FragileClone::new(&counter, false));
+            buffer.insert(FragileClone::new(&counter, false));
+            buffer.insert_after(FragileClone::new(&counter, true));
+            buffer.insert_after(FragileClone::new(&counter, false));
+            let result = catch_unwind(AssertUnwindSafe(|| buffer.clone()));
+            assert!(result.is_err());
+            assert_eq!(4, counter.load(Ordering::Relaxed));
+        }
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[should_panic(expected = "GapBuffer::check_invariants: gap start 5 is past gap end 4")]
+    fn check_invariants() {
+        // Don't try to drop the broken buffer while unwinding.
+        let mut buffer = ManuallyDrop::new(GapBuffer::<i32, 4>::new());
+        buffer.gap_start = 5;
+        buffer.check_invariants();
+    }
+}
@@ -6,13 +6,15 @@
 //!
 //! See [`InlineArray`](struct.InlineArray.html)
 
+use crate::sized_chunk::Chunk;
 use core::borrow::{Borrow, BorrowMut};
 use core::cmp::Ordering;
-use core::fmt::{Debug, Error, Formatter};
+use core::convert::{Infallible, TryFrom};
+use core::fmt::{self, Debug, Error, Formatter};
 use core::hash::{Hash, Hasher};
 use core::iter::FromIterator;
 use core::marker::PhantomData;
-use core::mem::{self, MaybeUninit};
+use core::mem::{self, ManuallyDrop, MaybeUninit};
 use core::ops::{Deref, DerefMut};
 use core::ptr;
 use core::ptr::NonNull;
@@ -21,6 +23,9 @@ use core::slice::{from_raw_parts, from_raw_parts_mut, Iter as SliceIter, IterMut
 mod iter;
 pub use self::iter::{Drain, Iter};
 
+#[cfg(feature = "refpool")]
+mod refpool;
+
 /// A fixed capacity array sized to match some other type `T`.
 ///
 /// This works like a vector, but allocated on the stack (and thus marginally
@@ -125,6 +130,14 @@ const fn capacity(
     }
 }
 
+/// A compile time check that `M` items fit in an `InlineArray<A, T>`. This is
+/// `util::Fits` for a capacity that isn't a const parameter.
+struct FitsInline<A, T, const M: usize>(PhantomData<(A, T)>);
+
+impl<A, T, const M: usize> FitsInline<A, T, M> {
+    const CHECK: () = [()][(M > InlineArray::<A, T>::CAPACITY) as usize];
+}
+
 impl<A, T> InlineArray<A, T> {
     const HOST_SIZE: usize = mem::size_of::<T>();
     const ELEMENT_SIZE: usize = mem::size_of::<A>();
@@ -249,22 +262,46 @@ impl<A, T> InlineArray<A, T> {
         self.len() >= Self::CAPACITY
     }
 
-    /// Construct a new empty array.
+    /// Check the array's internal invariants, panicking if any of them don't
+    /// hold.
     ///
-    /// # Panics
+    /// This is meant for tests and fuzzing of code built on top of arrays.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the array, so corruption is caught where it
+    /// happens rather than wherever it's first noticed.
     ///
-    /// If the element type requires large alignment, which is larger than
-    /// both alignment of `usize` and alignment of the type that provides the capacity.
+    /// Time: O(1)
+    pub fn check_invariants(&self) {
+        assert!(
+            self.len() <= Self::CAPACITY,
+            "InlineArray::check_invariants: length {} is past capacity {}",
+            self.len(),
+            Self::CAPACITY
+        );
+    }
+
+    /// Panic if `T` is too small to hold the length, or if there's room for
+    /// items but the array can't be aligned for them.
     #[inline]
-    #[must_use]
-    pub fn new() -> Self {
+    pub(crate) fn assert_layout() {
         assert!(Self::HOST_SIZE > Self::HEADER_SIZE);
         assert!(
             (Self::CAPACITY == 0) || (mem::align_of::<Self>() % mem::align_of::<A>() == 0),
             "InlineArray can't satisfy alignment of {}",
             core::any::type_name::<A>()
         );
+    }
 
+    /// Construct a new empty array.
+    ///
+    /// # Panics
+    ///
+    /// If the element type requires large alignment, which is larger than
+    /// both alignment of `usize` and alignment of the type that provides the capacity.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::assert_layout();
         let mut self_ = Self {
             _header_align: [],
             _phantom: PhantomData,
@@ -290,6 +327,29 @@ impl<A, T> InlineArray<A, T> {
         self_
     }
 
+    /// Construct a full array by calling `f` with each index from `0` to
+    /// `CAPACITY - 1` in turn and using the results as its items.
+    ///
+    /// Panics if `A` is zero sized, as an array of zero sized items has a
+    /// capacity of `usize::MAX`, which would take forever to fill.
+    ///
+    /// Time: O(n)
+    #[must_use]
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(usize) -> A,
+    {
+        if Self::ELEMENT_SIZE == 0 {
+            panic!("InlineArray::from_fn: can't fill an array of zero sized items");
+        }
+        let mut array = Self::new();
+        while !array.is_full() {
+            let value = f(array.len());
+            array.push(value);
+        }
+        array
+    }
+
     /// Push an item to the back of the array.
     ///
     /// Panics if the capacity of the array is exceeded.
@@ -303,6 +363,7 @@ impl<A, T> InlineArray<A, T> {
             self.write_at(self.len(), value);
             *self.len_mut() += 1;
         }
+        debug_invariants!(self);
     }
 
     /// Pop an item from the back of the array.
@@ -317,7 +378,9 @@ impl<A, T> InlineArray<A, T> {
             unsafe {
                 *self.len_mut() -= 1;
             }
-            Some(unsafe { self.read_at(self.len()) })
+            let value = unsafe { self.read_at(self.len()) };
+            debug_invariants!(self);
+            Some(value)
         }
     }
 
@@ -340,6 +403,7 @@ impl<A, T> InlineArray<A, T> {
             ptr::write(src, value);
             *self.len_mut() += 1;
         }
+        debug_invariants!(self);
     }
 
     /// Remove the value at index `index`, shifting all the following values to
@@ -358,6 +422,7 @@ impl<A, T> InlineArray<A, T> {
                 let value = ptr::read(src);
                 *self.len_mut() -= 1;
                 ptr::copy(src.add(1), src, self.len() - index);
+                debug_invariants!(self);
                 Some(value)
             }
         }
@@ -382,9 +447,81 @@ impl<A, T> InlineArray<A, T> {
                 *self.len_mut() = index;
             }
         }
+        debug_invariants!(self, out);
         out
     }
 
+    /// Remove all items from `other`, an array of any capacity, and append
+    /// them to the back of `self`.
+    ///
+    /// Panics if the capacity of the array is exceeded.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn append_from<U>(&mut self, other: &mut InlineArray<A, U>) {
+        let len = self.len();
+        if len + other.len() > Self::CAPACITY {
+            panic!("InlineArray::append_from: chunk size overflow");
+        }
+        unsafe {
+            ptr::copy_nonoverlapping(other.data(), self.data_mut().add(len), other.len());
+            *self.len_mut() = len + other.len();
+            *other.len_mut() = 0;
+        }
+        debug_invariants!(self, other);
+    }
+
+    /// Move the contents of the array into a new array with its capacity
+    /// given by `U`.
+    ///
+    /// If the items don't fit in the new array, you get the old one back as
+    /// the error.
+    ///
+    /// Time: O(n)
+    pub fn into_capacity<U>(mut self) -> Result<InlineArray<A, U>, Self> {
+        let len = self.len();
+        if len > InlineArray::<A, U>::CAPACITY {
+            return Err(self);
+        }
+        let mut out = InlineArray::new();
+        unsafe {
+            ptr::copy_nonoverlapping(self.data(), out.data_mut(), len);
+            *out.len_mut() = len;
+            *self.len_mut() = 0;
+        }
+        debug_invariants!(out);
+        Ok(out)
+    }
+
+    /// Get the uninitialised space after the last element of the array.
+    ///
+    /// You can write values into the start of this slice and then call
+    /// [`set_len`][set_len] to make them part of the array, which lets you
+    /// fill it without initialising a temporary buffer first.
+    ///
+    /// [set_len]: #method.set_len
+    pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<A>] {
+        let len = self.len();
+        unsafe {
+            from_raw_parts_mut(
+                self.data_mut().add(len).cast::<MaybeUninit<A>>(),
+                Self::CAPACITY - len,
+            )
+        }
+    }
+
+    /// Set the length of the array without dropping or initialising anything.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be no larger than the capacity of the array, and every
+    /// element up to `new_len` must be initialised. If the array shrinks, the
+    /// elements past `new_len` are leaked rather than dropped.
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(new_len <= Self::CAPACITY);
+        *self.len_mut() = new_len;
+        debug_invariants!(self);
+    }
+
     #[inline]
     unsafe fn drop_contents(&mut self) {
         ptr::drop_in_place::<[A]>(&mut **self) // uses DerefMut
@@ -398,12 +535,85 @@ impl<A, T> InlineArray<A, T> {
             self.drop_contents();
             *self.len_mut() = 0;
         }
+        debug_invariants!(self);
     }
 
     /// Construct an iterator that drains values from the front of the array.
     pub fn drain(&mut self) -> Drain<'_, A, T> {
         Drain { array: self }
     }
+
+    /// Construct an array with the same backing type by calling `f` on each
+    /// item in turn, from front to back.
+    ///
+    /// Panics before calling `f` if an `InlineArray<B, T>` doesn't have room
+    /// for all the items, which can happen if `B` is larger than `A`.
+    ///
+    /// If `f` panics, the items it's already returned are dropped along with
+    /// the ones it hasn't seen yet.
+    ///
+    /// Time: O(n)
+    pub fn map<B, F>(self, mut f: F) -> InlineArray<B, T>
+    where
+        F: FnMut(A) -> B,
+    {
+        match self.try_map(|value| Ok::<_, Infallible>(f(value))) {
+            Ok(array) => array,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Construct an array with the same backing type by calling `f` on each
+    /// item in turn, from front to back, stopping at the first error `f`
+    /// returns.
+    ///
+    /// Panics before calling `f` if an `InlineArray<B, T>` doesn't have room
+    /// for all the items, which can happen if `B` is larger than `A`.
+    ///
+    /// On error, or if `f` panics, the items it's already returned are
+    /// dropped along with the ones it hasn't seen yet.
+    ///
+    /// Time: O(n)
+    pub fn try_map<B, E, F>(mut self, mut f: F) -> Result<InlineArray<B, T>, E>
+    where
+        F: FnMut(A) -> Result<B, E>,
+    {
+        /// Drops the items from `next` up to `len` when it goes out of scope.
+        struct Unmapped<'a, A, T> {
+            array: &'a mut InlineArray<A, T>,
+            next: usize,
+            len: usize,
+        }
+
+        impl<'a, A, T> Drop for Unmapped<'a, A, T> {
+            fn drop(&mut self) {
+                unsafe {
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                        self.array.data_mut().add(self.next),
+                        self.len - self.next,
+                    ))
+                }
+            }
+        }
+
+        let len = self.len();
+        if len > InlineArray::<B, T>::CAPACITY {
+            panic!("InlineArray::map: chunk size overflow");
+        }
+        let mut out = InlineArray::new();
+        unsafe { *self.len_mut() = 0 };
+        let mut unmapped = Unmapped {
+            array: &mut self,
+            next: 0,
+            len,
+        };
+        while unmapped.next < unmapped.len {
+            let value = unsafe { unmapped.array.read_at(unmapped.next) };
+            unmapped.next += 1;
+            out.push(f(value)?);
+        }
+        Ok(out)
+    }
 }
 
 impl<A, T> Drop for InlineArray<A, T> {
@@ -487,12 +697,13 @@ where
 
 impl<A, T> Eq for InlineArray<A, T> where A: Eq {}
 
-impl<A, T> PartialOrd for InlineArray<A, T>
+impl<A, T, Slice> PartialOrd<Slice> for InlineArray<A, T>
 where
+    Slice: Borrow<[A]>,
     A: PartialOrd,
 {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.iter().partial_cmp(other.iter())
+    fn partial_cmp(&self, other: &Slice) -> Option<Ordering> {
+        self.iter().partial_cmp(other.borrow().iter())
     }
 }
 
@@ -515,6 +726,63 @@ where
     }
 }
 
+impl<T> fmt::Write for InlineArray<u8, T> {
+    /// Append the UTF-8 bytes of a string to the back of the array.
+    ///
+    /// Fails without writing anything if the array doesn't have room for the
+    /// whole string.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.len() > Self::CAPACITY - self.len() {
+            return Err(Error);
+        }
+        self.extend(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<A, T, const M: usize> From<[A; M]> for InlineArray<A, T> {
+    /// Construct an inline array from an array of up to `CAPACITY` items.
+    ///
+    /// This fails to compile if `M` is larger than the capacity.
+    ///
+    /// ```rust,compile_fail
+    /// # use sized_chunks::InlineArray;
+    /// let array: InlineArray<u64, [u64; 3]> = InlineArray::from([1, 2, 3]);
+    /// ```
+    fn from(array: [A; M]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = FitsInline::<A, T, M>::CHECK;
+        let array = ManuallyDrop::new(array);
+        let mut out = Self::new();
+        unsafe {
+            ptr::copy_nonoverlapping(array.as_ptr(), out.data_mut(), M);
+            *out.len_mut() = M;
+        }
+        out
+    }
+}
+
+impl<A, T, const N: usize> TryFrom<Chunk<A, N>> for InlineArray<A, T> {
+    type Error = Chunk<A, N>;
+
+    /// Move the contents of a chunk into an inline array.
+    ///
+    /// If the chunk has more items than the array has capacity for, you get
+    /// it back as the error.
+    fn try_from(mut chunk: Chunk<A, N>) -> Result<Self, Self::Error> {
+        if chunk.len() > Self::CAPACITY {
+            return Err(chunk);
+        }
+        let mut out = Self::new();
+        unsafe {
+            ptr::copy_nonoverlapping(chunk.as_ptr(), out.data_mut(), chunk.len());
+            *out.len_mut() = chunk.len();
+            chunk.set_len(0);
+        }
+        Ok(out)
+    }
+}
+
 impl<A, T> Hash for InlineArray<A, T>
 where
     A: Hash,
@@ -602,11 +870,22 @@ where
 }
 
 #[cfg(test)]
+#[allow(dead_code, clippy::bool_assert_comparison, clippy::get_first)]
 mod test {
     use super::*;
     use crate::tests::DropTest;
     use std::sync::atomic::{AtomicUsize, Ordering};
 
+    #[test]
+    #[should_panic(expected = "InlineArray::check_invariants: length 4 is past capacity 3")]
+    fn check_invariants() {
+        // Don't try to drop the broken array while unwinding.
+        let mut array = ManuallyDrop::new(InlineArray::<u64, [u64; 4]>::from([1, 2]));
+        array.check_invariants();
+        unsafe { *array.len_mut() = 4 };
+        array.check_invariants();
+    }
+
     #[test]
     fn dropping() {
         let counter = AtomicUsize::new(0);
@@ -624,6 +903,123 @@ mod test {
         assert_eq!(0, counter.load(Ordering::Relaxed));
     }
 
+    #[test]
+    fn array_conversions() {
+        let array: InlineArray<u64, [u64; 4]> = InlineArray::from([1, 2, 3]);
+        assert_eq!(array, [1, 2, 3]);
+        let array: InlineArray<u64, [u64; 4]> = InlineArray::from([]);
+        assert!(array.is_empty());
+    }
+
+    #[test]
+    fn chunk_conversions() {
+        let mut chunk = Chunk::<u64, 4>::from([1, 2, 3, 4]);
+        chunk.pop_front();
+        let array = InlineArray::<u64, [u64; 4]>::try_from(chunk).unwrap();
+        assert_eq!(array, [2, 3, 4]);
+        assert_eq!(Chunk::<u64, 4>::from(array), [2, 3, 4]);
+
+        let chunk = Chunk::<u64, 4>::from([1, 2, 3, 4]);
+        let chunk = InlineArray::<u64, [u64; 4]>::try_from(chunk).unwrap_err();
+        assert_eq!(chunk, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn capacity_changes() {
+        let mut small = InlineArray::<u64, [u64; 3]>::new();
+        small.push(1);
+        small.push(2);
+        let mut large: InlineArray<u64, [u64; 8]> = small.into_capacity().unwrap();
+        assert_eq!(large, [1, 2]);
+
+        let mut other = InlineArray::<u64, [u64; 4]>::from([3, 4, 5]);
+        large.append_from(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(large, [1, 2, 3, 4, 5]);
+
+        let large = large.into_capacity::<[u64; 4]>().unwrap_err();
+        let exact: InlineArray<u64, [u64; 6]> = large.into_capacity().unwrap();
+        assert!(exact.is_full());
+        let copy: InlineArray<u64, [u64; 16]> = exact.iter().cloned().collect();
+        assert_eq!(exact, copy);
+    }
+
+    #[test]
+    #[should_panic(expected = "InlineArray::append_from: chunk size overflow")]
+    fn append_from_too_much() {
+        let mut array = InlineArray::<u64, [u64; 3]>::from([1, 2]);
+        array.append_from(&mut InlineArray::<u64, [u64; 3]>::from([3]));
+    }
+
+    #[test]
+    fn map() {
+        let array = InlineArray::<u32, [u64; 4]>::from([1, 2, 3]);
+        let mapped = array.map(|i| i as u64 * 10);
+        assert_eq!(mapped, [10, 20, 30]);
+
+        let array = InlineArray::<u32, [u64; 4]>::from([1, 2, 3]);
+        let result = array.try_map(|i| if i < 2 { Ok(i) } else { Err(i) });
+        assert_eq!(Err(2), result);
+    }
+
+    #[test]
+    #[should_panic(expected = "InlineArray::map: chunk size overflow")]
+    fn map_too_large() {
+        let array = InlineArray::<u32, [u64; 4]>::from([1, 2, 3, 4, 5, 6]);
+        let _ = array.map(|i| i as u64);
+    }
+
+    #[test]
+    fn map_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        crate::tests::map_panic_safety(
+            &counter,
+            |counter| {
+                (0..8)
+                    .map(|_| DropTest::new(counter))
+                    .collect::<InlineArray<_, [usize; 16]>>()
+            },
+            |array, f| {
+                let _ = array.map(f);
+            },
+        );
+    }
+
+    #[test]
+    fn from_fn() {
+        let array = InlineArray::<u16, [u64; 2]>::from_fn(|i| i as u16);
+        assert_eq!(array.len(), InlineArray::<u16, [u64; 2]>::CAPACITY);
+        assert!(array.iter().enumerate().all(|(i, &v)| i as u16 == v));
+    }
+
+    #[test]
+    #[should_panic(expected = "InlineArray::from_fn: can't fill an array of zero sized items")]
+    fn from_fn_zero_sized() {
+        let _ = InlineArray::<(), [u64; 2]>::from_fn(|_| ());
+    }
+
+    #[test]
+    fn spare_capacity() {
+        let mut array: InlineArray<u16, [u16; 16]> = InlineArray::new();
+        array.push(0);
+        let spare = array.spare_capacity_mut();
+        assert_eq!(InlineArray::<u16, [u16; 16]>::CAPACITY - 1, spare.len());
+        spare[0] = MaybeUninit::new(1);
+        spare[1] = MaybeUninit::new(2);
+        unsafe { array.set_len(3) };
+        assert_eq!(&[0, 1, 2], &array[..]);
+    }
+
+    #[test]
+    fn fmt_write() {
+        use core::fmt::Write;
+        let mut array: InlineArray<u8, [u8; 16]> = InlineArray::new();
+        write!(array, "{}", 1234).unwrap();
+        assert_eq!(b"1234", &array[..]);
+        assert!(array.write_str("too long to fit").is_err());
+        assert_eq!(b"1234", &array[..]);
+    }
+
     #[test]
     fn zero_sized_values() {
         let mut chunk: InlineArray<(), [usize; 32]> = InlineArray::new();
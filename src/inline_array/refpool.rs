@@ -0,0 +1,83 @@
+use core::mem::{self, MaybeUninit};
+
+use ::refpool::{PoolClone, PoolDefault};
+
+use crate::util::DropOnUnwind;
+use crate::InlineArray;
+
+impl<A, T> PoolDefault for InlineArray<A, T> {
+    unsafe fn default_uninit(target: &mut MaybeUninit<Self>) {
+        Self::assert_layout();
+        // All of the array's fields are valid uninitialised, so it's fine to
+        // make a reference to it before the length has been written.
+        let array = &mut *target.as_mut_ptr();
+        array.len_mut().write(0);
+    }
+}
+
+impl<A, T> PoolClone for InlineArray<A, T>
+where
+    A: Clone,
+{
+    unsafe fn clone_uninit(&self, target: &mut MaybeUninit<Self>) {
+        let ptr = target.as_mut_ptr();
+        let array = &mut *ptr;
+        array.len_mut().write(0);
+        // Panic safety: `target` holds a valid array of the items cloned so
+        // far at every step, which the guard drops if a clone panics.
+        let guard = DropOnUnwind(ptr);
+        for (index, item) in self.iter().enumerate() {
+            array.write_at(index, item.clone());
+            *array.len_mut() += 1;
+        }
+        mem::forget(guard);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::FragileClone;
+    use ::refpool::{Pool, PoolRef};
+    use std::iter::FromIterator;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    type Array = InlineArray<usize, [usize; 8]>;
+
+    #[test]
+    fn default_and_clone() {
+        let pool: Pool<Array> = Pool::new(16);
+        let mut ref1 = PoolRef::default(&pool);
+        {
+            let array = PoolRef::make_mut(&pool, &mut ref1);
+            array.push(1);
+            array.push(2);
+            array.push(3);
+        }
+        let ref2 = PoolRef::cloned(&pool, &ref1);
+        let ref3 = PoolRef::clone_from(&pool, &Array::from_iter(1..=3));
+        assert_eq!(Array::from_iter(1..=3), *ref1);
+        assert_eq!(Array::from_iter(1..=3), *ref2);
+        assert_eq!(Array::from_iter(1..=3), *ref3);
+        assert_eq!(ref1, ref2);
+        assert_eq!(ref1, ref3);
+        assert!(!PoolRef::ptr_eq(&ref1, &ref2));
+        ref2.check_invariants();
+    }
+
+    #[test]
+    fn clone_uninit_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut array: InlineArray<_, [usize; 8]> = InlineArray::new();
+        array.push(FragileClone::new(&counter, false));
+        array.push(FragileClone::new(&counter, false));
+        array.push(FragileClone::new(&counter, true));
+        let pool = Pool::new(4);
+        let result = catch_unwind(AssertUnwindSafe(|| PoolRef::clone_from(&pool, &array)));
+        assert!(result.is_err());
+        assert_eq!(3, counter.load(Ordering::Relaxed));
+        drop(array);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+}
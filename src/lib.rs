@@ -28,6 +28,12 @@
 //! | [`Chunk`][Chunk] | Contiguous array | O(1)/O(n) | O(1) | Yes |
 //! | [`RingBuffer`][RingBuffer] | Non-contiguous array | O(1) | O(1) | No |
 //! | [`SparseChunk`][SparseChunk] | Sparse array | N/A | N/A | No |
+//! | [`GapBuffer`][GapBuffer] | Array with a cursor | O(1) at cursor | O(1) at cursor | No |
+//! | [`ChunkHeap`][ChunkHeap] | Priority queue | O(log n) | O(log n) | No |
+//! | [`ChunkMap`][ChunkMap] | Sorted map | N/A | N/A | No |
+//! | [`ChunkSet`][ChunkSet] | Sorted set | N/A | N/A | No |
+//! | [`LruChunk`][LruChunk] | LRU cache | N/A | N/A | No |
+//! | [`ChunkList`][ChunkList] | Unrolled list | O(1) | O(1) | No |
 //!
 //! The [`Chunk`][Chunk] and [`RingBuffer`][RingBuffer] are very similar in
 //! practice, in that they both work like a plain array, except that you can
@@ -44,6 +50,11 @@
 //! in constant time, but a push to one side followed by a push to the other
 //! side will cause the latter to run in linear time if there's no room (which
 //! there would only be if you've popped from that side).
+//! If you do expect to push to both sides, a
+//! [`RebalancingChunk`][RebalancingChunk] with a different
+//! [`Rebalance`][Rebalance] policy keeps room free on both sides instead, and
+//! [`push_front_with`][push_front_with] and [`push_back_with`][push_back_with]
+//! pick a policy for a single push.
 //!
 //! To choose between them, you can use the following rules:
 //! - I only ever want to push to the back: you don't need this crate, try
@@ -62,6 +73,67 @@
 //! overhead. Its API is also more consistent with a map than an array - there's
 //! no push, pop, append, etc, just insert, remove and lookup.
 //!
+//! # [`GapBuffer`][GapBuffer]
+//!
+//! [`GapBuffer`][GapBuffer] keeps its free space at a cursor instead of at its
+//! ends, so that inserts and deletes at the cursor run in constant time, and
+//! moving the cursor costs time in proportion to how far it moves. This is the
+//! classic layout for the text in an editor, where most edits happen close to
+//! the previous one. Its contents are split in two by the gap, but
+//! [`GapBuffer::as_slices`][as_slices] gives you the slices on either side.
+//!
+//! # [`ChunkHeap`][ChunkHeap]
+//!
+//! [`ChunkHeap`][ChunkHeap] is a binary heap stored in a [`Chunk`][Chunk], for
+//! when you need a priority queue without an allocator, like a scheduler's run
+//! queue in `no_std` code. It works like `std::collections::BinaryHeap`, and can
+//! also keep just the greatest `N` items pushed to it, for collecting the top
+//! results in fixed memory.
+//!
+//! # [`ChunkMap`][ChunkMap] and [`ChunkSet`][ChunkSet]
+//!
+//! [`ChunkMap`][ChunkMap] and [`ChunkSet`][ChunkSet] are a sorted map and set
+//! stored as a sorted [`Chunk`][Chunk], with APIs following `BTreeMap` and
+//! `BTreeSet`. Lookups are binary searches, while inserts and removals shift
+//! the contents over, which is a good trade for small maps, or for the leaves
+//! of a B-tree.
+//!
+//! # [`LruChunk`][LruChunk]
+//!
+//! [`LruChunk`][LruChunk] is a small least recently used cache, which keeps its
+//! entries in a [`SparseChunk`][SparseChunk] and their recency order in a
+//! [`RingBuffer`][RingBuffer], and evicts the least recently used entry when
+//! you put a new one into a full cache. It never allocates, so it has a hard
+//! memory bound, and it needs the `ringbuffer` feature flag.
+//!
+//! # [`ChunkList`][ChunkList]
+//!
+//! [`ChunkList`][ChunkList] is the one data structure here which grows: it's an
+//! unrolled list of heap allocated [`RingBuffer`][RingBuffer] blocks, which
+//! adds a block when it runs out of room instead of panicking. It pushes and
+//! pops at both ends in constant time, splits and appends by moving whole
+//! blocks, and has cursors for editing in the middle. It needs both the
+//! `alloc` and `ringbuffer` feature flags.
+//!
+//! # [`SharedChunk`][SharedChunk]
+//!
+//! [`SharedChunk`][SharedChunk] is a copy-on-write handle to a [`Chunk`][Chunk]
+//! behind an `Rc`, an `Arc` or a [`refpool`][refpool] `PoolRef`, for sharing
+//! chunks between versions of a persistent data structure. Cloning the handle
+//! shares the chunk, and the chunk is only cloned when you first ask to change
+//! it through a handle that isn't the only one. It needs the `alloc` feature
+//! flag.
+//!
+//! # Views over borrowed memory
+//!
+//! [`ChunkView`][ChunkView], [`RingBufferView`][RingBufferView] and
+//! [`SparseChunkView`][SparseChunkView] work like [`Chunk`][Chunk],
+//! [`RingBuffer`][RingBuffer] and [`SparseChunk`][SparseChunk], but keep their
+//! items in a `&mut [MaybeUninit<A>]` you lend them instead of an inline array,
+//! for when the memory comes from somewhere else, like an arena or a memory
+//! mapped region. They own and drop the items they hold, and `into_raw_parts`
+//! hands them back to the buffer without dropping them.
+//!
 //! # [`InlineArray`][InlineArray]
 //!
 //! Finally, there's [`InlineArray`][InlineArray], which is a simple vector that's
@@ -73,26 +145,59 @@
 //! good example of that, and the use case for which [`InlineArray`][InlineArray]
 //! was implemented.
 //!
+//! # [`FixedString`][FixedString]
+//!
+//! [`FixedString`][FixedString] is a [`Chunk`][Chunk] of bytes which is
+//! guaranteed to hold valid UTF-8, for when you need to format text into a
+//! fixed size buffer, for instance when logging from `no_std` code. All of
+//! [`Chunk<u8, N>`][Chunk], [`RingBuffer<u8, N>`][RingBuffer],
+//! [`GapBuffer<u8, N>`][GapBuffer] and [`InlineArray<u8, T>`][InlineArray]
+//! also implement
+//! [`core::fmt::Write`][fmt::Write], if you just need the bytes.
+//!
 //! # Feature Flags
 //!
 //! The following feature flags are available:
 //!
 //! | Feature | Description |
 //! | ------- | ----------- |
+//! | `alloc` | Provides implementations relating to `alloc`, such as conversions and comparisons with `Vec`, `Box` and `BTreeMap`, without requiring the `std` flag. Enabled by `std`. |
 //! | `arbitrary` | Provides [`Arbitrary`][Arbitrary] implementations from the [`arbitrary`][arbitrary_crate] crate. Requires the `std` flag. |
-//! | `refpool` | Provides [`PoolDefault`][PoolDefault] and [`PoolClone`][PoolClone] implemetations from the [`refpool`][refpool] crate. |
-//! | `ringbuffer` | Enables the [`RingBuffer`][RingBuffer] data structure. |
-//! | `std` | Without this flag (enabled by default), the crate will be `no_std`, and absent traits relating to `std::collections` and `std::io`. |
+//! | `debug-invariants` | Calls `check_invariants()` after every method which mutates a container, to catch corruption where it happens. Meant for tests and fuzzing, as it slows everything down. |
+//! | `proptest` | Provides [`Arbitrary`][proptest_Arbitrary] implementations and parameterised strategies for the [`proptest`][proptest] crate, in the [`proptest`][proptest_module] module. Requires the `alloc` flag. |
+//! | `refpool` | Provides [`PoolDefault`][PoolDefault] and [`PoolClone`][PoolClone] implemetations from the [`refpool`][refpool] crate, and lets a [`SharedChunk`][SharedChunk] keep its chunk in a `PoolRef`. |
+//! | `ringbuffer` | Enables the [`RingBuffer`][RingBuffer], [`RingBufferView`][RingBufferView] and [`LruChunk`][LruChunk] data structures, and the [`ChunkList`][ChunkList] data structure when `alloc` is also enabled. |
+//! | `std` | Without this flag (enabled by default), the crate will be `no_std`, and absent traits relating to `std::collections` and `std::io`. Implies `alloc`. |
 //!
 //! [immutable.rs]: https://immutable.rs/
 //! [typenum]: https://docs.rs/typenum/
-//! [Chunk]: struct.Chunk.html
+//! [Chunk]: type.Chunk.html
 //! [RingBuffer]: struct.RingBuffer.html
 //! [SparseChunk]: struct.SparseChunk.html
+//! [GapBuffer]: struct.GapBuffer.html
+//! [ChunkHeap]: struct.ChunkHeap.html
+//! [ChunkMap]: struct.ChunkMap.html
+//! [ChunkSet]: struct.ChunkSet.html
+//! [LruChunk]: struct.LruChunk.html
+//! [ChunkList]: struct.ChunkList.html
+//! [SharedChunk]: struct.SharedChunk.html
+//! [ChunkView]: struct.ChunkView.html
+//! [RingBufferView]: struct.RingBufferView.html
+//! [SparseChunkView]: struct.SparseChunkView.html
+//! [as_slices]: struct.GapBuffer.html#method.as_slices
 //! [InlineArray]: struct.InlineArray.html
+//! [FixedString]: struct.FixedString.html
+//! [RebalancingChunk]: sized_chunk/struct.RebalancingChunk.html
+//! [push_front_with]: sized_chunk/struct.RebalancingChunk.html#method.push_front_with
+//! [push_back_with]: sized_chunk/struct.RebalancingChunk.html#method.push_back_with
+//! [Rebalance]: sized_chunk/trait.Rebalance.html
+//! [fmt::Write]: https://doc.rust-lang.org/core/fmt/trait.Write.html
 //! [ArrayVec]: https://docs.rs/arrayvec/
 //! [Arbitrary]: https://docs.rs/arbitrary/latest/arbitrary/trait.Arbitrary.html
 //! [arbitrary_crate]: https://docs.rs/arbitrary
+//! [proptest]: https://docs.rs/proptest
+//! [proptest_Arbitrary]: https://docs.rs/proptest/latest/proptest/arbitrary/trait.Arbitrary.html
+//! [proptest_module]: proptest/index.html
 //! [refpool]: https://docs.rs/refpool
 //! [PoolDefault]: https://docs.rs/refpool/latest/refpool/trait.PoolDefault.html
 //! [PoolClone]: https://docs.rs/refpool/latest/refpool/trait.PoolClone.html
@@ -105,6 +210,17 @@
 // Jeremy Francis Corbyn, clippy devs need to calm down 🤦‍♀️
 #![allow(clippy::suspicious_op_assign_impl, clippy::suspicious_arithmetic_impl)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[macro_use]
+mod util;
+
+pub mod chunk_heap;
+pub mod chunk_map;
+pub mod chunk_set;
+pub mod fixed_string;
+pub mod gap_buffer;
 pub mod inline_array;
 pub mod sized_chunk;
 pub mod sparse_chunk;
@@ -115,11 +231,34 @@ mod tests;
 #[cfg(feature = "arbitrary")]
 mod arbitrary;
 
+#[cfg(all(feature = "proptest", feature = "alloc"))]
+pub mod proptest;
+
+pub use crate::chunk_heap::ChunkHeap;
+pub use crate::chunk_map::ChunkMap;
+pub use crate::chunk_set::ChunkSet;
+pub use crate::fixed_string::FixedString;
+pub use crate::gap_buffer::GapBuffer;
 pub use crate::inline_array::InlineArray;
-pub use crate::sized_chunk::Chunk;
-pub use crate::sparse_chunk::SparseChunk;
+pub use crate::sized_chunk::{Chunk, ChunkView};
+pub use crate::sparse_chunk::{SparseChunk, SparseChunkView};
+
+#[cfg(feature = "alloc")]
+pub mod shared_chunk;
+#[cfg(feature = "alloc")]
+pub use crate::shared_chunk::SharedChunk;
 
 #[cfg(feature = "ringbuffer")]
 pub mod ring_buffer;
 #[cfg(feature = "ringbuffer")]
-pub use crate::ring_buffer::RingBuffer;
+pub use crate::ring_buffer::{RingBuffer, RingBufferView};
+
+#[cfg(feature = "ringbuffer")]
+pub mod lru_chunk;
+#[cfg(feature = "ringbuffer")]
+pub use crate::lru_chunk::LruChunk;
+
+#[cfg(all(feature = "alloc", feature = "ringbuffer"))]
+pub mod chunk_list;
+#[cfg(all(feature = "alloc", feature = "ringbuffer"))]
+pub use crate::chunk_list::ChunkList;
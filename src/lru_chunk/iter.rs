@@ -0,0 +1,56 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use core::iter::FusedIterator;
+
+use bitmaps::{Bits, BitsImpl};
+
+use crate::ring_buffer::Iter as RingIter;
+use crate::SparseChunk;
+
+/// An iterator over the entries of an `LruChunk`, from the most recently
+/// used to the least.
+pub struct Iter<'a, K, V, const N: usize>
+where
+    BitsImpl<N>: Bits,
+{
+    pub(crate) order: RingIter<'a, usize, N>,
+    pub(crate) entries: &'a SparseChunk<(K, V), N>,
+}
+
+impl<'a, K, V, const N: usize> Iterator for Iter<'a, K, V, N>
+where
+    BitsImpl<N>: Bits,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entries = self.entries;
+        self.order.next().map(|&slot| {
+            let (key, value) = &entries[slot];
+            (key, value)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.order.size_hint()
+    }
+}
+
+impl<'a, K, V, const N: usize> DoubleEndedIterator for Iter<'a, K, V, N>
+where
+    BitsImpl<N>: Bits,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let entries = self.entries;
+        self.order.next_back().map(|&slot| {
+            let (key, value) = &entries[slot];
+            (key, value)
+        })
+    }
+}
+
+impl<'a, K, V, const N: usize> ExactSizeIterator for Iter<'a, K, V, N> where BitsImpl<N>: Bits {}
+
+impl<'a, K, V, const N: usize> FusedIterator for Iter<'a, K, V, N> where BitsImpl<N>: Bits {}
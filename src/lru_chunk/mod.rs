@@ -0,0 +1,435 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A fixed capacity least recently used cache.
+//!
+//! See [`LruChunk`](struct.LruChunk.html)
+
+use core::borrow::Borrow;
+use core::fmt::{Debug, Error, Formatter};
+use core::mem::replace;
+
+use array_ops::{Array, HasLength};
+use bitmaps::{Bitmap, Bits, BitsImpl};
+
+use crate::{RingBuffer, SparseChunk};
+
+mod iter;
+pub use self::iter::Iter;
+
+/// A fixed capacity least recently used cache.
+///
+/// A map of up to `N` entries which remembers the order they were last used
+/// in, and makes room for new entries by evicting the one which has gone
+/// unused for the longest. The entries live in a [`SparseChunk`][SparseChunk],
+/// and their order is kept as a [`RingBuffer`][RingBuffer] of indices into it,
+/// so like the rest of the crate, it's stored inline and never allocates.
+///
+/// Lookups are a linear scan in order of recency, so it's meant for small
+/// caches, where that's cheaper than hashing, and the most recently used
+/// entries are found first. Only [`get`][get] and [`get_mut`][get_mut] count
+/// as a use: [`peek`][peek] leaves the order alone.
+///
+/// It's only available with the `ringbuffer` feature flag.
+///
+/// # Examples
+///
+/// ```rust
+/// # use sized_chunks::LruChunk;
+/// let mut cache = LruChunk::<&str, i32, 2>::new();
+/// assert_eq!(None, cache.put("one", 1));
+/// assert_eq!(None, cache.put("two", 2));
+/// // Using "one" leaves "two" as the least recently used entry...
+/// assert_eq!(Some(&1), cache.get("one"));
+/// // ...so that's the one which makes room for "three".
+/// assert_eq!(Some(("two", 2)), cache.put("three", 3));
+/// assert!(cache.iter().eq(vec![(&"three", &3), (&"one", &1)]));
+/// ```
+///
+/// [SparseChunk]: ../sparse_chunk/struct.SparseChunk.html
+/// [RingBuffer]: ../ring_buffer/struct.RingBuffer.html
+/// [get]: #method.get
+/// [get_mut]: #method.get_mut
+/// [peek]: #method.peek
+pub struct LruChunk<K, V, const N: usize>
+where
+    BitsImpl<N>: Bits,
+{
+    entries: SparseChunk<(K, V), N>,
+    /// Indices into `entries`, from the most recently used to the least.
+    order: RingBuffer<usize, N>,
+}
+
+impl<K, V, const N: usize> LruChunk<K, V, N>
+where
+    BitsImpl<N>: Bits,
+{
+    /// The maximum number of entries this `LruChunk` can contain.
+    pub const CAPACITY: usize = N;
+
+    /// Construct a new empty cache.
+    pub fn new() -> Self {
+        LruChunk {
+            entries: SparseChunk::new(),
+            order: RingBuffer::new(),
+        }
+    }
+
+    /// Get the number of entries in the cache.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Test if the cache is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Test if the cache is at capacity, so that the next new entry will
+    /// evict an old one.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.order.is_full()
+    }
+
+    /// Check the cache's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of caches.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the cache.
+    ///
+    /// Time: O(n)
+    pub fn check_invariants(&self) {
+        self.entries.check_invariants();
+        self.order.check_invariants();
+        assert_eq!(
+            self.entries.len(),
+            self.order.len(),
+            "LruChunk::check_invariants: entry count doesn't match the recency list"
+        );
+        let mut seen = Bitmap::<N>::new();
+        for &slot in self.order.iter() {
+            assert!(
+                self.entries.get(slot).is_some(),
+                "LruChunk::check_invariants: recency list points at empty slot {}",
+                slot
+            );
+            assert!(
+                !seen.set(slot, true),
+                "LruChunk::check_invariants: slot {} is in the recency list twice",
+                slot
+            );
+        }
+    }
+
+    /// Get a reference to the least recently used entry, without counting it
+    /// as a use.
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.order.last().map(|&slot| {
+            let (key, value) = &self.entries[slot];
+            (key, value)
+        })
+    }
+
+    /// Remove and return the least recently used entry.
+    ///
+    /// Time: O(1)
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let slot = self.order.pop_back()?;
+        let entry = self.entries.remove(slot);
+        debug_invariants!(self);
+        entry
+    }
+
+    /// Get an iterator over the entries of the cache, from the most recently
+    /// used to the least, without counting any of them as a use.
+    pub fn iter(&self) -> Iter<'_, K, V, N> {
+        Iter {
+            order: self.order.iter(),
+            entries: &self.entries,
+        }
+    }
+
+    /// Discard the contents of the cache.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        self.order.clear();
+        self.entries = SparseChunk::new();
+        debug_invariants!(self);
+    }
+}
+
+impl<K, V, const N: usize> LruChunk<K, V, N>
+where
+    K: Eq,
+    BitsImpl<N>: Bits,
+{
+    /// Find the position of `key` in the recency list, and its slot in
+    /// `entries`.
+    fn find<Q>(&self, key: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.order
+            .iter()
+            .position(|&slot| self.entries[slot].0.borrow() == key)
+            .map(|position| (position, self.order[position]))
+    }
+
+    /// Move the entry at `position` in the recency list to the front.
+    fn promote(&mut self, position: usize) {
+        if position > 0 {
+            let slot = self.order.remove(position);
+            self.order.push_front(slot);
+        }
+    }
+
+    /// Test if the cache contains `key`, without counting it as a use.
+    ///
+    /// Time: O(n)
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Get a reference to the value for `key`, and mark it as the most
+    /// recently used entry.
+    ///
+    /// Time: O(n)
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    /// Get a mutable reference to the value for `key`, and mark it as the
+    /// most recently used entry.
+    ///
+    /// Time: O(n)
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let (position, slot) = self.find(key)?;
+        self.promote(position);
+        debug_invariants!(self);
+        Some(&mut self.entries[slot].1)
+    }
+
+    /// Get a reference to the value for `key`, without counting it as a use.
+    ///
+    /// Time: O(n)
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.find(key).map(|(_, slot)| &self.entries[slot].1)
+    }
+
+    /// Insert a value for `key`, and mark it as the most recently used entry.
+    ///
+    /// If the key was already in the cache, its old entry is replaced and
+    /// returned. Otherwise, if the cache is full, the least recently used
+    /// entry is evicted to make room, and returned.
+    ///
+    /// Time: O(n)
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some((position, slot)) = self.find(&key) {
+            let old = replace(&mut self.entries[slot], (key, value));
+            self.promote(position);
+            debug_invariants!(self);
+            return Some(old);
+        }
+        let (slot, evicted) = if self.is_full() {
+            // A full cache has at least one entry, unless it has no room at
+            // all.
+            match self.order.pop_back() {
+                Some(slot) => (slot, self.entries.remove(slot)),
+                None => return Some((key, value)),
+            }
+        } else {
+            let slot = (0..N)
+                .find(|&slot| self.entries.get(slot).is_none())
+                .unwrap();
+            (slot, None)
+        };
+        self.entries.insert(slot, (key, value));
+        self.order.push_front(slot);
+        debug_invariants!(self);
+        evicted
+    }
+
+    /// Remove `key` from the cache, returning its value if it was there.
+    ///
+    /// Time: O(n)
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        let (position, slot) = self.find(key)?;
+        self.order.remove(position);
+        let entry = self.entries.remove(slot);
+        debug_invariants!(self);
+        entry.map(|(_, value)| value)
+    }
+}
+
+impl<K, V, const N: usize> Default for LruChunk<K, V, N>
+where
+    BitsImpl<N>: Bits,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const N: usize> Clone for LruChunk<K, V, N>
+where
+    K: Clone,
+    V: Clone,
+    BitsImpl<N>: Bits,
+{
+    fn clone(&self) -> Self {
+        LruChunk {
+            entries: self.entries.clone(),
+            order: self.order.clone(),
+        }
+    }
+}
+
+impl<K, V, const N: usize> Debug for LruChunk<K, V, N>
+where
+    K: Debug,
+    V: Debug,
+    BitsImpl<N>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("LruChunk")?;
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V, const N: usize> IntoIterator for &'a LruChunk<K, V, N>
+where
+    BitsImpl<N>: Bits,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::DropTest;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruChunk::<u32, u32, 4>::new();
+        // A model of the cache, from the most recently used entry to the
+        // least.
+        let mut model: Vec<(u32, u32)> = Vec::new();
+        for step in 0..200u32 {
+            let key = (step * 7) % 9;
+            match step % 4 {
+                0 | 1 => {
+                    let expected = match model.iter().position(|(k, _)| *k == key) {
+                        Some(position) => Some(model.remove(position)),
+                        None if model.len() == 4 => model.pop(),
+                        None => None,
+                    };
+                    model.insert(0, (key, step));
+                    assert_eq!(expected, cache.put(key, step));
+                }
+                2 => {
+                    let expected = model.iter().position(|(k, _)| *k == key).map(|position| {
+                        let entry = model.remove(position);
+                        model.insert(0, entry);
+                        entry.1
+                    });
+                    assert_eq!(expected.as_ref(), cache.get(&key));
+                }
+                _ => {
+                    let expected = model
+                        .iter()
+                        .position(|(k, _)| *k == key)
+                        .map(|position| model.remove(position).1);
+                    assert_eq!(expected, cache.remove(&key));
+                }
+            }
+            cache.check_invariants();
+            assert!(cache.iter().eq(model.iter().map(|(k, v)| (k, v))));
+        }
+    }
+
+    #[test]
+    fn peek_does_not_promote() {
+        let mut cache = LruChunk::<char, i32, 2>::new();
+        cache.put('a', 1);
+        cache.put('b', 2);
+        assert_eq!(Some(&1), cache.peek(&'a'));
+        assert_eq!(Some((&'a', &1)), cache.peek_lru());
+        *cache.get_mut(&'a').unwrap() += 10;
+        assert_eq!(Some((&'b', &2)), cache.peek_lru());
+        assert_eq!(Some(('b', 2)), cache.pop_lru());
+        assert_eq!(Some(('a', 11)), cache.pop_lru());
+        assert_eq!(None, cache.pop_lru());
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let mut cache = LruChunk::<String, usize, 4>::new();
+        cache.put("hello".to_string(), 5);
+        assert!(cache.contains_key("hello"));
+        assert_eq!(Some(&5), cache.get("hello"));
+        assert_eq!(Some(5), cache.remove("hello"));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn dropping() {
+        let counter = AtomicUsize::new(0);
+        {
+            let mut cache = LruChunk::<usize, DropTest<'_>, 4>::new();
+            for key in 0..6 {
+                cache.put(key, DropTest::new(&counter));
+            }
+            assert_eq!(4, counter.load(Ordering::Relaxed));
+            cache.remove(&3);
+            assert_eq!(3, counter.load(Ordering::Relaxed));
+            cache.clear();
+            assert_eq!(0, counter.load(Ordering::Relaxed));
+            cache.put(0, DropTest::new(&counter));
+        }
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "LruChunk::check_invariants: entry count doesn't match the recency list"
+    )]
+    fn check_invariants() {
+        let mut cache = LruChunk::<u8, u8, 4>::new();
+        cache.put(1, 1);
+        cache.order.pop_back();
+        cache.check_invariants();
+    }
+}
@@ -0,0 +1,421 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! [`proptest`][proptest] strategies for the data types in this crate.
+//!
+//! Every type also implements proptest's [`Arbitrary`][Arbitrary], so
+//! [`any`][any] works for them, but the functions in this module let you
+//! choose the strategy for the elements and how many of them to generate.
+//!
+//! The sequence types shrink like a `Vec` does, by removing elements and then
+//! by shrinking the ones that are left. A [`SparseChunk`][SparseChunk]
+//! shrinks by removing values, then by moving the remaining values down to
+//! the lowest free indices, then by shrinking the values themselves.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use proptest::prelude::*;
+//! # use sized_chunks::proptest::{chunk_of, sparse_chunk_of};
+//! # use sized_chunks::{Chunk, SparseChunk};
+//! proptest! {
+//!     fn push_makes_chunk_longer(mut chunk in chunk_of::<_, _, 16>(any::<u8>(), 0..16)) {
+//!         let len = chunk.len();
+//!         chunk.push_back(0);
+//!         prop_assert_eq!(len + 1, chunk.len());
+//!     }
+//!
+//!     fn sparse_chunk_has_no_more_than_capacity(
+//!         chunk in sparse_chunk_of::<_, 32>(any::<u8>(), 0.25)
+//!     ) {
+//!         prop_assert!(chunk.len() <= 32);
+//!     }
+//! }
+//! # push_makes_chunk_longer();
+//! # sparse_chunk_has_no_more_than_capacity();
+//! ```
+//!
+//! [proptest]: https://docs.rs/proptest
+//! [Arbitrary]: https://docs.rs/proptest/latest/proptest/arbitrary/trait.Arbitrary.html
+//! [any]: https://docs.rs/proptest/latest/proptest/arbitrary/fn.any.html
+//! [SparseChunk]: ../struct.SparseChunk.html
+
+use core::fmt::Debug;
+
+use alloc::vec::Vec;
+
+use bitmaps::{Bits, BitsImpl};
+
+use ::proptest::arbitrary::{any_with, Arbitrary};
+use ::proptest::collection::{vec, SizeRange, VecStrategy};
+use ::proptest::strategy::{Map, NewTree, Strategy, ValueTree};
+use ::proptest::test_runner::TestRunner;
+
+use crate::{Chunk, InlineArray, SparseChunk};
+
+#[cfg(feature = "ringbuffer")]
+use crate::RingBuffer;
+
+/// The strategy returned by [`chunk_of`][chunk_of].
+///
+/// [chunk_of]: fn.chunk_of.html
+pub type ChunkStrategy<S, const N: usize> =
+    Map<VecStrategy<S>, fn(Vec<<S as Strategy>::Value>) -> Chunk<<S as Strategy>::Value, N>>;
+
+/// The strategy returned by [`ring_buffer_of`][ring_buffer_of].
+///
+/// [ring_buffer_of]: fn.ring_buffer_of.html
+#[cfg(feature = "ringbuffer")]
+pub type RingBufferStrategy<S, const N: usize> = Map<
+    VecStrategy<S>,
+    fn(Vec<<S as Strategy>::Value>) -> RingBuffer<<S as Strategy>::Value, N>,
+>;
+
+/// The strategy returned by [`inline_array_of`][inline_array_of].
+///
+/// [inline_array_of]: fn.inline_array_of.html
+pub type InlineArrayStrategy<S, T> = Map<
+    VecStrategy<S>,
+    fn(Vec<<S as Strategy>::Value>) -> InlineArray<<S as Strategy>::Value, T>,
+>;
+
+fn collect<A, C: core::iter::FromIterator<A>>(values: Vec<A>) -> C {
+    values.into_iter().collect()
+}
+
+/// Make sure a size range fits in a container's capacity, and convert it.
+fn checked_size(size: impl Into<SizeRange>, capacity: usize, caller: &str) -> SizeRange {
+    let size = size.into();
+    if size.end_incl() > capacity {
+        panic!(
+            "{}: size range {:?} goes past the capacity of {}",
+            caller, size, capacity
+        );
+    }
+    size
+}
+
+/// Create a strategy for generating a [`Chunk`][Chunk] with a length in the
+/// range `size`, filled with elements from `element`.
+///
+/// Panics if `size` allows lengths greater than `N`.
+///
+/// [Chunk]: ../type.Chunk.html
+pub fn chunk_of<S, R, const N: usize>(element: S, size: R) -> ChunkStrategy<S, N>
+where
+    S: Strategy,
+    R: Into<SizeRange>,
+{
+    let size = checked_size(size, N, "chunk_of");
+    vec(element, size).prop_map(collect as fn(_) -> _)
+}
+
+/// Create a strategy for generating a [`RingBuffer`][RingBuffer] with a
+/// length in the range `size`, filled with elements from `element`.
+///
+/// Panics if `size` allows lengths greater than `N`.
+///
+/// [RingBuffer]: ../struct.RingBuffer.html
+#[cfg(feature = "ringbuffer")]
+pub fn ring_buffer_of<S, R, const N: usize>(element: S, size: R) -> RingBufferStrategy<S, N>
+where
+    S: Strategy,
+    R: Into<SizeRange>,
+{
+    let size = checked_size(size, N, "ring_buffer_of");
+    vec(element, size).prop_map(collect as fn(_) -> _)
+}
+
+/// Create a strategy for generating an [`InlineArray`][InlineArray] with a
+/// length in the range `size`, filled with elements from `element`.
+///
+/// Panics if `size` allows lengths greater than the array's capacity.
+///
+/// [InlineArray]: ../struct.InlineArray.html
+pub fn inline_array_of<S, T, R>(element: S, size: R) -> InlineArrayStrategy<S, T>
+where
+    S: Strategy,
+    R: Into<SizeRange>,
+{
+    let capacity = InlineArray::<S::Value, T>::CAPACITY;
+    let size = checked_size(size, capacity, "inline_array_of");
+    vec(element, size).prop_map(collect as fn(_) -> _)
+}
+
+/// Create a strategy for generating a [`SparseChunk`][SparseChunk] where
+/// each index holds a value from `element` with a probability of `density`.
+///
+/// Panics if `density` isn't between 0 and 1.
+///
+/// [SparseChunk]: ../struct.SparseChunk.html
+pub fn sparse_chunk_of<S, const N: usize>(element: S, density: f64) -> SparseChunkStrategy<S, N>
+where
+    S: Strategy,
+    BitsImpl<N>: Bits,
+{
+    if !(0.0..=1.0).contains(&density) {
+        panic!("sparse_chunk_of: density {} is not between 0 and 1", density);
+    }
+    SparseChunkStrategy { element, density }
+}
+
+/// The strategy returned by [`sparse_chunk_of`][sparse_chunk_of].
+///
+/// [sparse_chunk_of]: fn.sparse_chunk_of.html
+#[derive(Clone, Debug)]
+pub struct SparseChunkStrategy<S, const N: usize> {
+    element: S,
+    density: f64,
+}
+
+impl<S, const N: usize> Strategy for SparseChunkStrategy<S, N>
+where
+    S: Strategy,
+    BitsImpl<N>: Bits,
+{
+    type Tree = SparseChunkValueTree<S::Tree, N>;
+    type Value = SparseChunk<S::Value, N>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let present = ::proptest::bool::weighted(self.density);
+        let mut entries = Vec::new();
+        for index in 0..N {
+            if present.new_tree(runner)?.current() {
+                entries.push((index, self.element.new_tree(runner)?));
+            }
+        }
+        Ok(SparseChunkValueTree {
+            entries,
+            stage: Stage::Remove,
+            next: 0,
+            undo: None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Stage {
+    Remove,
+    Lower,
+    Shrink,
+}
+
+#[derive(Clone, Debug)]
+enum Undo<T> {
+    Removed(usize, (usize, T)),
+    Lowered(usize, usize),
+    Shrunk(usize),
+}
+
+/// The value tree for [`SparseChunkStrategy`][SparseChunkStrategy].
+///
+/// [SparseChunkStrategy]: struct.SparseChunkStrategy.html
+#[derive(Clone, Debug)]
+pub struct SparseChunkValueTree<T, const N: usize> {
+    // The values in index order, with the index each one is at.
+    entries: Vec<(usize, T)>,
+    stage: Stage,
+    // The entry to try simplifying next.
+    next: usize,
+    undo: Option<Undo<T>>,
+}
+
+impl<T, const N: usize> ValueTree for SparseChunkValueTree<T, N>
+where
+    T: ValueTree,
+    BitsImpl<N>: Bits,
+{
+    type Value = SparseChunk<T::Value, N>;
+
+    fn current(&self) -> Self::Value {
+        let mut chunk = SparseChunk::new();
+        for (index, tree) in &self.entries {
+            chunk.insert(*index, tree.current());
+        }
+        chunk
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.undo = None;
+        loop {
+            match self.stage {
+                Stage::Remove => {
+                    if self.next < self.entries.len() {
+                        let entry = self.entries.remove(self.next);
+                        self.undo = Some(Undo::Removed(self.next, entry));
+                        return true;
+                    }
+                    self.stage = Stage::Lower;
+                    self.next = 0;
+                }
+                Stage::Lower => {
+                    if self.next < self.entries.len() {
+                        let lowest = match self.next {
+                            0 => 0,
+                            next => self.entries[next - 1].0 + 1,
+                        };
+                        let index = &mut self.entries[self.next].0;
+                        if *index > lowest {
+                            self.undo = Some(Undo::Lowered(self.next, *index));
+                            *index = lowest;
+                            return true;
+                        }
+                        self.next += 1;
+                    } else {
+                        self.stage = Stage::Shrink;
+                        self.next = 0;
+                    }
+                }
+                Stage::Shrink => {
+                    if self.next < self.entries.len() {
+                        if self.entries[self.next].1.simplify() {
+                            self.undo = Some(Undo::Shrunk(self.next));
+                            return true;
+                        }
+                        self.next += 1;
+                    } else {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.undo.take() {
+            None => false,
+            Some(Undo::Removed(position, entry)) => {
+                self.entries.insert(position, entry);
+                self.next = position + 1;
+                true
+            }
+            Some(Undo::Lowered(position, index)) => {
+                self.entries[position].0 = index;
+                self.next = position + 1;
+                true
+            }
+            Some(Undo::Shrunk(position)) if self.entries[position].1.complicate() => {
+                self.undo = Some(Undo::Shrunk(position));
+                true
+            }
+            Some(Undo::Shrunk(_)) => false,
+        }
+    }
+}
+
+impl<A, const N: usize> Arbitrary for Chunk<A, N>
+where
+    A: Arbitrary,
+{
+    type Parameters = A::Parameters;
+    type Strategy = ChunkStrategy<A::Strategy, N>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        chunk_of(any_with::<A>(args), 0..=N)
+    }
+}
+
+#[cfg(feature = "ringbuffer")]
+impl<A, const N: usize> Arbitrary for RingBuffer<A, N>
+where
+    A: Arbitrary,
+{
+    type Parameters = A::Parameters;
+    type Strategy = RingBufferStrategy<A::Strategy, N>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        ring_buffer_of(any_with::<A>(args), 0..=N)
+    }
+}
+
+impl<A, T> Arbitrary for InlineArray<A, T>
+where
+    A: Arbitrary,
+    T: Debug,
+{
+    type Parameters = A::Parameters;
+    type Strategy = InlineArrayStrategy<A::Strategy, T>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        inline_array_of(any_with::<A>(args), 0..=Self::CAPACITY)
+    }
+}
+
+impl<A, const N: usize> Arbitrary for SparseChunk<A, N>
+where
+    A: Arbitrary,
+    BitsImpl<N>: Bits,
+{
+    type Parameters = A::Parameters;
+    type Strategy = SparseChunkStrategy<A::Strategy, N>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        sparse_chunk_of(any_with::<A>(args), 0.5)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::proptest::arbitrary::any;
+    use ::proptest::prop_assert;
+    use ::proptest::test_runner::TestError;
+
+    #[test]
+    fn lengths_stay_in_range() {
+        let mut runner = TestRunner::default();
+        let strategy = chunk_of::<_, _, 16>(any::<u8>(), 3..=9);
+        for _ in 0..256 {
+            let chunk = strategy.new_tree(&mut runner).unwrap().current();
+            chunk.check_invariants();
+            assert!((3..=9).contains(&chunk.len()));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk_of: size range")]
+    fn size_range_past_capacity() {
+        let _ = chunk_of::<_, _, 4>(any::<u8>(), 0..8);
+    }
+
+    #[test]
+    fn sparse_density() {
+        let mut runner = TestRunner::default();
+        let empty = sparse_chunk_of::<_, 64>(any::<u8>(), 0.0);
+        let full = sparse_chunk_of::<_, 64>(any::<u8>(), 1.0);
+        for _ in 0..16 {
+            assert!(empty.new_tree(&mut runner).unwrap().current().is_empty());
+            assert!(full.new_tree(&mut runner).unwrap().current().is_full());
+        }
+    }
+
+    #[test]
+    fn chunk_shrinks_to_fewer_elements() {
+        let mut runner = TestRunner::default();
+        let result = runner.run(&any::<Chunk<u8, 32>>(), |chunk| {
+            prop_assert!(chunk.len() < 3);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, chunk)) => assert_eq!(chunk, [0, 0, 0]),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn sparse_chunk_shrinks_to_lower_indices() {
+        let mut runner = TestRunner::default();
+        let result = runner.run(&any::<SparseChunk<u8, 32>>(), |chunk| {
+            prop_assert!(chunk.len() < 3);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, chunk)) => {
+                assert_eq!(vec![0, 1, 2], chunk.indices().collect::<Vec<_>>());
+                assert!(chunk.iter().all(|value| *value == 0));
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
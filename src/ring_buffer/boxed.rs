@@ -0,0 +1,126 @@
+use alloc::boxed::Box;
+use core::ptr::addr_of_mut;
+
+use array_ops::HasLength;
+
+use crate::ring_buffer::index::RawIndex;
+use crate::util::new_uninit_box;
+use crate::RingBuffer;
+
+impl<A, const N: usize> RingBuffer<A, N> {
+    /// Construct a new empty ring buffer directly in a heap allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::RingBuffer;
+    /// let mut buffer = RingBuffer::<u8, 65536>::new_boxed();
+    /// buffer.push_back(1);
+    /// assert_eq!(Some(1), buffer.pop_front());
+    /// ```
+    pub fn new_boxed() -> Box<Self> {
+        let mut buffer = new_uninit_box::<Self>();
+        unsafe {
+            let ptr = buffer.as_mut_ptr();
+            addr_of_mut!((*ptr).origin).write(RawIndex::from(0));
+            addr_of_mut!((*ptr).length).write(0);
+            Box::from_raw(Box::into_raw(buffer).cast())
+        }
+    }
+
+    /// Construct a ring buffer from an iterator directly in a heap
+    /// allocation.
+    ///
+    /// Panics if the iterator contains more than `N` items.
+    ///
+    /// Time: O(n)
+    pub fn boxed_from_iter<I>(iter: I) -> Box<Self>
+    where
+        I: IntoIterator<Item = A>,
+    {
+        let mut buffer = Self::new_boxed();
+        buffer.extend(iter);
+        buffer
+    }
+
+    /// Clone the ring buffer directly into a heap allocation.
+    ///
+    /// Time: O(n)
+    pub fn clone_boxed(&self) -> Box<Self>
+    where
+        A: Clone,
+    {
+        let mut out = Self::new_boxed();
+        out.origin = self.origin;
+        // Panic safety: as with `clone`, only cover the items written so far.
+        for index in self.range() {
+            unsafe { out.force_write(index, (&*self.ptr(index)).clone()) };
+            out.length += 1;
+        }
+        out
+    }
+
+    /// Split a buffer into two like [`split_off`][split_off], but construct
+    /// the returned buffer directly in a heap allocation.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(n) for the number of items in the new buffer
+    ///
+    /// [split_off]: #method.split_off
+    pub fn split_off_boxed(&mut self, index: usize) -> Box<Self> {
+        if index > self.len() {
+            panic!("RingBuffer::split_off_boxed: index out of bounds");
+        }
+        let mut right = Self::new_boxed();
+        let length = self.length - index;
+        unsafe {
+            right
+                .raw_ring()
+                .take_back_from(&mut self.raw_ring(), length)
+        };
+        debug_invariants!(self, right);
+        right
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::FragileClone;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn split_off_boxed() {
+        for index in 0..=6 {
+            // Wrap the buffer around the end of its storage.
+            let mut left = RingBuffer::<usize, 8>::new_boxed();
+            left.extend(0..6);
+            for _ in 0..5 {
+                let item = left.pop_front().unwrap();
+                left.push_back(item);
+            }
+            let right = left.split_off_boxed(index);
+            left.check_invariants();
+            right.check_invariants();
+            let expected = [5, 0, 1, 2, 3, 4];
+            assert!(left.iter().eq(expected[..index].iter()));
+            assert!(right.iter().eq(expected[index..].iter()));
+        }
+    }
+
+    #[test]
+    fn clone_boxed_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut buffer: RingBuffer<_, 8> = RingBuffer::new();
+        buffer.push_back(FragileClone::new(&counter, false));
+        buffer.push_back(FragileClone::new(&counter, false));
+        buffer.push_back(FragileClone::new(&counter, true));
+        buffer.push_front(FragileClone::new(&counter, false));
+        assert!(catch_unwind(AssertUnwindSafe(|| buffer.clone_boxed())).is_err());
+        assert_eq!(4, counter.load(Ordering::Relaxed));
+        drop(buffer);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+}
@@ -18,12 +18,26 @@ impl<const N: usize> Clone for RawIndex<N> {
 impl<const N: usize> Copy for RawIndex<N> {}
 
 impl<const N: usize> RawIndex<N> {
+    /// The first raw index, usable in `const` contexts where `From` isn't.
+    #[inline]
+    #[must_use]
+    pub(crate) const fn zero() -> Self {
+        RawIndex(0)
+    }
+
     #[inline]
     #[must_use]
     pub(crate) fn to_usize(self) -> usize {
         self.0
     }
 
+    /// Borrow the index as a plain `usize`, for `RawRing`.
+    #[inline]
+    #[must_use]
+    pub(crate) fn as_mut_usize(&mut self) -> &mut usize {
+        &mut self.0
+    }
+
     /// Increments the index and returns a copy of the index /before/ incrementing.
     #[inline]
     #[must_use]
@@ -75,6 +89,10 @@ impl<const N: usize> Add<usize> for RawIndex<N> {
     #[inline]
     #[must_use]
     fn add(self, other: usize) -> Self::Output {
+        // A zero capacity buffer's only index is its origin.
+        if N == 0 {
+            return self;
+        }
         let mut result = self.0 + other;
         while result >= N {
             result -= N;
@@ -86,6 +104,9 @@ impl<const N: usize> Add<usize> for RawIndex<N> {
 impl<const N: usize> AddAssign<usize> for RawIndex<N> {
     #[inline]
     fn add_assign(&mut self, other: usize) {
+        if N == 0 {
+            return;
+        }
         self.0 += other;
         while self.0 >= N {
             self.0 -= N;
@@ -8,12 +8,21 @@
 
 use core::borrow::Borrow;
 use core::cmp::Ordering;
-use core::fmt::{Debug, Error, Formatter};
+use core::convert::{Infallible, TryFrom};
+use core::fmt::{self, Debug, Error, Formatter};
 use core::hash::{Hash, Hasher};
 use core::iter::FromIterator;
-use core::mem::{replace, MaybeUninit};
+use core::mem::{ManuallyDrop, MaybeUninit};
 use core::ops::{Bound, Range, RangeBounds};
 use core::ops::{Index, IndexMut};
+use core::ptr;
+
+use crate::inline_array::InlineArray;
+use crate::sized_chunk::Chunk;
+use crate::util::Fits;
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::VecDeque, vec::Vec};
 
 pub use array_ops::{Array, ArrayMut, HasLength};
 
@@ -23,9 +32,18 @@ use index::{IndexIter, RawIndex};
 mod iter;
 pub use iter::{Drain, Iter, IterMut, OwnedIter};
 
+mod raw;
+use raw::RawRing;
+
 mod slice;
 pub use slice::{Slice, SliceMut};
 
+#[cfg(feature = "alloc")]
+mod boxed;
+
+mod view;
+pub use view::{RingBufferView, ViewDrain};
+
 #[cfg(feature = "refpool")]
 mod refpool;
 
@@ -54,7 +72,7 @@ mod refpool;
 ///
 /// To use this data structure, you need to enable the `ringbuffer` feature.
 ///
-/// [Chunk]: ../sized_chunk/struct.Chunk.html
+/// [Chunk]: ../sized_chunk/type.Chunk.html
 /// [Slice]: struct.Slice.html
 /// [SliceMut]: struct.SliceMut.html
 pub struct RingBuffer<A, const N: usize> {
@@ -147,49 +165,16 @@ impl<A, const N: usize> RingBuffer<A, N> {
         core::ptr::write(self.mut_ptr(index), value)
     }
 
-    /// Copy a range of raw indices from another buffer.
-    unsafe fn copy_from(
-        &mut self,
-        source: &mut Self,
-        from: RawIndex<N>,
-        to: RawIndex<N>,
-        count: usize,
-    ) {
-        #[inline]
-        unsafe fn force_copy_to<A, const N: usize>(
-            source: &mut RingBuffer<A, N>,
-            from: RawIndex<N>,
-            target: &mut RingBuffer<A, N>,
-            to: RawIndex<N>,
-            count: usize,
-        ) {
-            if count > 0 {
-                debug_assert!(from.to_usize() + count <= RingBuffer::<A, N>::CAPACITY);
-                debug_assert!(to.to_usize() + count <= RingBuffer::<A, N>::CAPACITY);
-                core::ptr::copy_nonoverlapping(source.mut_ptr(from), target.mut_ptr(to), count)
-            }
-        }
-
-        if from.to_usize() + count > Self::CAPACITY {
-            let first_length = Self::CAPACITY - from.to_usize();
-            let last_length = count - first_length;
-            self.copy_from(source, from, to, first_length);
-            self.copy_from(source, 0.into(), to + first_length, last_length);
-        } else if to.to_usize() + count > Self::CAPACITY {
-            let first_length = Self::CAPACITY - to.to_usize();
-            let last_length = count - first_length;
-            force_copy_to(source, from, self, to, first_length);
-            force_copy_to(source, from + first_length, self, 0.into(), last_length);
-        } else {
-            force_copy_to(source, from, self, to, count);
-        }
+    #[inline]
+    fn raw_ring(&mut self) -> RawRing<'_, A> {
+        let base = self.data.as_mut_ptr().cast();
+        unsafe { RawRing::new(base, N, self.origin.as_mut_usize(), &mut self.length) }
     }
 
     /// Copy values from a slice.
-    #[allow(dead_code)]
     unsafe fn copy_from_slice(&mut self, source: &[A], to: RawIndex<N>) {
         let count = source.len();
-        debug_assert!(to.to_usize() + count <= Self::CAPACITY);
+        debug_assert!(count <= Self::CAPACITY);
         if to.to_usize() + count > Self::CAPACITY {
             let first_length = Self::CAPACITY - to.to_usize();
             let first_slice = &source[..first_length];
@@ -219,17 +204,85 @@ impl<A, const N: usize> RingBuffer<A, N> {
         }
     }
 
+    /// Get the contents of the buffer as two slices, the first one starting
+    /// at the front of the buffer and the second one ending at the back.
+    fn raw_slices(&self) -> (&[A], &[A]) {
+        let origin = self.origin.to_usize();
+        let first_length = self.length.min(N - origin);
+        let data: *const A = self.data.as_ptr().cast();
+        unsafe {
+            (
+                core::slice::from_raw_parts(data.add(origin), first_length),
+                core::slice::from_raw_parts(data, self.length - first_length),
+            )
+        }
+    }
+
+    /// Copy every item in the buffer, in order from front to back, into
+    /// contiguous memory at `target`, discarding ownership of the copied
+    /// values.
+    unsafe fn copy_to_ptr(&self, target: *mut A) {
+        let (front, back) = self.raw_slices();
+        ptr::copy_nonoverlapping(front.as_ptr(), target, front.len());
+        ptr::copy_nonoverlapping(back.as_ptr(), target.add(front.len()), back.len());
+    }
+
     /// Construct an empty ring buffer.
+    ///
+    /// This is a `const fn`, so you can use it to initialise a `static`.
     #[inline]
     #[must_use]
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         Self {
-            origin: 0.into(),
+            origin: RawIndex::zero(),
             length: 0,
             data: MaybeUninit::uninit(),
         }
     }
 
+    /// Construct an empty ring buffer which will store its first item at raw
+    /// index `origin`, wrapped to fit the capacity.
+    #[cfg(feature = "arbitrary")]
+    pub(crate) fn with_origin(origin: usize) -> Self {
+        let mut buffer = Self::new();
+        if N > 0 {
+            buffer.origin = RawIndex::from(origin % N);
+        }
+        buffer
+    }
+
+    /// Construct a full ring buffer from an array of exactly `N` items.
+    ///
+    /// This is a `const fn`, so you can use it to initialise a `static`.
+    #[inline]
+    #[must_use]
+    pub const fn from_array(array: [A; N]) -> Self {
+        Self {
+            origin: RawIndex::zero(),
+            length: N,
+            data: MaybeUninit::new(array),
+        }
+    }
+
+    /// Construct a full ring buffer by calling `f` with each index from `0`
+    /// to `N - 1` in turn and using the results as its items.
+    ///
+    /// Time: O(n)
+    #[must_use]
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(usize) -> A,
+    {
+        let mut buffer = Self::new();
+        for index in 0..N {
+            let value = f(index);
+            unsafe { buffer.force_write(index.into(), value) };
+            buffer.length += 1;
+        }
+        debug_invariants!(buffer);
+        buffer
+    }
+
     /// Construct a ring buffer with a single item.
     #[inline]
     #[must_use]
@@ -320,6 +373,31 @@ impl<A, const N: usize> RingBuffer<A, N> {
         self.len() == Self::CAPACITY
     }
 
+    /// Check the buffer's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of ring
+    /// buffers. With the `debug-invariants` feature enabled, it's called after
+    /// every method which changes the buffer, so corruption is caught where it
+    /// happens rather than wherever it's first noticed.
+    ///
+    /// Time: O(1)
+    pub fn check_invariants(&self) {
+        // A zero capacity buffer still has an origin, which has to be 0.
+        assert!(
+            self.origin.to_usize() < N.max(1),
+            "RingBuffer::check_invariants: origin {} is past capacity {}",
+            self.origin.to_usize(),
+            N
+        );
+        assert!(
+            self.length <= N,
+            "RingBuffer::check_invariants: length {} is past capacity {}",
+            self.length,
+            N
+        );
+    }
+
     /// Get an iterator over references to the items in the ring buffer in
     /// order.
     #[inline]
@@ -411,6 +489,7 @@ impl<A, const N: usize> RingBuffer<A, N> {
             unsafe { self.force_write(self.raw(self.length), value) }
             self.length += 1;
         }
+        debug_invariants!(self);
     }
 
     /// Push a value to the front of the buffer.
@@ -426,6 +505,7 @@ impl<A, const N: usize> RingBuffer<A, N> {
             self.length += 1;
             unsafe { self.force_write(origin, value) }
         }
+        debug_invariants!(self);
     }
 
     /// Pop a value from the back of the buffer.
@@ -438,7 +518,9 @@ impl<A, const N: usize> RingBuffer<A, N> {
             None
         } else {
             self.length -= 1;
-            Some(unsafe { self.force_read(self.raw(self.length)) })
+            let value = unsafe { self.force_read(self.raw(self.length)) };
+            debug_invariants!(self);
+            Some(value)
         }
     }
 
@@ -453,7 +535,9 @@ impl<A, const N: usize> RingBuffer<A, N> {
         } else {
             self.length -= 1;
             let index = self.origin.inc();
-            Some(unsafe { self.force_read(index) })
+            let value = unsafe { self.force_read(index) };
+            debug_invariants!(self);
+            Some(value)
         }
     }
 
@@ -473,6 +557,7 @@ impl<A, const N: usize> RingBuffer<A, N> {
             self.origin += index;
             self.length -= index;
         }
+        debug_invariants!(self);
     }
 
     /// Discard all items from `index` onward.
@@ -491,6 +576,7 @@ impl<A, const N: usize> RingBuffer<A, N> {
             unsafe { self.force_drop(i) }
         }
         self.length = index;
+        debug_invariants!(self);
     }
 
     /// Split a buffer into two, the original buffer containing
@@ -510,9 +596,12 @@ impl<A, const N: usize> RingBuffer<A, N> {
         }
         let mut right = Self::new();
         let length = self.length - index;
-        unsafe { right.copy_from(self, self.raw(index), 0.into(), length) };
-        self.length = index;
-        right.length = length;
+        unsafe {
+            right
+                .raw_ring()
+                .take_back_from(&mut self.raw_ring(), length)
+        };
+        debug_invariants!(self, right);
         right
     }
 
@@ -528,6 +617,48 @@ impl<A, const N: usize> RingBuffer<A, N> {
         self.drain_from_front(other, other.len());
     }
 
+    /// Remove all items from `other`, a buffer of any capacity, and append
+    /// them to the back of `self`.
+    ///
+    /// Panics if the capacity of `self` is exceeded.
+    ///
+    /// `other` will be an empty buffer after this operation.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn append_from<const M: usize>(&mut self, other: &mut RingBuffer<A, M>) {
+        if self.len() + other.len() > Self::CAPACITY {
+            panic!("RingBuffer::append_from: chunk size overflow");
+        }
+        let (front, back) = other.raw_slices();
+        for slice in &[front, back] {
+            if !slice.is_empty() {
+                unsafe { self.copy_from_slice(slice, self.origin + self.length) };
+                self.length += slice.len();
+            }
+        }
+        other.length = 0;
+        debug_invariants!(self, other);
+    }
+
+    /// Move the contents of the buffer into a new buffer with a capacity of
+    /// `M`.
+    ///
+    /// If the items don't fit in the new buffer, you get the old one back as
+    /// the error.
+    ///
+    /// Time: O(n)
+    pub fn into_capacity<const M: usize>(mut self) -> Result<RingBuffer<A, M>, Self> {
+        if self.len() > M {
+            return Err(self);
+        }
+        let mut out = RingBuffer::new();
+        unsafe { self.copy_to_ptr(out.data.as_mut_ptr().cast()) };
+        out.length = self.length;
+        self.length = 0;
+        debug_invariants!(out);
+        Ok(out)
+    }
+
     /// Remove `count` items from the front of `other` and append them to the
     /// back of `self`.
     ///
@@ -544,10 +675,11 @@ impl<A, const N: usize> RingBuffer<A, N> {
         if other_len < count {
             panic!("RingBuffer::drain_from_front: index out of bounds");
         }
-        unsafe { self.copy_from(other, other.origin, self.raw(self.len()), count) };
-        other.origin += count;
-        other.length -= count;
-        self.length += count;
+        unsafe {
+            self.raw_ring()
+                .take_front_from(&mut other.raw_ring(), count)
+        };
+        debug_invariants!(self, other);
     }
 
     /// Remove `count` items from the back of `other` and append them to the
@@ -566,11 +698,8 @@ impl<A, const N: usize> RingBuffer<A, N> {
         if other_len < count {
             panic!("RingBuffer::drain_from_back: index out of bounds");
         }
-        self.origin -= count;
-        let source_index = other.origin + (other.len() - count);
-        unsafe { self.copy_from(other, source_index, self.origin, count) };
-        other.length -= count;
-        self.length += count;
+        unsafe { self.raw_ring().take_back_from(&mut other.raw_ring(), count) };
+        debug_invariants!(self, other);
     }
 
     /// Insert a new value at index `index`, shifting all the following values
@@ -586,33 +715,8 @@ impl<A, const N: usize> RingBuffer<A, N> {
         if index > self.len() {
             panic!("RingBuffer::insert: index out of bounds");
         }
-        if index == 0 {
-            return self.push_front(value);
-        }
-        if index == self.len() {
-            return self.push_back(value);
-        }
-        let right_count = self.len() - index;
-        // Check which side has fewer elements to shift.
-        if right_count < index {
-            // Shift to the right.
-            let mut i = self.raw(self.len() - 1);
-            let target = self.raw(index);
-            while i != target {
-                unsafe { self.force_write(i + 1, self.force_read(i)) };
-                i -= 1;
-            }
-            unsafe { self.force_write(target + 1, self.force_read(target)) };
-            self.length += 1;
-        } else {
-            // Shift to the left.
-            self.origin -= 1;
-            self.length += 1;
-            for i in self.range().take(index) {
-                unsafe { self.force_write(i, self.force_read(i + 1)) };
-            }
-        }
-        unsafe { self.force_write(self.raw(index), value) };
+        unsafe { self.raw_ring().insert(index, value) };
+        debug_invariants!(self);
     }
 
     /// Insert a new value into the buffer in sorted order.
@@ -661,47 +765,8 @@ impl<A, const N: usize> RingBuffer<A, N> {
         if index > self.len() {
             panic!("Chunk::insert_from: index out of bounds");
         }
-        if index == self.len() {
-            self.extend(iter);
-            return;
-        }
-        let right_count = self.len() - index;
-        // Check which side has fewer elements to shift.
-        if right_count < index {
-            // Shift to the right.
-            let mut i = self.raw(self.len() - 1);
-            let target = self.raw(index);
-            while i != target {
-                unsafe { self.force_write(i + insert_size, self.force_read(i)) };
-                i -= 1;
-            }
-            unsafe { self.force_write(target + insert_size, self.force_read(target)) };
-            self.length += insert_size;
-        } else {
-            // Shift to the left.
-            self.origin -= insert_size;
-            self.length += insert_size;
-            for i in self.range().take(index) {
-                unsafe { self.force_write(i, self.force_read(i + insert_size)) };
-            }
-        }
-        let mut index = self.raw(index);
-        // Panic safety: unless and until we fill it fully, there's a hole somewhere in the middle
-        // and the destructor would drop non-existing elements. Therefore we pretend to be empty
-        // for a while (and leak the elements instead in case something bad happens).
-        let mut inserted = 0;
-        let length = replace(&mut self.length, 0);
-        for value in iter.take(insert_size) {
-            unsafe { self.force_write(index, value) };
-            index += 1;
-            inserted += 1;
-        }
-        // This would/could create a hole in the middle if it was less
-        assert_eq!(
-            inserted, insert_size,
-            "Iterator has fewer elements than advertised",
-        );
-        self.length = length;
+        unsafe { self.raw_ring().insert_from(index, iter) };
+        debug_invariants!(self);
     }
 
     /// Remove the value at index `index`, shifting all the following values to
@@ -716,28 +781,8 @@ impl<A, const N: usize> RingBuffer<A, N> {
         if index >= self.len() {
             panic!("RingBuffer::remove: index out of bounds");
         }
-        let value = unsafe { self.force_read(self.raw(index)) };
-        let right_count = self.len() - index;
-        // Check which side has fewer elements to shift.
-        if right_count < index {
-            // Shift from the right.
-            self.length -= 1;
-            let mut i = self.raw(index);
-            let target = self.raw(self.len());
-            while i != target {
-                unsafe { self.force_write(i, self.force_read(i + 1)) };
-                i += 1;
-            }
-        } else {
-            // Shift from the left.
-            let mut i = self.raw(index);
-            while i != self.origin {
-                unsafe { self.force_write(i, self.force_read(i - 1)) };
-                i -= 1;
-            }
-            self.origin += 1;
-            self.length -= 1;
-        }
+        let value = unsafe { self.raw_ring().remove(index) };
+        debug_invariants!(self);
         value
     }
 
@@ -746,6 +791,62 @@ impl<A, const N: usize> RingBuffer<A, N> {
         Drain { buffer: self }
     }
 
+    /// Get the uninitialised space after the last element of the buffer.
+    ///
+    /// Because the free space may wrap around the end of the underlying array,
+    /// it's returned as two slices, the second of which follows the first
+    /// and may be empty. You can write values into the start of this pair and
+    /// then call [`commit_back`][commit_back] to make them part of the buffer,
+    /// which lets you fill it without initialising a temporary buffer first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::RingBuffer;
+    /// # use std::mem::MaybeUninit;
+    /// let mut buffer: RingBuffer<u8, 8> = (0..6).collect();
+    /// buffer.drop_left(4);
+    /// let (first, second) = buffer.spare_capacity_mut();
+    /// assert_eq!((2, 4), (first.len(), second.len()));
+    /// first[0] = MaybeUninit::new(6);
+    /// first[1] = MaybeUninit::new(7);
+    /// second[0] = MaybeUninit::new(8);
+    /// unsafe { buffer.commit_back(3) };
+    /// assert_eq!(buffer, vec![4, 5, 6, 7, 8]);
+    /// ```
+    ///
+    /// [commit_back]: #method.commit_back
+    pub fn spare_capacity_mut(&mut self) -> (&mut [MaybeUninit<A>], &mut [MaybeUninit<A>]) {
+        let free = Self::CAPACITY - self.len();
+        if free == 0 {
+            return (&mut [], &mut []);
+        }
+        let start = self.raw(self.len()).to_usize();
+        let first_length = free.min(Self::CAPACITY - start);
+        let data: *mut MaybeUninit<A> = self.data.as_mut_ptr().cast();
+        unsafe {
+            (
+                core::slice::from_raw_parts_mut(data.add(start), first_length),
+                core::slice::from_raw_parts_mut(data, free - first_length),
+            )
+        }
+    }
+
+    /// Extend the buffer at the back over the first `count` slots of
+    /// [`spare_capacity_mut`][spare_capacity_mut].
+    ///
+    /// # Safety
+    ///
+    /// `count` must be no larger than the combined length of the two spare
+    /// capacity slices, and those `count` slots must have been initialised.
+    ///
+    /// [spare_capacity_mut]: #method.spare_capacity_mut
+    pub unsafe fn commit_back(&mut self, count: usize) {
+        debug_assert!(self.length + count <= Self::CAPACITY);
+        self.length += count;
+        debug_invariants!(self);
+    }
+
     /// Discard the contents of the buffer.
     ///
     /// Time: O(n)
@@ -755,6 +856,52 @@ impl<A, const N: usize> RingBuffer<A, N> {
         }
         self.origin = 0.into();
         self.length = 0;
+        debug_invariants!(self);
+    }
+
+    /// Construct a buffer of the same capacity by calling `f` on each item in
+    /// turn, from front to back.
+    ///
+    /// If `f` panics, the items it's already returned are dropped along with
+    /// the ones it hasn't seen yet.
+    ///
+    /// Time: O(n)
+    #[must_use]
+    pub fn map<B, F>(self, mut f: F) -> RingBuffer<B, N>
+    where
+        F: FnMut(A) -> B,
+    {
+        match self.try_map(|value| Ok::<_, Infallible>(f(value))) {
+            Ok(buffer) => buffer,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Construct a buffer of the same capacity by calling `f` on each item in
+    /// turn, from front to back, stopping at the first error `f` returns.
+    ///
+    /// On error, or if `f` panics, the items it's already returned are
+    /// dropped along with the ones it hasn't seen yet.
+    ///
+    /// Time: O(n)
+    pub fn try_map<B, E, F>(mut self, mut f: F) -> Result<RingBuffer<B, N>, E>
+    where
+        F: FnMut(A) -> Result<B, E>,
+    {
+        let mut out = RingBuffer::new();
+        out.origin = RawIndex::from(self.origin.to_usize());
+        while !self.is_empty() {
+            // Move the origin past the value first, so `self` doesn't drop it
+            // again if `f` panics.
+            let value = unsafe { self.force_read(self.origin) };
+            self.origin += 1;
+            self.length -= 1;
+            let value = f(value)?;
+            unsafe { out.force_write(out.origin + out.length, value) };
+            out.length += 1;
+        }
+        debug_invariants!(out);
+        Ok(out)
     }
 }
 
@@ -812,10 +959,12 @@ impl<A, const N: usize> IndexMut<usize> for RingBuffer<A, N> {
     }
 }
 
-impl<A: PartialEq, const N: usize> PartialEq for RingBuffer<A, N> {
+impl<A: PartialEq, const M: usize, const N: usize> PartialEq<RingBuffer<A, M>>
+    for RingBuffer<A, N>
+{
     #[inline]
     #[must_use]
-    fn eq(&self, other: &Self) -> bool {
+    fn eq(&self, other: &RingBuffer<A, M>) -> bool {
         self.len() == other.len() && self.iter().eq(other.iter())
     }
 }
@@ -861,6 +1010,89 @@ impl<A: PartialOrd, const N: usize> PartialOrd for RingBuffer<A, N> {
     }
 }
 
+impl<A, const N: usize> From<Chunk<A, N>> for RingBuffer<A, N> {
+    /// Move the contents of a chunk into a ring buffer of the same capacity.
+    ///
+    /// Time: O(n)
+    fn from(mut chunk: Chunk<A, N>) -> Self {
+        let mut buffer = Self::new();
+        if !chunk.is_empty() {
+            unsafe {
+                buffer.copy_from_slice(chunk.as_slice(), RawIndex::zero());
+                buffer.length = chunk.len();
+                chunk.set_len(0);
+            }
+        }
+        buffer
+    }
+}
+
+impl<A, const N: usize> From<RingBuffer<A, N>> for Chunk<A, N> {
+    /// Move the contents of a ring buffer into a chunk of the same capacity.
+    ///
+    /// Time: O(n)
+    fn from(mut buffer: RingBuffer<A, N>) -> Self {
+        let mut chunk = Chunk::new();
+        unsafe {
+            buffer.copy_to_ptr(chunk.spare_capacity_back_mut().as_mut_ptr().cast());
+            chunk.commit_back(buffer.len());
+        }
+        buffer.length = 0;
+        chunk
+    }
+}
+
+impl<A, PrimSlice, const N: usize> PartialOrd<PrimSlice> for RingBuffer<A, N>
+where
+    PrimSlice: Borrow<[A]>,
+    A: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &PrimSlice) -> Option<Ordering> {
+        self.iter().partial_cmp(other.borrow().iter())
+    }
+}
+
+impl<A, const M: usize, const N: usize> PartialEq<RingBuffer<A, M>> for Chunk<A, N>
+where
+    A: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &RingBuffer<A, M>) -> bool {
+        other == self
+    }
+}
+
+impl<A, const M: usize, const N: usize> PartialOrd<RingBuffer<A, M>> for Chunk<A, N>
+where
+    A: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &RingBuffer<A, M>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<A, T, const N: usize> PartialEq<RingBuffer<A, N>> for InlineArray<A, T>
+where
+    A: PartialEq,
+{
+    #[inline]
+    fn eq(&self, other: &RingBuffer<A, N>) -> bool {
+        other == self
+    }
+}
+
+impl<A, T, const N: usize> PartialOrd<RingBuffer<A, N>> for InlineArray<A, T>
+where
+    A: PartialOrd,
+{
+    #[inline]
+    fn partial_cmp(&self, other: &RingBuffer<A, N>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
 impl<A: Ord, const N: usize> Ord for RingBuffer<A, N> {
     #[inline]
     #[must_use]
@@ -903,6 +1135,134 @@ impl<A: Hash, const N: usize> Hash for RingBuffer<A, N> {
     }
 }
 
+impl<const N: usize> fmt::Write for RingBuffer<u8, N> {
+    /// Append the UTF-8 bytes of a string to the back of the buffer.
+    ///
+    /// Fails without writing anything if the buffer doesn't have room for the
+    /// whole string.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let buf = s.as_bytes();
+        if buf.len() > Self::CAPACITY - self.len() {
+            return Err(Error);
+        }
+        if !buf.is_empty() {
+            unsafe { self.copy_from_slice(buf, self.origin + self.len()) };
+            self.length += buf.len();
+        }
+        Ok(())
+    }
+}
+
+impl<A, const M: usize, const N: usize> From<[A; M]> for RingBuffer<A, N> {
+    /// Construct a ring buffer from an array of up to `N` items.
+    ///
+    /// This fails to compile if `M` is larger than `N`.
+    ///
+    /// ```rust,compile_fail
+    /// # use sized_chunks::RingBuffer;
+    /// let buffer: RingBuffer<i32, 2> = RingBuffer::from([1, 2, 3]);
+    /// ```
+    fn from(array: [A; M]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Fits::<M, N>::CHECK;
+        let array = ManuallyDrop::new(array);
+        let mut buffer = Self::new();
+        unsafe { ptr::copy_nonoverlapping(array.as_ptr(), buffer.data.as_mut_ptr().cast(), M) };
+        buffer.length = M;
+        buffer
+    }
+}
+
+impl<A, const N: usize> TryFrom<RingBuffer<A, N>> for [A; N] {
+    type Error = RingBuffer<A, N>;
+
+    /// Move the contents of a full ring buffer into an array, in order from
+    /// front to back.
+    ///
+    /// If the buffer isn't full, you get it back as the error.
+    fn try_from(buffer: RingBuffer<A, N>) -> Result<Self, Self::Error> {
+        if !buffer.is_full() {
+            return Err(buffer);
+        }
+        let buffer = ManuallyDrop::new(buffer);
+        let mut array = MaybeUninit::<[A; N]>::uninit();
+        unsafe {
+            buffer.copy_to_ptr(array.as_mut_ptr().cast());
+            Ok(array.assume_init())
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, const N: usize> TryFrom<Vec<A>> for RingBuffer<A, N> {
+    type Error = Vec<A>;
+
+    /// Move the contents of a `Vec` into a ring buffer.
+    ///
+    /// If the `Vec` has more than `N` items, you get it back as the error.
+    fn try_from(mut vec: Vec<A>) -> Result<Self, Self::Error> {
+        if vec.len() > N {
+            return Err(vec);
+        }
+        let mut buffer = Self::new();
+        if !vec.is_empty() {
+            unsafe {
+                buffer.copy_from_slice(&vec, RawIndex::zero());
+                buffer.length = vec.len();
+                vec.set_len(0);
+            }
+        }
+        Ok(buffer)
+    }
+}
+
+impl<'a, A: Clone, const N: usize> TryFrom<&'a [A]> for RingBuffer<A, N> {
+    type Error = &'a [A];
+
+    /// Clone the contents of a slice into a ring buffer.
+    ///
+    /// If the slice has more than `N` items, you get it back as the error.
+    fn try_from(slice: &'a [A]) -> Result<Self, Self::Error> {
+        if slice.len() > N {
+            return Err(slice);
+        }
+        Ok(slice.iter().cloned().collect())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, const N: usize> TryFrom<VecDeque<A>> for RingBuffer<A, N> {
+    type Error = VecDeque<A>;
+
+    /// Move the contents of a `VecDeque` into a ring buffer, keeping their
+    /// order from front to back.
+    ///
+    /// If the `VecDeque` has more than `N` items, you get it back as the
+    /// error.
+    fn try_from(deque: VecDeque<A>) -> Result<Self, Self::Error> {
+        if deque.len() > N {
+            return Err(deque);
+        }
+        Ok(deque.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, const N: usize> From<RingBuffer<A, N>> for Vec<A> {
+    #[inline]
+    fn from(buffer: RingBuffer<A, N>) -> Self {
+        buffer.into_iter().collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, const N: usize> From<RingBuffer<A, N>> for VecDeque<A> {
+    #[inline]
+    fn from(buffer: RingBuffer<A, N>) -> Self {
+        buffer.into_iter().collect()
+    }
+}
+
 #[cfg(feature = "std")]
 impl<const N: usize> std::io::Write for RingBuffer<u8, N> {
     fn write(&mut self, mut buf: &[u8]) -> std::io::Result<usize> {
@@ -989,6 +1349,209 @@ mod test {
         assert!(Some(RingBuffer::<Box<()>, 64>::new()).is_some());
     }
 
+    #[test]
+    fn const_constructors() {
+        static EMPTY: RingBuffer<u8, 16> = RingBuffer::new();
+        const ARRAY: RingBuffer<u8, 3> = RingBuffer::from_array([1, 2, 3]);
+        assert!(EMPTY.is_empty());
+        assert_eq!(ARRAY, vec![1, 2, 3]);
+        assert!(ARRAY.is_full());
+    }
+
+    #[test]
+    fn array_conversions() {
+        let buffer: RingBuffer<i32, 4> = RingBuffer::from([1, 2, 3]);
+        assert_eq!(buffer, vec![1, 2, 3]);
+        let buffer: RingBuffer<i32, 0> = RingBuffer::from([]);
+        assert!(buffer.is_empty());
+
+        let mut buffer: RingBuffer<i32, 3> = RingBuffer::from([1, 2]);
+        buffer = <[i32; 3]>::try_from(buffer).unwrap_err();
+        assert_eq!(buffer, vec![1, 2]);
+
+        // Wrapped around the end of the storage
+        buffer.pop_front();
+        buffer.push_back(3);
+        buffer.push_back(4);
+        assert_eq!(Ok([2, 3, 4]), <[i32; 3]>::try_from(buffer));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vec_conversions() {
+        let vec = vec!["a".to_string(), "b".to_string()];
+        let buffer = RingBuffer::<String, 2>::try_from(vec).unwrap();
+        assert!(buffer.iter().eq(["a", "b"].iter()));
+        let vec = Vec::from(buffer);
+        assert_eq!(vec!["a", "b"], vec);
+
+        let vec = RingBuffer::<String, 1>::try_from(vec).unwrap_err();
+        assert_eq!(vec!["a", "b"], vec);
+
+        let buffer = RingBuffer::<i32, 0>::try_from(Vec::new()).unwrap();
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn slice_conversions() {
+        let slice: &[_] = &[1, 2, 3];
+        let buffer = RingBuffer::<i32, 4>::try_from(slice).unwrap();
+        assert_eq!(buffer, slice);
+        assert_eq!(Err(slice), RingBuffer::<i32, 2>::try_from(slice).map(|_| ()));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vec_deque_conversions() {
+        let mut deque = VecDeque::new();
+        deque.push_back(2);
+        deque.push_back(3);
+        deque.push_front(1);
+        let mut buffer = RingBuffer::<i32, 3>::try_from(deque).unwrap();
+        assert_eq!(buffer, vec![1, 2, 3]);
+
+        buffer.pop_front();
+        buffer.push_back(4);
+        let mut deque = VecDeque::from(buffer);
+        assert!(deque.iter().eq([2, 3, 4].iter()));
+
+        deque.push_front(1);
+        let deque = RingBuffer::<i32, 3>::try_from(deque).unwrap_err();
+        assert_eq!(4, deque.len());
+    }
+
+    #[test]
+    fn map() {
+        let mut buffer = RingBuffer::<i32, 4>::from([0, 0, 1]);
+        buffer.pop_front();
+        buffer.pop_front();
+        buffer.push_back(2);
+        buffer.push_back(3);
+        let mapped = buffer.map(|i| i.to_string());
+        assert!(mapped.iter().eq(["1", "2", "3"].iter()));
+
+        let buffer = RingBuffer::<i32, 4>::from([1, 2, 3]);
+        let result = buffer.try_map(|i| if i < 2 { Ok(i) } else { Err(i) });
+        assert_eq!(Err(2), result);
+    }
+
+    #[test]
+    fn drop_zero_capacity() {
+        let buffer = RingBuffer::<String, 0>::new();
+        assert!(buffer.iter().next().is_none());
+        drop(buffer);
+    }
+
+    #[test]
+    fn chunk_conversions() {
+        let mut chunk = Chunk::<i32, 4>::from([1, 2, 3, 4]);
+        chunk.pop_front();
+        let mut buffer = RingBuffer::from(chunk);
+        assert_eq!(buffer, vec![2, 3, 4]);
+
+        buffer.push_back(5);
+        buffer.pop_front();
+        buffer.push_back(6);
+        let chunk = Chunk::from(buffer);
+        assert_eq!(&[3, 4, 5, 6], chunk.as_slice());
+
+        let buffer = RingBuffer::from(Chunk::<String, 0>::new());
+        assert!(Chunk::from(buffer).is_empty());
+    }
+
+    #[test]
+    fn cross_container_comparisons() {
+        let buffer = RingBuffer::<i32, 8>::from([1, 2, 3]);
+        let chunk = Chunk::<i32, 4>::from([1, 2, 3]);
+        let array = InlineArray::<i32, [i32; 8]>::from([1, 2, 4]);
+        assert_eq!(buffer, chunk);
+        assert_eq!(chunk, buffer);
+        assert_ne!(buffer, array);
+        assert_ne!(array, buffer);
+        let longer = Chunk::<i32, 16>::from([1, 2, 3, 0]);
+        assert_ne!(chunk, longer);
+        assert!(buffer < array);
+        assert!(array > buffer);
+        assert!(chunk < array);
+        assert!(array > chunk);
+        assert!(buffer <= chunk && chunk <= buffer);
+        assert!(chunk < longer && buffer < longer);
+    }
+
+    #[test]
+    fn capacity_changes() {
+        let mut small = RingBuffer::<String, 4>::new();
+        small.push_back("b".to_string());
+        small.push_front("a".to_string());
+        let mut large: RingBuffer<String, 8> = small.into_capacity().unwrap();
+        assert!(large.iter().eq(["a", "b"].iter()));
+
+        let mut other = RingBuffer::<String, 3>::new();
+        other.push_back("d".to_string());
+        other.push_front("c".to_string());
+        large.pop_front();
+        large.append_from(&mut other);
+        assert!(other.is_empty());
+        assert!(large.iter().eq(["b", "c", "d"].iter()));
+
+        let large = large.into_capacity::<2>().unwrap_err();
+        let exact: RingBuffer<String, 3> = large.into_capacity().unwrap();
+        assert!(exact.is_full());
+        let copy: RingBuffer<String, 16> = exact.iter().cloned().collect();
+        assert_eq!(exact, copy);
+    }
+
+    #[test]
+    fn append_from_wrapped() {
+        let mut buffer = RingBuffer::<i32, 4>::from([1, 2, 3]);
+        buffer.pop_front();
+        buffer.pop_front();
+        let mut other = RingBuffer::<i32, 2>::new();
+        other.push_back(5);
+        other.push_front(4);
+        buffer.append_from(&mut other);
+        assert_eq!(buffer, vec![3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic(expected = "RingBuffer::append_from: chunk size overflow")]
+    fn append_from_too_much() {
+        let mut buffer = RingBuffer::<i32, 4>::from([1, 2, 3]);
+        buffer.append_from(&mut RingBuffer::<i32, 8>::from([4, 5]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Slice::index: index out of bounds 2 >= 2")]
+    fn slice_index_out_of_bounds() {
+        let buffer = RingBuffer::<i32, 4>::from([1, 2, 3]);
+        let _ = buffer.slice(..2)[2];
+    }
+
+    #[test]
+    #[should_panic(expected = "SliceMut::index_mut: index out of bounds 1 >= 1")]
+    fn slice_mut_index_out_of_bounds() {
+        let mut buffer = RingBuffer::<i32, 4>::from([1, 2, 3]);
+        let (mut left, _right) = buffer.slice_mut(..).split_at(1);
+        // This used to hand out the first element of `right`.
+        left[1] = 4;
+    }
+
+    #[test]
+    fn slice_mut_split_at_keeps_halves_apart() {
+        let mut buffer = RingBuffer::<i32, 4>::from([1, 2, 3]);
+        let (mut left, right) = buffer.slice_mut(..).split_at(1);
+        left[0] = 4;
+        assert_eq!(1, left.len());
+        assert_eq!(2, right[0]);
+    }
+
+    #[test]
+    fn from_fn() {
+        let buffer = RingBuffer::<String, 3>::from_fn(|i| i.to_string());
+        assert!(buffer.iter().eq(["0", "1", "2"].iter()));
+        assert!(buffer.is_full());
+    }
+
     #[test]
     fn is_full() {
         let mut chunk = RingBuffer::<_, 64>::new();
@@ -1061,6 +1624,37 @@ mod test {
         assert_eq!(0, buffer.read(&mut read_buf).unwrap());
     }
 
+    #[test]
+    fn fmt_write() {
+        use core::fmt::Write;
+        let mut buffer: RingBuffer<u8, 8> = RingBuffer::new();
+        buffer.extend(b"xxxxxx");
+        buffer.drop_left(6);
+        buffer.write_str("hello").unwrap();
+        assert_eq!(buffer, *b"hello");
+        assert!(buffer.write_str("world").is_err());
+        assert_eq!(buffer, *b"hello");
+    }
+
+    #[test]
+    fn spare_capacity() {
+        let mut buffer: RingBuffer<u32, 8> = RingBuffer::new();
+        let (first, second) = buffer.spare_capacity_mut();
+        assert_eq!((8, 0), (first.len(), second.len()));
+        buffer.extend(0..8);
+        let (first, second) = buffer.spare_capacity_mut();
+        assert_eq!((0, 0), (first.len(), second.len()));
+        buffer.drop_left(5);
+        buffer.pop_back();
+        let (first, second) = buffer.spare_capacity_mut();
+        assert_eq!((1, 5), (first.len(), second.len()));
+        for (index, slot) in first.iter_mut().chain(second.iter_mut()).enumerate() {
+            *slot = MaybeUninit::new(index as u32 + 7);
+        }
+        unsafe { buffer.commit_back(6) };
+        assert_eq!(buffer, (5..13).collect::<Vec<_>>());
+    }
+
     #[test]
     fn clone() {
         let buffer: RingBuffer<u32, 64> = (0..50).collect();
@@ -1078,9 +1672,85 @@ mod test {
         assert_eq!(buffer, expected);
     }
 
-    use crate::tests::DropTest;
+    use crate::tests::{DropTest, FragileClone, PanickingIter};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
     use std::sync::atomic::{AtomicUsize, Ordering};
 
+    /// Insert from an iterator that panics halfway, into a buffer of six
+    /// items which wraps around the end of its storage.
+    fn panicking_insert_from(index: usize) {
+        let counter = AtomicUsize::new(0);
+        let mut buffer: RingBuffer<DropTest<'_>, 10> = RingBuffer::new();
+        for _ in 0..7 {
+            buffer.push_back(DropTest::new(&counter));
+        }
+        for _ in 0..4 {
+            buffer.pop_front();
+        }
+        for _ in 0..3 {
+            buffer.push_back(DropTest::new(&counter));
+        }
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let iter = PanickingIter {
+                counter: &counter,
+                len: 3,
+                panic_after: 1,
+            };
+            buffer.insert_from(index, iter);
+        }));
+        assert!(result.is_err());
+        assert_eq!(7, buffer.len());
+        assert_eq!(7, counter.load(Ordering::Relaxed));
+        drop(buffer);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn insert_from_panic_safety() {
+        // Shifting the front to the left
+        panicking_insert_from(1);
+        // Shifting the back to the right
+        panicking_insert_from(5);
+    }
+
+    #[test]
+    fn clone_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut buffer: RingBuffer<_, 4> = RingBuffer::new();
+        buffer.push_back(FragileClone::new(&counter, false));
+        buffer.push_back(FragileClone::new(&counter, true));
+        buffer.push_front(FragileClone::new(&counter, false));
+        assert!(catch_unwind(AssertUnwindSafe(|| buffer.clone())).is_err());
+        assert_eq!(3, counter.load(Ordering::Relaxed));
+        drop(buffer);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn collect_from_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut iter = PanickingIter {
+            counter: &counter,
+            len: 5,
+            panic_after: 2,
+        };
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            RingBuffer::<_, 8>::collect_from(&mut iter, 5)
+        }));
+        assert!(result.is_err());
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    #[should_panic(expected = "RingBuffer::check_invariants: length 5 is past capacity 4")]
+    fn check_invariants() {
+        // Don't try to drop the broken buffer while unwinding.
+        let mut buffer = ManuallyDrop::new(RingBuffer::<i32, 4>::from([1, 2]));
+        buffer.check_invariants();
+        buffer.length = 5;
+        buffer.check_invariants();
+    }
+
     #[test]
     fn dropping() {
         let counter = AtomicUsize::new(0);
@@ -1101,6 +1771,24 @@ mod test {
         assert_eq!(0, counter.load(Ordering::Relaxed));
     }
 
+    #[test]
+    fn map_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        crate::tests::map_panic_safety(
+            &counter,
+            |counter| {
+                // Wrap the buffer around the end of its storage.
+                let mut buffer: RingBuffer<_, 8> = (0..6).map(|_| DropTest::new(counter)).collect();
+                buffer.push_front(DropTest::new(counter));
+                buffer.push_front(DropTest::new(counter));
+                buffer
+            },
+            |buffer, f| {
+                let _ = buffer.map(f);
+            },
+        );
+    }
+
     #[test]
     #[should_panic(expected = "assertion failed: Self::CAPACITY >= 1")]
     fn unit_on_empty() {
@@ -0,0 +1,244 @@
+use core::ptr;
+
+/// A ring buffer's storage and the indices of the items in it, borrowed from
+/// either a `RingBuffer` or a `RingBufferView`, so the two can share the code
+/// which moves items around.
+///
+/// Indices are logical, counted from the origin, unless they're called raw.
+/// Nothing here checks its arguments. The callers do that, so their panics
+/// can name the right type.
+pub(crate) struct RawRing<'a, A> {
+    base: *mut A,
+    capacity: usize,
+    origin: &'a mut usize,
+    length: &'a mut usize,
+}
+
+impl<'a, A> RawRing<'a, A> {
+    /// # Safety
+    ///
+    /// `base` must point to storage for `capacity` items, `origin` must be
+    /// less than `capacity` unless that's zero, and the `length` items from
+    /// `origin` onwards, wrapping around the end of the storage, must be
+    /// initialised.
+    #[inline]
+    pub(crate) unsafe fn new(
+        base: *mut A,
+        capacity: usize,
+        origin: &'a mut usize,
+        length: &'a mut usize,
+    ) -> Self {
+        RawRing {
+            base,
+            capacity,
+            origin,
+            length,
+        }
+    }
+
+    /// Add `count` to a raw index, wrapping around the end of the storage.
+    #[inline]
+    fn wrap_add(&self, raw: usize, count: usize) -> usize {
+        let raw = raw + count;
+        if raw >= self.capacity {
+            raw - self.capacity
+        } else {
+            raw
+        }
+    }
+
+    /// Subtract `count` from a raw index, wrapping around the start of the
+    /// storage.
+    #[inline]
+    fn wrap_sub(&self, raw: usize, count: usize) -> usize {
+        if raw >= count {
+            raw - count
+        } else {
+            raw + self.capacity - count
+        }
+    }
+
+    /// Get the raw index of a logical index.
+    #[inline]
+    fn raw(&self, index: usize) -> usize {
+        self.wrap_add(*self.origin, index)
+    }
+
+    /// Move `count` items from raw index `from` to raw index `to`, starting
+    /// with the first, which is safe when `to` comes before `from`.
+    unsafe fn copy_forward(&mut self, from: usize, to: usize, count: usize) {
+        let mut done = 0;
+        while done < count {
+            let (from, to) = (self.wrap_add(from, done), self.wrap_add(to, done));
+            let run = (count - done)
+                .min(self.capacity - from)
+                .min(self.capacity - to);
+            ptr::copy(self.base.add(from), self.base.add(to), run);
+            done += run;
+        }
+    }
+
+    /// Move `count` items from raw index `from` to raw index `to`, starting
+    /// with the last, which is safe when `to` comes after `from`.
+    unsafe fn copy_backward(&mut self, from: usize, to: usize, count: usize) {
+        let mut left = count;
+        while left > 0 {
+            // The raw indices just past the last items left to move.
+            let from_end = self.wrap_add(from, left - 1) + 1;
+            let to_end = self.wrap_add(to, left - 1) + 1;
+            let run = left.min(from_end).min(to_end);
+            ptr::copy(
+                self.base.add(from_end - run),
+                self.base.add(to_end - run),
+                run,
+            );
+            left -= run;
+        }
+    }
+
+    /// Copy `count` items from logical index `from` in `self` to logical
+    /// index `to` in `target`, leaving both rings' indices alone.
+    unsafe fn copy_to(&self, target: &mut RawRing<'_, A>, from: usize, to: usize, count: usize) {
+        let mut done = 0;
+        while done < count {
+            let (from, to) = (self.raw(from + done), target.raw(to + done));
+            let run = (count - done)
+                .min(self.capacity - from)
+                .min(target.capacity - to);
+            ptr::copy_nonoverlapping(self.base.add(from), target.base.add(to), run);
+            done += run;
+        }
+    }
+
+    /// Move `count` items from the front of `other` onto the back of `self`.
+    ///
+    /// # Safety
+    ///
+    /// `self` must have room for `count` more items, and `other` must have at
+    /// least `count` items.
+    pub(crate) unsafe fn take_front_from(&mut self, other: &mut RawRing<'_, A>, count: usize) {
+        other.copy_to(self, 0, *self.length, count);
+        *other.origin = other.raw(count);
+        *other.length -= count;
+        *self.length += count;
+    }
+
+    /// Move `count` items from the back of `other` onto the front of `self`.
+    ///
+    /// # Safety
+    ///
+    /// `self` must have room for `count` more items, and `other` must have at
+    /// least `count` items.
+    pub(crate) unsafe fn take_back_from(&mut self, other: &mut RawRing<'_, A>, count: usize) {
+        *self.origin = self.wrap_sub(*self.origin, count);
+        *self.length += count;
+        other.copy_to(self, *other.length - count, 0, count);
+        *other.length -= count;
+    }
+
+    /// Open a gap of `count` slots at index `index`, shifting whichever side
+    /// of it is shorter, and return the gap's raw index.
+    unsafe fn open_gap(&mut self, index: usize, count: usize) -> usize {
+        let len = *self.length;
+        if len - index < index {
+            let from = self.raw(index);
+            self.copy_backward(from, self.wrap_add(from, count), len - index);
+        } else {
+            let origin = *self.origin;
+            *self.origin = self.wrap_sub(origin, count);
+            self.copy_forward(origin, *self.origin, index);
+        }
+        *self.length += count;
+        self.raw(index)
+    }
+
+    /// Insert a value at index `index`, shifting whichever side of it is
+    /// shorter.
+    ///
+    /// # Safety
+    ///
+    /// The ring mustn't be full, and `index` must be no greater than its
+    /// length.
+    pub(crate) unsafe fn insert(&mut self, index: usize, value: A) {
+        let raw = self.open_gap(index, 1);
+        self.base.add(raw).write(value);
+    }
+
+    /// Insert the values from `iter` at index `index`, shifting whichever
+    /// side of it is shorter.
+    ///
+    /// # Safety
+    ///
+    /// The ring must have room for every value in `iter`, and `index` must
+    /// be no greater than its length.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator panics, or yields fewer values than it advertised, the
+    /// values after the gap are moved back over the part of it that wasn't
+    /// written, so the ring is left holding its original values and whatever
+    /// the iterator managed to yield.
+    pub(crate) unsafe fn insert_from<I>(&mut self, index: usize, iter: I)
+    where
+        I: ExactSizeIterator<Item = A>,
+    {
+        // Panic safety: until the gap between `next` and `end` is filled, the
+        // ring would drop uninitialised values, so if we unwind out of here
+        // we shift the values after it back over what's left of it.
+        struct Hole<'b, 'a, A> {
+            ring: &'b mut RawRing<'a, A>,
+            next: usize,
+            end: usize,
+        }
+
+        impl<'b, 'a, A> Drop for Hole<'b, 'a, A> {
+            fn drop(&mut self) {
+                let unfilled = self.end - self.next;
+                if unfilled > 0 {
+                    let (from, to) = (self.ring.raw(self.end), self.ring.raw(self.next));
+                    let count = *self.ring.length - self.end;
+                    unsafe { self.ring.copy_forward(from, to, count) };
+                    *self.ring.length -= unfilled;
+                }
+            }
+        }
+
+        let len = iter.len();
+        self.open_gap(index, len);
+        let mut hole = Hole {
+            ring: self,
+            next: index,
+            end: index + len,
+        };
+        for value in iter.take(len) {
+            let raw = hole.ring.raw(hole.next);
+            hole.ring.base.add(raw).write(value);
+            hole.next += 1;
+        }
+        assert_eq!(
+            hole.end, hole.next,
+            "ExactSizeIterator yielded fewer values than advertised",
+        );
+    }
+
+    /// Remove the value at index `index`, shifting whichever side of it is
+    /// shorter.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than the ring's length.
+    pub(crate) unsafe fn remove(&mut self, index: usize) -> A {
+        let len = *self.length;
+        let raw = self.raw(index);
+        let value = self.base.add(raw).read();
+        if len - index < index {
+            self.copy_forward(self.wrap_add(raw, 1), raw, len - index - 1);
+        } else {
+            let origin = *self.origin;
+            self.copy_backward(origin, self.wrap_add(origin, 1), index);
+            *self.origin = self.wrap_add(origin, 1);
+        }
+        *self.length -= 1;
+        value
+    }
+}
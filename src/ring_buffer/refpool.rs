@@ -1,8 +1,9 @@
-use core::mem::MaybeUninit;
+use core::mem::{self, MaybeUninit};
 
 use ::refpool::{PoolClone, PoolDefault};
 
 use crate::ring_buffer::index::RawIndex;
+use crate::util::DropOnUnwind;
 use crate::RingBuffer;
 
 impl<A, const N: usize> PoolDefault for RingBuffer<A, N> {
@@ -26,19 +27,27 @@ where
         let data_ptr: *mut _ = &mut (*ptr).data;
         let data_ptr: *mut A = (*data_ptr).as_mut_ptr().cast();
         origin_ptr.write(self.origin);
-        length_ptr.write(self.length);
+        length_ptr.write(0);
+        // Panic safety: `target` holds a valid buffer of the items cloned so
+        // far at every step, which the guard drops if a clone panics.
+        let guard = DropOnUnwind(ptr);
         for index in self.range() {
             data_ptr
                 .add(index.to_usize())
                 .write((*self.ptr(index)).clone());
+            *length_ptr += 1;
         }
+        mem::forget(guard);
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::tests::FragileClone;
     use ::refpool::{Pool, PoolRef};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::iter::FromIterator;
 
     #[test]
@@ -61,4 +70,19 @@ mod test {
         assert_eq!(ref2, ref3);
         assert!(!PoolRef::ptr_eq(&ref1, &ref2));
     }
+
+    #[test]
+    fn clone_uninit_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut chunk: RingBuffer<_, 4> = RingBuffer::new();
+        chunk.push_back(FragileClone::new(&counter, false));
+        chunk.push_back(FragileClone::new(&counter, true));
+        chunk.push_front(FragileClone::new(&counter, false));
+        let pool = Pool::new(4);
+        let result = catch_unwind(AssertUnwindSafe(|| PoolRef::clone_from(&pool, &chunk)));
+        assert!(result.is_err());
+        assert_eq!(3, counter.load(Ordering::Relaxed));
+        drop(chunk);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
 }
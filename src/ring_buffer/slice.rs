@@ -164,7 +164,14 @@ impl<'a, A: 'a, const N: usize> Index<usize> for Slice<'a, A, N> {
     #[inline]
     #[must_use]
     fn index(&self, index: usize) -> &Self::Output {
-        self.buffer.index(self.range.start + index)
+        if index >= self.len() {
+            panic!(
+                "Slice::index: index out of bounds {} >= {}",
+                index,
+                self.len()
+            );
+        }
+        unsafe { self.get_unchecked(index) }
     }
 }
 
@@ -442,7 +449,14 @@ impl<'a, A: 'a, const N: usize> Index<usize> for SliceMut<'a, A, N> {
     #[inline]
     #[must_use]
     fn index(&self, index: usize) -> &Self::Output {
-        self.buffer.index(self.range.start + index)
+        if index >= self.len() {
+            panic!(
+                "SliceMut::index: index out of bounds {} >= {}",
+                index,
+                self.len()
+            );
+        }
+        unsafe { self.get_unchecked(index) }
     }
 }
 
@@ -450,7 +464,14 @@ impl<'a, A: 'a, const N: usize> IndexMut<usize> for SliceMut<'a, A, N> {
     #[inline]
     #[must_use]
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        self.buffer.index_mut(self.range.start + index)
+        if index >= self.len() {
+            panic!(
+                "SliceMut::index_mut: index out of bounds {} >= {}",
+                index,
+                self.len()
+            );
+        }
+        unsafe { self.get_unchecked_mut(index) }
     }
 }
 
@@ -0,0 +1,890 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use core::cmp::Ordering;
+use core::fmt::{Debug, Error, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::{Chain, FusedIterator};
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::{Index, IndexMut};
+use core::ptr;
+use core::slice::{from_raw_parts, from_raw_parts_mut, Iter as SliceIter, IterMut as SliceIterMut};
+
+use array_ops::{Array, ArrayMut, HasLength};
+
+use super::raw::RawRing;
+
+/// A ring buffer over a borrowed buffer.
+///
+/// This works like a [`RingBuffer`][RingBuffer], with constant time pushes
+/// and pops at both ends, except that instead of an inline array it keeps
+/// its items in a slice of `MaybeUninit<A>` you lend it. The capacity is the
+/// length of the slice.
+///
+/// The view owns the items it holds, and drops them when it's dropped. Use
+/// [`into_raw_parts`][into_raw_parts] to hand them back to the buffer
+/// instead, and [`from_raw_parts`][from_raw_parts] to pick them up again.
+///
+/// It moves items around with the same code as `RingBuffer`, and implements
+/// the same [`Array`][Array] and [`ArrayMut`][ArrayMut] traits.
+/// [`split_off`][split_off] needs a second buffer to put the split off items
+/// in.
+///
+/// # Feature Flag
+///
+/// To use this data structure, you need to enable the `ringbuffer` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// # use core::mem::MaybeUninit;
+/// # use sized_chunks::RingBufferView;
+/// let mut buffer: Vec<MaybeUninit<i32>> = (0..4).map(|_| MaybeUninit::uninit()).collect();
+/// let mut ring = RingBufferView::new(&mut buffer);
+/// ring.push_back(2);
+/// ring.push_back(3);
+/// ring.push_front(1);
+/// assert_eq!(Some(1), ring.pop_front());
+/// assert_eq!(Some(&3), ring.back());
+/// ```
+///
+/// [RingBuffer]: struct.RingBuffer.html
+/// [into_raw_parts]: #method.into_raw_parts
+/// [from_raw_parts]: #method.from_raw_parts
+/// [split_off]: #method.split_off
+/// [Array]: trait.Array.html
+/// [ArrayMut]: trait.ArrayMut.html
+pub struct RingBufferView<'a, A> {
+    origin: usize,
+    length: usize,
+    data: &'a mut [MaybeUninit<A>],
+}
+
+impl<'a, A> Drop for RingBufferView<'a, A> {
+    fn drop(&mut self) {
+        let (left, right) = self.as_mut_slices();
+        unsafe {
+            ptr::drop_in_place(left);
+            ptr::drop_in_place(right);
+        }
+    }
+}
+
+impl<'a, A> RingBufferView<'a, A> {
+    /// Construct a new empty ring buffer over `buffer`.
+    ///
+    /// The capacity of the ring buffer is the length of the buffer.
+    pub fn new(buffer: &'a mut [MaybeUninit<A>]) -> Self {
+        RingBufferView {
+            origin: 0,
+            length: 0,
+            data: buffer,
+        }
+    }
+
+    /// Construct a ring buffer over `buffer` holding `length` items starting
+    /// at index `origin` and wrapping around the end of the buffer.
+    ///
+    /// # Safety
+    ///
+    /// `origin` must be in bounds for the buffer unless it's empty, `length`
+    /// must be no greater than the buffer's length, and the items in the
+    /// range must be initialised. The ring buffer takes ownership of them,
+    /// and will drop them unless you take them back with
+    /// [`into_raw_parts`][into_raw_parts].
+    ///
+    /// [into_raw_parts]: #method.into_raw_parts
+    pub unsafe fn from_raw_parts(
+        buffer: &'a mut [MaybeUninit<A>],
+        origin: usize,
+        length: usize,
+    ) -> Self {
+        let ring = RingBufferView {
+            origin,
+            length,
+            data: buffer,
+        };
+        debug_invariants!(ring);
+        ring
+    }
+
+    /// Give the buffer back without dropping the ring buffer's items, along
+    /// with the `origin` index and the length of the items in it.
+    ///
+    /// The items stay initialised in the buffer, and it's up to you to drop
+    /// them, or pass them to [`from_raw_parts`][from_raw_parts] to get the
+    /// ring buffer back.
+    ///
+    /// [from_raw_parts]: #method.from_raw_parts
+    pub fn into_raw_parts(self) -> (&'a mut [MaybeUninit<A>], usize, usize) {
+        let this = ManuallyDrop::new(self);
+        let data = unsafe { ptr::read(&this.data) };
+        (data, this.origin, this.length)
+    }
+
+    /// Get the capacity of the ring buffer, which is the length of its buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the length of the ring buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Test if the ring buffer is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Test if the ring buffer is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.length == self.capacity()
+    }
+
+    /// Check the ring buffer's internal invariants, panicking if any of them
+    /// don't hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of ring
+    /// buffers. With the `debug-invariants` feature enabled, it's called
+    /// after every method which changes the ring buffer.
+    ///
+    /// Time: O(1)
+    pub fn check_invariants(&self) {
+        assert!(
+            self.length <= self.capacity(),
+            "RingBufferView::check_invariants: length {} is past capacity {}",
+            self.length,
+            self.capacity()
+        );
+        assert!(
+            self.origin < self.capacity() || self.capacity() == 0,
+            "RingBufferView::check_invariants: origin {} is past capacity {}",
+            self.origin,
+            self.capacity()
+        );
+    }
+
+    /// Get the index into the buffer of logical index `index`.
+    #[inline]
+    fn raw(&self, index: usize) -> usize {
+        let raw = self.origin + index;
+        if raw >= self.capacity() {
+            raw - self.capacity()
+        } else {
+            raw
+        }
+    }
+
+    #[inline]
+    fn ptr(&self, index: usize) -> *const A {
+        self.data[self.raw(index)].as_ptr()
+    }
+
+    #[inline]
+    fn mut_ptr(&mut self, index: usize) -> *mut A {
+        let raw = self.raw(index);
+        self.data[raw].as_mut_ptr()
+    }
+
+    #[inline]
+    fn raw_ring(&mut self) -> RawRing<'_, A> {
+        let capacity = self.data.len();
+        let base = self.data.as_mut_ptr().cast();
+        unsafe { RawRing::new(base, capacity, &mut self.origin, &mut self.length) }
+    }
+
+    /// Push a value to the back of the ring buffer.
+    ///
+    /// Panics if the capacity of the ring buffer is exceeded.
+    ///
+    /// Time: O(1)
+    pub fn push_back(&mut self, value: A) {
+        if self.is_full() {
+            panic!("RingBufferView::push_back: can't push to a full buffer");
+        }
+        let raw = self.raw(self.length);
+        self.data[raw] = MaybeUninit::new(value);
+        self.length += 1;
+        debug_invariants!(self);
+    }
+
+    /// Push a value to the front of the ring buffer.
+    ///
+    /// Panics if the capacity of the ring buffer is exceeded.
+    ///
+    /// Time: O(1)
+    pub fn push_front(&mut self, value: A) {
+        if self.is_full() {
+            panic!("RingBufferView::push_front: can't push to a full buffer");
+        }
+        self.origin = if self.origin == 0 {
+            self.capacity() - 1
+        } else {
+            self.origin - 1
+        };
+        self.data[self.origin] = MaybeUninit::new(value);
+        self.length += 1;
+        debug_invariants!(self);
+    }
+
+    /// Pop a value from the back of the ring buffer.
+    ///
+    /// Returns `None` if the ring buffer is empty.
+    ///
+    /// Time: O(1)
+    pub fn pop_back(&mut self) -> Option<A> {
+        if self.is_empty() {
+            return None;
+        }
+        self.length -= 1;
+        let value = unsafe { self.ptr(self.length).read() };
+        debug_invariants!(self);
+        Some(value)
+    }
+
+    /// Pop a value from the front of the ring buffer.
+    ///
+    /// Returns `None` if the ring buffer is empty.
+    ///
+    /// Time: O(1)
+    pub fn pop_front(&mut self) -> Option<A> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = unsafe { self.ptr(0).read() };
+        self.origin = self.raw(1);
+        self.length -= 1;
+        debug_invariants!(self);
+        Some(value)
+    }
+
+    /// Get a reference to the value at a given index.
+    pub fn get(&self, index: usize) -> Option<&A> {
+        if index < self.length {
+            Some(unsafe { &*self.ptr(index) })
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the value at a given index.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut A> {
+        if index < self.length {
+            Some(unsafe { &mut *self.mut_ptr(index) })
+        } else {
+            None
+        }
+    }
+
+    /// Get a reference to the first value in the ring buffer.
+    pub fn front(&self) -> Option<&A> {
+        self.get(0)
+    }
+
+    /// Get a reference to the last value in the ring buffer.
+    pub fn back(&self) -> Option<&A> {
+        self.length.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Get a mutable reference to the first value in the ring buffer.
+    pub fn front_mut(&mut self) -> Option<&mut A> {
+        self.get_mut(0)
+    }
+
+    /// Get a mutable reference to the last value in the ring buffer.
+    pub fn back_mut(&mut self) -> Option<&mut A> {
+        match self.length.checked_sub(1) {
+            Some(index) => self.get_mut(index),
+            None => None,
+        }
+    }
+
+    /// Insert a new value at index `index`, shifting all the following values
+    /// to the right.
+    ///
+    /// Panics if the index is out of bounds or the ring buffer is full.
+    ///
+    /// Time: O(n) for the number of values shifted
+    pub fn insert(&mut self, index: usize, value: A) {
+        if self.is_full() {
+            panic!("RingBufferView::insert: buffer is full");
+        }
+        if index > self.length {
+            panic!("RingBufferView::insert: index out of bounds");
+        }
+        unsafe { self.raw_ring().insert(index, value) };
+        debug_invariants!(self);
+    }
+
+    /// Insert multiple values at index `index`, shifting all the following
+    /// values to the right.
+    ///
+    /// Panics if the index is out of bounds or the ring buffer doesn't have
+    /// room for all the values.
+    ///
+    /// Time: O(m+n) where m is the number of values inserted and n is the
+    /// number of values shifted.
+    pub fn insert_from<Iterable, I>(&mut self, index: usize, iter: Iterable)
+    where
+        Iterable: IntoIterator<Item = A, IntoIter = I>,
+        I: ExactSizeIterator<Item = A>,
+    {
+        let iter = iter.into_iter();
+        let insert_size = iter.len();
+        if self.length + insert_size > self.capacity() {
+            panic!(
+                "RingBufferView::insert_from: buffer cannot fit {} values",
+                insert_size
+            );
+        }
+        if index > self.length {
+            panic!("RingBufferView::insert_from: index out of bounds");
+        }
+        unsafe { self.raw_ring().insert_from(index, iter) };
+        debug_invariants!(self);
+    }
+
+    /// Remove the value at index `index`, shifting all the following values
+    /// to the left.
+    ///
+    /// Returns the removed value.
+    ///
+    /// Panics if the index is out of bounds.
+    ///
+    /// Time: O(n) for the number of values shifted
+    pub fn remove(&mut self, index: usize) -> A {
+        if index >= self.length {
+            panic!("RingBufferView::remove: index out of bounds");
+        }
+        let value = unsafe { self.raw_ring().remove(index) };
+        debug_invariants!(self);
+        value
+    }
+
+    /// Construct an iterator that drains values from the front of the ring
+    /// buffer.
+    pub fn drain(&mut self) -> ViewDrain<'_, 'a, A> {
+        ViewDrain { ring: self }
+    }
+
+    /// Split the ring buffer into two, the original containing everything up
+    /// to `index` and the returned ring buffer, over `buffer`, containing
+    /// everything from `index` onwards.
+    ///
+    /// Panics if `index` is out of bounds, or if `buffer` is too short to
+    /// hold the items being moved into it.
+    ///
+    /// Time: O(n) for the number of items in the new ring buffer
+    pub fn split_off<'b>(
+        &mut self,
+        index: usize,
+        buffer: &'b mut [MaybeUninit<A>],
+    ) -> RingBufferView<'b, A> {
+        if index > self.length {
+            panic!("RingBufferView::split_off: index out of bounds");
+        }
+        let count = self.length - index;
+        if count > buffer.len() {
+            panic!("RingBufferView::split_off: buffer too short");
+        }
+        let mut right = RingBufferView::new(buffer);
+        unsafe { right.raw_ring().take_back_from(&mut self.raw_ring(), count) };
+        debug_invariants!(self, right);
+        right
+    }
+
+    /// Remove all items from `other` and append them to the back of `self`.
+    ///
+    /// Panics if the capacity of `self` is exceeded.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn append(&mut self, other: &mut RingBufferView<'_, A>) {
+        self.drain_from_front(other, other.len());
+    }
+
+    /// Remove `count` items from the front of `other` and append them to the
+    /// back of `self`.
+    ///
+    /// Panics if `self` doesn't have `count` items left, or if `other` has
+    /// fewer than `count` items.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn drain_from_front(&mut self, other: &mut RingBufferView<'_, A>, count: usize) {
+        if self.length + count > self.capacity() {
+            panic!("RingBufferView::drain_from_front: buffer size overflow");
+        }
+        if other.length < count {
+            panic!("RingBufferView::drain_from_front: index out of bounds");
+        }
+        unsafe {
+            self.raw_ring()
+                .take_front_from(&mut other.raw_ring(), count)
+        };
+        debug_invariants!(self, other);
+    }
+
+    /// Remove `count` items from the back of `other` and append them to the
+    /// front of `self`.
+    ///
+    /// Panics if `self` doesn't have `count` items left, or if `other` has
+    /// fewer than `count` items.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn drain_from_back(&mut self, other: &mut RingBufferView<'_, A>, count: usize) {
+        if self.length + count > self.capacity() {
+            panic!("RingBufferView::drain_from_back: buffer size overflow");
+        }
+        if other.length < count {
+            panic!("RingBufferView::drain_from_back: index out of bounds");
+        }
+        unsafe { self.raw_ring().take_back_from(&mut other.raw_ring(), count) };
+        debug_invariants!(self, other);
+    }
+
+    /// Discard every item from index `len` onwards, if the ring buffer holds
+    /// more than `len` items.
+    ///
+    /// Time: O(n) for the number of items dropped
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.length {
+            return;
+        }
+        let count = self.length - len;
+        let start = self.raw(len);
+        let first = count.min(self.capacity() - start);
+        let base = self.data.as_mut_ptr().cast::<A>();
+        // Shorten the ring buffer first, so a panicking destructor leaks the
+        // rest of the dropped items rather than dropping them twice.
+        self.length = len;
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(base.add(start), first));
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(base, count - first));
+        }
+        debug_invariants!(self);
+    }
+
+    /// Discard the contents of the ring buffer.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        let (left, right) = self.as_mut_slices();
+        let (left, right): (*mut [A], *mut [A]) = (left, right);
+        self.origin = 0;
+        self.length = 0;
+        unsafe {
+            ptr::drop_in_place(left);
+            ptr::drop_in_place(right);
+        }
+        debug_invariants!(self);
+    }
+
+    /// Get the contents of the ring buffer as a pair of slices, the first
+    /// running from the front to the end of the buffer and the second
+    /// wrapping around from the start of the buffer to the back.
+    pub fn as_slices(&self) -> (&[A], &[A]) {
+        let base = self.data.as_ptr().cast::<A>();
+        let left_len = self.length.min(self.capacity() - self.origin);
+        unsafe {
+            (
+                from_raw_parts(base.add(self.origin), left_len),
+                from_raw_parts(base, self.length - left_len),
+            )
+        }
+    }
+
+    /// Get the contents of the ring buffer as a pair of mutable slices, as
+    /// with [`as_slices`][as_slices].
+    ///
+    /// [as_slices]: #method.as_slices
+    pub fn as_mut_slices(&mut self) -> (&mut [A], &mut [A]) {
+        let base = self.data.as_mut_ptr().cast::<A>();
+        let left_len = self.length.min(self.capacity() - self.origin);
+        unsafe {
+            (
+                from_raw_parts_mut(base.add(self.origin), left_len),
+                from_raw_parts_mut(base, self.length - left_len),
+            )
+        }
+    }
+
+    /// Get an iterator over references to the items in the ring buffer in
+    /// order.
+    pub fn iter(&self) -> Chain<SliceIter<'_, A>, SliceIter<'_, A>> {
+        let (left, right) = self.as_slices();
+        left.iter().chain(right.iter())
+    }
+
+    /// Get an iterator over mutable references to the items in the ring
+    /// buffer in order.
+    pub fn iter_mut(&mut self) -> Chain<SliceIterMut<'_, A>, SliceIterMut<'_, A>> {
+        let (left, right) = self.as_mut_slices();
+        left.iter_mut().chain(right.iter_mut())
+    }
+}
+
+/// A draining iterator over a [`RingBufferView`][RingBufferView].
+///
+/// As the iterator yields each element, it's removed from the ring buffer,
+/// and when it terminates, the ring buffer will be empty.
+///
+/// [RingBufferView]: struct.RingBufferView.html
+pub struct ViewDrain<'b, 'a, A> {
+    ring: &'b mut RingBufferView<'a, A>,
+}
+
+impl<'b, 'a, A> Iterator for ViewDrain<'b, 'a, A> {
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ring.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.ring.len(), Some(self.ring.len()))
+    }
+}
+
+impl<'b, 'a, A> DoubleEndedIterator for ViewDrain<'b, 'a, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.ring.pop_back()
+    }
+}
+
+impl<'b, 'a, A> ExactSizeIterator for ViewDrain<'b, 'a, A> {}
+
+impl<'b, 'a, A> FusedIterator for ViewDrain<'b, 'a, A> {}
+
+impl<'a, A> HasLength for RingBufferView<'a, A> {
+    /// Get the length of the ring buffer.
+    #[inline]
+    fn len(&self) -> usize {
+        self.length
+    }
+}
+
+impl<'a, A> Array for RingBufferView<'a, A> {
+    /// Get a reference to the value at a given index.
+    fn get(&self, index: usize) -> Option<&A> {
+        RingBufferView::get(self, index)
+    }
+}
+
+impl<'a, A> ArrayMut for RingBufferView<'a, A> {
+    /// Get a mutable reference to the value at a given index.
+    fn get_mut(&mut self, index: usize) -> Option<&mut A> {
+        RingBufferView::get_mut(self, index)
+    }
+}
+
+impl<'a, A> Index<usize> for RingBufferView<'a, A> {
+    type Output = A;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(value) => value,
+            None => panic!(
+                "RingBufferView::index: index out of bounds {} >= {}",
+                index,
+                self.len()
+            ),
+        }
+    }
+}
+
+impl<'a, A> IndexMut<usize> for RingBufferView<'a, A> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let len = self.len();
+        match self.get_mut(index) {
+            Some(value) => value,
+            None => panic!(
+                "RingBufferView::index_mut: index out of bounds {} >= {}",
+                index, len
+            ),
+        }
+    }
+}
+
+impl<'a, 'b, A> PartialEq<RingBufferView<'b, A>> for RingBufferView<'a, A>
+where
+    A: PartialEq,
+{
+    fn eq(&self, other: &RingBufferView<'b, A>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, A> Eq for RingBufferView<'a, A> where A: Eq {}
+
+impl<'a, A> PartialOrd for RingBufferView<'a, A>
+where
+    A: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<'a, A> Ord for RingBufferView<'a, A>
+where
+    A: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<'a, A> Extend<A> for RingBufferView<'a, A> {
+    /// Append the contents of the iterator to the back of the ring buffer.
+    ///
+    /// Panics if the ring buffer exceeds its capacity.
+    ///
+    /// Time: O(n) for the length of the iterator
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = A>,
+    {
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<'a, A> Debug for RingBufferView<'a, A>
+where
+    A: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("RingBufferView")?;
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, A> Hash for RingBufferView<'a, A>
+where
+    A: Hash,
+{
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        for item in self.iter() {
+            item.hash(hasher)
+        }
+    }
+}
+
+impl<'b, 'a, A> IntoIterator for &'b RingBufferView<'a, A> {
+    type Item = &'b A;
+    type IntoIter = Chain<SliceIter<'b, A>, SliceIter<'b, A>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'b, 'a, A> IntoIterator for &'b mut RingBufferView<'a, A> {
+    type Item = &'b mut A;
+    type IntoIter = Chain<SliceIterMut<'b, A>, SliceIterMut<'b, A>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::{buffer, DropTest, PanickingIter};
+    use std::collections::VecDeque;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn wrap_around() {
+        let mut buffer = buffer(4);
+        let mut ring = RingBufferView::new(&mut buffer);
+        ring.push_back(2);
+        ring.push_back(3);
+        ring.push_front(1);
+        assert_eq!((&[1][..], &[2, 3][..]), ring.as_slices());
+        ring.insert(1, 4);
+        ring.check_invariants();
+        assert_eq!(4, ring.remove(1));
+        assert_eq!(Some(1), ring.pop_front());
+        assert_eq!((&[2, 3][..], &[][..]), ring.as_slices());
+    }
+
+    #[test]
+    fn shifting_across_the_wrap() {
+        // Start from every origin, so both halves of every shift get to wrap
+        // around the end of the buffer.
+        for origin in 0..7 {
+            for index in 0..=4 {
+                let mut buffer = buffer(7);
+                let mut ring = RingBufferView::new(&mut buffer);
+                for _ in 0..origin {
+                    ring.push_back(0);
+                    ring.pop_front();
+                }
+                ring.extend(0..4);
+                let mut model: VecDeque<i32> = (0..4).collect();
+                ring.insert(index, 10);
+                model.insert(index, 10);
+                assert!(ring.iter().eq(model.iter()));
+                ring.insert_from(index, vec![20, 21]);
+                model.insert(index, 21);
+                model.insert(index, 20);
+                assert!(ring.iter().eq(model.iter()));
+                for step in 0..3 {
+                    let remove = [index, 0, ring.len() - 1][step];
+                    assert_eq!(model.remove(remove), Some(ring.remove(remove)));
+                    ring.check_invariants();
+                    assert!(ring.iter().eq(model.iter()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn split_off_append_drain_from() {
+        let mut left_buffer = buffer(8);
+        let mut right_buffer = buffer(4);
+        let mut left = RingBufferView::new(&mut left_buffer);
+        left.extend(0..6);
+        left.push_front(-1);
+        let mut right = left.split_off(4, &mut right_buffer);
+        assert!(left.iter().eq(&[-1, 0, 1, 2]));
+        assert!(right.iter().eq(&[3, 4, 5]));
+        left.append(&mut right);
+        assert!(left.iter().eq(&[-1, 0, 1, 2, 3, 4, 5]));
+        assert!(right.is_empty());
+        right.drain_from_front(&mut left, 2);
+        right.drain_from_back(&mut left, 2);
+        left.check_invariants();
+        right.check_invariants();
+        assert!(left.iter().eq(&[1, 2, 3]));
+        assert!(right.iter().eq(&[4, 5, -1, 0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "RingBufferView::split_off: buffer too short")]
+    fn split_off_into_short_buffer() {
+        let mut left_buffer = buffer(8);
+        let mut right_buffer = buffer(2);
+        let mut left = RingBufferView::new(&mut left_buffer);
+        left.extend(0..6);
+        left.split_off(3, &mut right_buffer);
+    }
+
+    #[test]
+    fn array_ops_and_drain() {
+        let mut buffer = buffer(8);
+        let mut ring = RingBufferView::new(&mut buffer);
+        ring.extend(vec![3, 4]);
+        ring.push_front(0);
+        ring.insert_from(1, vec![1, 2]);
+        assert_eq!(Some(0), ring.set(0, 5));
+        ring.swap(0, 4);
+        *ring.front_mut().unwrap() += 1;
+        *ring.back_mut().unwrap() *= 2;
+        assert_eq!((Some(&5), Some(&10)), (ring.first(), ring.last()));
+        assert!(ring.contains(&3));
+        let mut drain = ring.drain();
+        assert_eq!(Some(10), drain.next_back());
+        assert_eq!(vec![5, 1, 2, 3], drain.collect::<Vec<_>>());
+        assert!(ring.is_empty());
+        assert_eq!(None, ring.front_mut());
+        assert_eq!(None, ring.back_mut());
+    }
+
+    #[test]
+    fn insert_from_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut buffer = buffer(16);
+        let mut ring = RingBufferView::new(&mut buffer);
+        for _ in 0..3 {
+            ring.push_back(DropTest::new(&counter));
+            ring.push_front(DropTest::new(&counter));
+        }
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let iter = PanickingIter {
+                counter: &counter,
+                len: 3,
+                panic_after: 1,
+            };
+            ring.insert_from(2, iter);
+        }));
+        assert!(result.is_err());
+        ring.check_invariants();
+        assert_eq!(7, ring.len());
+        assert_eq!(7, counter.load(Ordering::Relaxed));
+        drop(ring);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn truncate_wrapped() {
+        let counter = AtomicUsize::new(0);
+        let mut buffer = buffer(8);
+        let mut ring = RingBufferView::new(&mut buffer);
+        for _ in 0..3 {
+            ring.push_back(DropTest::new(&counter));
+            ring.push_front(DropTest::new(&counter));
+        }
+        ring.truncate(8);
+        assert_eq!(6, counter.load(Ordering::Relaxed));
+        ring.truncate(2);
+        ring.check_invariants();
+        assert_eq!(2, ring.len());
+        assert_eq!(2, counter.load(Ordering::Relaxed));
+        ring.truncate(0);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn raw_parts() {
+        let mut buffer = buffer(4);
+        let mut ring = RingBufferView::new(&mut buffer);
+        ring.extend(vec![1, 2, 3]);
+        ring.pop_front();
+        ring.pop_front();
+        ring.extend(vec![4, 5]);
+        let (buffer, origin, length) = ring.into_raw_parts();
+        assert_eq!((2, 3), (origin, length));
+        let ring = unsafe { RingBufferView::from_raw_parts(buffer, origin, length) };
+        assert_eq!((&[3, 4][..], &[5][..]), ring.as_slices());
+    }
+
+    #[test]
+    #[should_panic(expected = "RingBufferView::push_front: can't push to a full buffer")]
+    fn push_to_full() {
+        let mut buffer = buffer(2);
+        let mut ring = RingBufferView::new(&mut buffer);
+        for i in 0..3 {
+            ring.push_front(i);
+        }
+    }
+
+    #[test]
+    fn dropping_wrapped() {
+        let counter = AtomicUsize::new(0);
+        let mut buffer = buffer(16);
+        {
+            let mut ring = RingBufferView::new(&mut buffer);
+            for _ in 0..6 {
+                ring.push_back(DropTest::new(&counter));
+                ring.push_front(DropTest::new(&counter));
+            }
+            assert!(!ring.as_slices().1.is_empty());
+            ring.remove(5);
+            assert_eq!(11, counter.load(Ordering::Relaxed));
+        }
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+}
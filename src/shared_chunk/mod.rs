@@ -0,0 +1,414 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A copy-on-write handle to a shared chunk.
+//!
+//! See [`SharedChunk`](struct.SharedChunk.html)
+
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{Debug, Error, Formatter};
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+use crate::Chunk;
+
+#[cfg(feature = "refpool")]
+mod refpool;
+#[cfg(feature = "refpool")]
+pub use self::refpool::PoolChunk;
+
+/// A reference counted pointer which a [`SharedChunk`][SharedChunk] can keep
+/// its chunk in.
+///
+/// This is implemented for `Rc`, `Arc` and, with the `refpool` feature flag,
+/// `refpool`'s `PoolRef`. The `Context` is whatever else the pointer needs to
+/// allocate a new value: nothing for `Rc` and `Arc`, and the `Pool` for a
+/// `PoolRef`.
+///
+/// [SharedChunk]: struct.SharedChunk.html
+pub trait SharedPointer<T>: Deref<Target = T> + Clone {
+    /// What the pointer needs, besides a value, to allocate a new pointer.
+    type Context: Clone;
+
+    /// Allocate a new pointer to `value`.
+    fn new(context: &Self::Context, value: T) -> Self;
+
+    /// Get a mutable reference to the pointer's value, first replacing it with
+    /// a clone if the value is shared with any other pointers.
+    fn make_mut<'a>(context: &Self::Context, this: &'a mut Self) -> &'a mut T;
+
+    /// Get a mutable reference to the pointer's value if it isn't shared with
+    /// any other pointers.
+    fn get_mut(this: &mut Self) -> Option<&mut T>;
+
+    /// Test if two pointers point to the same value.
+    fn ptr_eq(left: &Self, right: &Self) -> bool;
+
+    /// Get the pointer's value if it isn't shared with any other pointers, or
+    /// the pointer back if it is.
+    fn try_unwrap(this: Self) -> Result<T, Self>;
+}
+
+impl<T> SharedPointer<T> for Rc<T>
+where
+    T: Clone,
+{
+    type Context = ();
+
+    fn new(_context: &Self::Context, value: T) -> Self {
+        Rc::new(value)
+    }
+
+    fn make_mut<'a>(_context: &Self::Context, this: &'a mut Self) -> &'a mut T {
+        Rc::make_mut(this)
+    }
+
+    fn get_mut(this: &mut Self) -> Option<&mut T> {
+        Rc::get_mut(this)
+    }
+
+    fn ptr_eq(left: &Self, right: &Self) -> bool {
+        Rc::ptr_eq(left, right)
+    }
+
+    fn try_unwrap(this: Self) -> Result<T, Self> {
+        Rc::try_unwrap(this)
+    }
+}
+
+impl<T> SharedPointer<T> for Arc<T>
+where
+    T: Clone,
+{
+    type Context = ();
+
+    fn new(_context: &Self::Context, value: T) -> Self {
+        Arc::new(value)
+    }
+
+    fn make_mut<'a>(_context: &Self::Context, this: &'a mut Self) -> &'a mut T {
+        Arc::make_mut(this)
+    }
+
+    fn get_mut(this: &mut Self) -> Option<&mut T> {
+        Arc::get_mut(this)
+    }
+
+    fn ptr_eq(left: &Self, right: &Self) -> bool {
+        Arc::ptr_eq(left, right)
+    }
+
+    fn try_unwrap(this: Self) -> Result<T, Self> {
+        Arc::try_unwrap(this)
+    }
+}
+
+/// A [`SharedChunk`][SharedChunk] in an `Rc`.
+///
+/// [SharedChunk]: struct.SharedChunk.html
+pub type RcChunk<A, const N: usize> = SharedChunk<A, Rc<Chunk<A, N>>, N>;
+
+/// A [`SharedChunk`][SharedChunk] in an `Arc`.
+///
+/// [SharedChunk]: struct.SharedChunk.html
+pub type ArcChunk<A, const N: usize> = SharedChunk<A, Arc<Chunk<A, N>>, N>;
+
+/// A copy-on-write handle to a shared chunk.
+///
+/// This keeps a [`Chunk`][Chunk] behind a reference counted pointer `P`, so
+/// that cloning the handle is cheap and the clones share the chunk, like you'd
+/// want for the nodes of a persistent data structure shared between versions.
+/// You can read the chunk through `Deref`, and
+/// [`SharedChunk::make_mut`][make_mut] gets you a mutable reference to it,
+/// cloning the chunk first if any other handle can still see it.
+///
+/// The pointer can be an `Rc` or an `Arc`, for which there are the
+/// [`RcChunk`][RcChunk] and [`ArcChunk`][ArcChunk] aliases, or, with the
+/// `refpool` feature flag, a `PoolRef`, which keeps the chunk and any clones
+/// of it in a memory pool (see [`PoolChunk`][PoolChunk]).
+///
+/// # Examples
+///
+/// ```rust
+/// # use sized_chunks::{Chunk, SharedChunk};
+/// # use sized_chunks::shared_chunk::RcChunk;
+/// let mut first: RcChunk<i32, 64> = (0..10).collect::<Chunk<_, 64>>().into();
+/// let second = first.clone();
+/// assert!(SharedChunk::ptr_eq(&first, &second));
+///
+/// SharedChunk::make_mut(&mut first).push_back(10);
+/// assert!(!SharedChunk::ptr_eq(&first, &second));
+/// assert_eq!(11, first.len());
+/// assert_eq!(10, second.len());
+/// ```
+///
+/// [Chunk]: ../sized_chunk/type.Chunk.html
+/// [make_mut]: #method.make_mut
+/// [RcChunk]: type.RcChunk.html
+/// [ArcChunk]: type.ArcChunk.html
+/// [PoolChunk]: type.PoolChunk.html
+pub struct SharedChunk<A, P, const N: usize>
+where
+    P: SharedPointer<Chunk<A, N>>,
+{
+    pointer: P,
+    context: P::Context,
+}
+
+impl<A, P, const N: usize> SharedChunk<A, P, N>
+where
+    P: SharedPointer<Chunk<A, N>, Context = ()>,
+{
+    /// Construct a new handle to `chunk`.
+    pub fn new(chunk: Chunk<A, N>) -> Self {
+        Self::with_context((), chunk)
+    }
+
+    /// Construct a handle from a pointer to a chunk.
+    pub fn from_pointer(pointer: P) -> Self {
+        SharedChunk {
+            pointer,
+            context: (),
+        }
+    }
+}
+
+impl<A, P, const N: usize> SharedChunk<A, P, N>
+where
+    P: SharedPointer<Chunk<A, N>>,
+{
+    /// Construct a new handle to `chunk`, using `context` to allocate it and
+    /// any clones of it.
+    pub fn with_context(context: P::Context, chunk: Chunk<A, N>) -> Self {
+        SharedChunk {
+            pointer: P::new(&context, chunk),
+            context,
+        }
+    }
+
+    /// Get a mutable reference to the chunk, first cloning it if any other
+    /// handle shares it.
+    ///
+    /// After this, the handle is the only one pointing at its chunk, so
+    /// further calls won't clone it again until the handle is cloned.
+    ///
+    /// Time: O(n) if the chunk has to be cloned, O(1) otherwise
+    pub fn make_mut(this: &mut Self) -> &mut Chunk<A, N> {
+        P::make_mut(&this.context, &mut this.pointer)
+    }
+
+    /// Get a mutable reference to the chunk if no other handle shares it.
+    pub fn get_mut(this: &mut Self) -> Option<&mut Chunk<A, N>> {
+        P::get_mut(&mut this.pointer)
+    }
+
+    /// Test if two handles point to the same chunk.
+    pub fn ptr_eq(left: &Self, right: &Self) -> bool {
+        P::ptr_eq(&left.pointer, &right.pointer)
+    }
+
+    /// Take the chunk out of the handle if no other handle shares it, or get
+    /// the handle back if another one does.
+    pub fn try_unwrap(this: Self) -> Result<Chunk<A, N>, Self> {
+        let SharedChunk { pointer, context } = this;
+        P::try_unwrap(pointer).map_err(|pointer| SharedChunk { pointer, context })
+    }
+
+    /// Get a reference to the pointer the chunk is kept in.
+    pub fn as_pointer(this: &Self) -> &P {
+        &this.pointer
+    }
+}
+
+impl<A, P, const N: usize> Deref for SharedChunk<A, P, N>
+where
+    P: SharedPointer<Chunk<A, N>>,
+{
+    type Target = Chunk<A, N>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.pointer
+    }
+}
+
+impl<A, P, const N: usize> AsRef<Chunk<A, N>> for SharedChunk<A, P, N>
+where
+    P: SharedPointer<Chunk<A, N>>,
+{
+    fn as_ref(&self) -> &Chunk<A, N> {
+        &self.pointer
+    }
+}
+
+impl<A, P, const N: usize> Borrow<Chunk<A, N>> for SharedChunk<A, P, N>
+where
+    P: SharedPointer<Chunk<A, N>>,
+{
+    fn borrow(&self) -> &Chunk<A, N> {
+        &self.pointer
+    }
+}
+
+impl<A, P, const N: usize> Clone for SharedChunk<A, P, N>
+where
+    P: SharedPointer<Chunk<A, N>>,
+{
+    /// Make another handle to the same chunk.
+    ///
+    /// Time: O(1)
+    fn clone(&self) -> Self {
+        SharedChunk {
+            pointer: self.pointer.clone(),
+            context: self.context.clone(),
+        }
+    }
+}
+
+impl<A, P, const N: usize> Default for SharedChunk<A, P, N>
+where
+    P: SharedPointer<Chunk<A, N>, Context = ()>,
+{
+    fn default() -> Self {
+        Self::new(Chunk::new())
+    }
+}
+
+impl<A, P, const N: usize> From<Chunk<A, N>> for SharedChunk<A, P, N>
+where
+    P: SharedPointer<Chunk<A, N>, Context = ()>,
+{
+    fn from(chunk: Chunk<A, N>) -> Self {
+        Self::new(chunk)
+    }
+}
+
+impl<A, P, const N: usize> Debug for SharedChunk<A, P, N>
+where
+    A: Debug,
+    P: SharedPointer<Chunk<A, N>>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("SharedChunk")?;
+        f.debug_list().entries(self.pointer.iter()).finish()
+    }
+}
+
+impl<A, P, const N: usize> Hash for SharedChunk<A, P, N>
+where
+    A: Hash,
+    P: SharedPointer<Chunk<A, N>>,
+{
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        (**self).hash(hasher)
+    }
+}
+
+impl<A, P, const N: usize> PartialEq for SharedChunk<A, P, N>
+where
+    A: PartialEq,
+    P: SharedPointer<Chunk<A, N>>,
+{
+    /// Compare the contents of two chunks, which is O(1) when both handles
+    /// point to the same chunk.
+    fn eq(&self, other: &Self) -> bool {
+        Self::ptr_eq(self, other) || **self == **other
+    }
+}
+
+impl<A, P, const N: usize> Eq for SharedChunk<A, P, N>
+where
+    A: Eq,
+    P: SharedPointer<Chunk<A, N>>,
+{
+}
+
+impl<A, P, const N: usize> PartialOrd for SharedChunk<A, P, N>
+where
+    A: PartialOrd,
+    P: SharedPointer<Chunk<A, N>>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<A, P, const N: usize> Ord for SharedChunk<A, P, N>
+where
+    A: Ord,
+    P: SharedPointer<Chunk<A, N>>,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::FragileClone;
+    use std::iter::FromIterator;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn make_mut_clones_on_write() {
+        let mut first: RcChunk<i32, 16> = Chunk::from_iter(0..5).into();
+        let before = first.as_ptr();
+        SharedChunk::make_mut(&mut first).push_back(5);
+        assert_eq!(before, first.as_ptr(), "a unique chunk shouldn't be cloned");
+
+        let second = first.clone();
+        assert!(SharedChunk::ptr_eq(&first, &second));
+        SharedChunk::make_mut(&mut first).push_back(6);
+        assert!(!SharedChunk::ptr_eq(&first, &second));
+        assert_eq!(&[0, 1, 2, 3, 4, 5, 6], first.as_slice());
+        assert_eq!(&[0, 1, 2, 3, 4, 5], second.as_slice());
+        first.check_invariants();
+    }
+
+    #[test]
+    fn get_mut_and_try_unwrap() {
+        let mut first: ArcChunk<i32, 16> = SharedChunk::new(Chunk::from_iter(0..3));
+        let second = first.clone();
+        assert!(SharedChunk::get_mut(&mut first).is_none());
+        let first = SharedChunk::try_unwrap(first).unwrap_err();
+        drop(second);
+        let mut first = first;
+        SharedChunk::get_mut(&mut first).unwrap().push_front(-1);
+        let chunk = SharedChunk::try_unwrap(first).unwrap();
+        assert_eq!(&[-1, 0, 1, 2], chunk.as_slice());
+    }
+
+    #[test]
+    fn comparisons() {
+        let first: RcChunk<i32, 16> = Chunk::from_iter(0..3).into();
+        let second: RcChunk<i32, 16> = Chunk::from_iter(0..3).into();
+        let third: RcChunk<i32, 16> = Chunk::from_iter(1..3).into();
+        assert_eq!(first, second);
+        assert!(first < third);
+        assert_eq!(RcChunk::<i32, 16>::default(), Chunk::new().into());
+        assert_eq!("SharedChunk[0, 1, 2]", format!("{:?}", first));
+    }
+
+    #[test]
+    fn dropping() {
+        let counter = AtomicUsize::new(0);
+        {
+            let mut chunk: RcChunk<FragileClone<'_>, 16> = SharedChunk::default();
+            for _ in 0..8 {
+                SharedChunk::make_mut(&mut chunk).push_back(FragileClone::new(&counter, false));
+            }
+            let other = chunk.clone();
+            drop(chunk);
+            assert_eq!(8, counter.load(Ordering::Relaxed));
+            drop(other);
+        }
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+}
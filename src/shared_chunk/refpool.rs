@@ -0,0 +1,101 @@
+use ::refpool::{Pool, PoolClone, PoolRef};
+
+use super::{SharedChunk, SharedPointer};
+use crate::Chunk;
+
+/// A [`SharedChunk`][SharedChunk] in a `PoolRef`, which allocates the chunk
+/// and any clones of it from a `Pool`.
+///
+/// [SharedChunk]: struct.SharedChunk.html
+pub type PoolChunk<A, const N: usize> = SharedChunk<A, PoolRef<Chunk<A, N>>, N>;
+
+impl<T> SharedPointer<T> for PoolRef<T>
+where
+    T: PoolClone,
+{
+    type Context = Pool<T>;
+
+    fn new(context: &Self::Context, value: T) -> Self {
+        PoolRef::new(context, value)
+    }
+
+    fn make_mut<'a>(context: &Self::Context, this: &'a mut Self) -> &'a mut T {
+        PoolRef::make_mut(context, this)
+    }
+
+    fn get_mut(this: &mut Self) -> Option<&mut T> {
+        PoolRef::get_mut(this)
+    }
+
+    fn ptr_eq(left: &Self, right: &Self) -> bool {
+        PoolRef::ptr_eq(left, right)
+    }
+
+    fn try_unwrap(this: Self) -> Result<T, Self> {
+        PoolRef::try_unwrap(this)
+    }
+}
+
+impl<A, const N: usize> SharedChunk<A, PoolRef<Chunk<A, N>>, N>
+where
+    A: Clone,
+{
+    /// Construct a new handle to an empty chunk allocated from `pool`.
+    ///
+    /// Clones of the chunk made by [`make_mut`][make_mut] are allocated from
+    /// the same pool.
+    ///
+    /// [make_mut]: #method.make_mut
+    pub fn default_in(pool: &Pool<Chunk<A, N>>) -> Self {
+        SharedChunk {
+            pointer: PoolRef::default(pool),
+            context: pool.clone(),
+        }
+    }
+
+    /// Construct a new handle to `chunk`, allocated from `pool`.
+    ///
+    /// Clones of the chunk made by [`make_mut`][make_mut] are allocated from
+    /// the same pool.
+    ///
+    /// [make_mut]: #method.make_mut
+    pub fn new_in(pool: &Pool<Chunk<A, N>>, chunk: Chunk<A, N>) -> Self {
+        Self::with_context(pool.clone(), chunk)
+    }
+
+    /// Construct a handle from a `PoolRef` to a chunk, using `pool` to
+    /// allocate clones of it.
+    pub fn from_pool_ref(pool: &Pool<Chunk<A, N>>, pointer: PoolRef<Chunk<A, N>>) -> Self {
+        SharedChunk {
+            pointer,
+            context: pool.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::iter::FromIterator;
+
+    #[test]
+    fn make_mut_clones_into_pool() {
+        let pool: Pool<Chunk<usize, 64>> = Pool::new(4);
+        let mut first = PoolChunk::new_in(&pool, Chunk::from_iter(0..3));
+        let second = first.clone();
+        assert_eq!(0, pool.get_pool_size());
+        SharedChunk::make_mut(&mut first).push_back(3);
+        assert!(!SharedChunk::ptr_eq(&first, &second));
+        assert_eq!(&[0, 1, 2, 3], first.as_slice());
+        assert_eq!(&[0, 1, 2], second.as_slice());
+        drop(first);
+        drop(second);
+        assert_eq!(2, pool.get_pool_size());
+
+        let mut third = PoolChunk::default_in(&pool);
+        assert_eq!(1, pool.get_pool_size());
+        SharedChunk::make_mut(&mut third).push_back(1);
+        let chunk = SharedChunk::try_unwrap(third).unwrap();
+        assert_eq!(&[1], chunk.as_slice());
+    }
+}
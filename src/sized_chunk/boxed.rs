@@ -0,0 +1,143 @@
+use alloc::boxed::Box;
+use core::ptr::addr_of_mut;
+
+use crate::sized_chunk::{Rebalance, RebalancingChunk};
+use crate::util::new_uninit_box;
+
+impl<A, P, const N: usize> RebalancingChunk<A, P, N> {
+    /// Construct a new empty chunk directly in a heap allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::Chunk;
+    /// let mut chunk = Chunk::<u8, 65536>::new_boxed();
+    /// chunk.push_back(1);
+    /// assert_eq!(&[1], chunk.as_slice());
+    /// ```
+    pub fn new_boxed() -> Box<Self> {
+        let mut chunk = new_uninit_box::<Self>();
+        unsafe {
+            let ptr = chunk.as_mut_ptr();
+            addr_of_mut!((*ptr).left).write(0);
+            addr_of_mut!((*ptr).right).write(0);
+            Box::from_raw(Box::into_raw(chunk).cast())
+        }
+    }
+
+    /// Construct a chunk from an iterator directly in a heap allocation.
+    ///
+    /// Panics if the iterator contains more than `N` items.
+    ///
+    /// Time: O(n)
+    pub fn boxed_from_iter<I>(iter: I) -> Box<Self>
+    where
+        I: IntoIterator<Item = A>,
+        P: Rebalance,
+    {
+        let mut chunk = Self::new_boxed();
+        for item in iter {
+            chunk.push_back(item);
+        }
+        chunk
+    }
+
+    /// Clone the chunk directly into a heap allocation.
+    ///
+    /// Time: O(n)
+    pub fn clone_boxed(&self) -> Box<Self>
+    where
+        A: Clone,
+    {
+        let mut out = Self::new_boxed();
+        out.left = self.left;
+        out.right = self.left;
+        for index in self.left..self.right {
+            unsafe { Self::force_write(index, (*self.ptr(index)).clone(), &mut out) }
+            // Panic safety: as with `clone`, only cover the items written so
+            // far.
+            out.right = index + 1;
+        }
+        out
+    }
+
+    /// Split a chunk into two like [`split_off`][split_off], but construct
+    /// the returned chunk directly in a heap allocation.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(n) for the number of items in the new chunk
+    ///
+    /// [split_off]: #method.split_off
+    pub fn split_off_boxed(&mut self, index: usize) -> Box<Self> {
+        if index > self.len() {
+            panic!("Chunk::split_off_boxed: index out of bounds");
+        }
+        let mut right_chunk = Self::new_boxed();
+        let start = self.left + index;
+        let len = self.right - start;
+        unsafe { Self::force_copy_to(start, 0, len, self, &mut right_chunk) };
+        right_chunk.right = len;
+        self.right = start;
+        debug_invariants!(self, right_chunk);
+        right_chunk
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::FragileClone;
+    use crate::Chunk;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn large_chunk_without_stack_copy() {
+        // The thread's stack is a quarter the size of a chunk, so this checks
+        // that none of the boxed constructors build a chunk on the stack on
+        // their way to the heap. `RingBuffer`'s use the same allocation.
+        std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                let mut chunk = Chunk::<u8, 262_144>::boxed_from_iter(0..10);
+                let copy = chunk.clone_boxed();
+                let tail = chunk.split_off_boxed(5);
+                assert_eq!(&[0, 1, 2, 3, 4], chunk.as_slice());
+                assert_eq!(&[5, 6, 7, 8, 9], tail.as_slice());
+                assert_eq!(10, copy.len());
+                assert!(Chunk::<u8, 262_144>::new_boxed().is_empty());
+                chunk.check_invariants();
+                tail.check_invariants();
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn split_off_boxed() {
+        for index in 0..=5 {
+            let mut left = Chunk::<usize, 8>::boxed_from_iter(0..6);
+            left.pop_front();
+            let right = left.split_off_boxed(index);
+            left.check_invariants();
+            right.check_invariants();
+            assert!(left.iter().copied().eq(1..1 + index));
+            assert!(right.iter().copied().eq(1 + index..6));
+        }
+    }
+
+    #[test]
+    fn clone_boxed_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut chunk: Chunk<_, 8> = Chunk::new();
+        chunk.push_back(FragileClone::new(&counter, false));
+        chunk.push_back(FragileClone::new(&counter, false));
+        chunk.push_back(FragileClone::new(&counter, true));
+        chunk.pop_front();
+        assert!(catch_unwind(AssertUnwindSafe(|| chunk.clone_boxed())).is_err());
+        assert_eq!(2, counter.load(Ordering::Relaxed));
+        drop(chunk);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+}
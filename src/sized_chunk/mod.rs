@@ -4,32 +4,61 @@
 
 //! A fixed capacity smart array.
 //!
-//! See [`Chunk`](struct.Chunk.html)
+//! See [`Chunk`](type.Chunk.html)
 
 use crate::inline_array::InlineArray;
+use crate::util::Fits;
 use core::borrow::{Borrow, BorrowMut};
 use core::cmp::Ordering;
-use core::fmt::{Debug, Error, Formatter};
+use core::convert::{Infallible, TryFrom};
+use core::fmt::{self, Debug, Error, Formatter};
 use core::hash::{Hash, Hasher};
 use core::iter::FromIterator;
-use core::mem::{replace, MaybeUninit};
+use core::marker::PhantomData;
+use core::mem::{replace, ManuallyDrop, MaybeUninit};
 use core::ops::{Deref, DerefMut, Index, IndexMut};
 use core::ptr;
 use core::slice::{
     from_raw_parts, from_raw_parts_mut, Iter as SliceIter, IterMut as SliceIterMut, SliceIndex,
 };
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 #[cfg(feature = "std")]
 use std::io;
 
 mod iter;
 pub use self::iter::{Drain, Iter};
 
+mod rebalance;
+pub use self::rebalance::{Centre, Proportional, Rebalance, ShiftToEdge};
+
+mod raw;
+use self::raw::RawChunk;
+
+mod view;
+pub use self::view::{ChunkView, ViewDrain};
+
+#[cfg(feature = "alloc")]
+mod boxed;
+
 #[cfg(feature = "refpool")]
 mod refpool;
 
 /// A fixed capacity smart array.
 ///
+/// This is a [`RebalancingChunk`][RebalancingChunk] using the
+/// [`ShiftToEdge`][ShiftToEdge] policy, which suits chunks that are mostly
+/// pushed to at one end. See [`RebalancingChunk`][RebalancingChunk] for the
+/// details and the methods.
+///
+/// [RebalancingChunk]: struct.RebalancingChunk.html
+/// [ShiftToEdge]: struct.ShiftToEdge.html
+pub type Chunk<A, const N: usize> = RebalancingChunk<A, ShiftToEdge, N>;
+
+/// A fixed capacity smart array, with a policy for where it moves its
+/// contents.
+///
 /// An inline array of items with a variable length but a fixed, preallocated
 /// capacity given by the `N` type.
 ///
@@ -73,6 +102,25 @@ mod refpool;
 /// is guaranteed to be contiguous in memory, so there's no need to remap logical
 /// indices to a ring buffer's physical layout.
 ///
+/// ## Rebalancing
+///
+/// When a push or an insert at either end finds no room on that side, the
+/// [`Rebalance`][Rebalance] policy `P` decides where to move the contents to,
+/// and so how the free space is split between the two sides.
+/// [`Chunk`][Chunk] uses [`ShiftToEdge`][ShiftToEdge], which leaves all of it
+/// on the side being pushed to, so pushing to one end is amortised O(1).
+/// If you push to both ends, [`Centre`][Centre] keeps pushes to either end
+/// amortised O(1) instead:
+///
+/// ```rust
+/// # use sized_chunks::sized_chunk::{Centre, RebalancingChunk};
+/// let mut deque = RebalancingChunk::<i32, Centre, 64>::new();
+/// deque.push_back(1);
+/// deque.push_front(0);
+/// assert_eq!(31, deque.front_capacity());
+/// assert_eq!(31, deque.back_capacity());
+/// ```
+///
 /// # Examples
 ///
 /// ```rust
@@ -90,19 +138,25 @@ mod refpool;
 ///
 /// [im::Vector]: https://docs.rs/im/latest/im/vector/enum.Vector.html
 /// [RingBuffer]: ../ring_buffer/struct.RingBuffer.html
-pub struct Chunk<A, const N: usize> {
+/// [Rebalance]: trait.Rebalance.html
+/// [Chunk]: type.Chunk.html
+/// [ShiftToEdge]: struct.ShiftToEdge.html
+/// [Centre]: struct.Centre.html
+#[repr(C)]
+pub struct RebalancingChunk<A, P, const N: usize> {
     left: usize,
     right: usize,
     data: MaybeUninit<[A; N]>,
+    policy: PhantomData<P>,
 }
 
-impl<A, const N: usize> Drop for Chunk<A, N> {
+impl<A, P, const N: usize> Drop for RebalancingChunk<A, P, N> {
     fn drop(&mut self) {
         unsafe { ptr::drop_in_place(self.as_mut_slice()) }
     }
 }
 
-impl<A, const N: usize> Clone for Chunk<A, N>
+impl<A, P, const N: usize> Clone for RebalancingChunk<A, P, N>
 where
     A: Clone,
 {
@@ -111,7 +165,7 @@ where
         out.left = self.left;
         out.right = self.left;
         for index in self.left..self.right {
-            unsafe { Chunk::force_write(index, (*self.ptr(index)).clone(), &mut out) }
+            unsafe { RebalancingChunk::force_write(index, (*self.ptr(index)).clone(), &mut out) }
             // Panic safety, move the right index to cover only the really initialized things. This
             // way we don't try to drop uninitialized, but also don't leak if we panic in the
             // middle.
@@ -121,17 +175,66 @@ where
     }
 }
 
-impl<A, const N: usize> Chunk<A, N> {
+impl<A, P, const N: usize> RebalancingChunk<A, P, N> {
     /// The maximum number of elements this `Chunk` can contain.
     pub const CAPACITY: usize = N;
 
     /// Construct a new empty chunk.
-    pub fn new() -> Self {
+    ///
+    /// This is a `const fn`, so you can use it to initialise a `static`.
+    pub const fn new() -> Self {
         Self {
             left: 0,
             right: 0,
             data: MaybeUninit::uninit(),
+            policy: PhantomData,
+        }
+    }
+
+    /// Construct a full chunk from an array of exactly `N` items.
+    ///
+    /// This is a `const fn`, so you can use it to initialise a `static`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::Chunk;
+    /// static PRIMES: Chunk<u32, 4> = Chunk::from_array([2, 3, 5, 7]);
+    /// assert_eq!(&[2, 3, 5, 7], PRIMES.as_slice());
+    /// ```
+    pub const fn from_array(array: [A; N]) -> Self {
+        Self {
+            left: 0,
+            right: N,
+            data: MaybeUninit::new(array),
+            policy: PhantomData,
+        }
+    }
+
+    /// Construct a full chunk by calling `f` with each index from `0` to
+    /// `N - 1` in turn and using the results as its items.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::Chunk;
+    /// let squares = Chunk::<usize, 4>::from_fn(|i| i * i);
+    /// assert_eq!(&[0, 1, 4, 9], squares.as_slice());
+    /// ```
+    ///
+    /// Time: O(n)
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(usize) -> A,
+    {
+        let mut chunk = Self::new();
+        while chunk.right < N {
+            let value = f(chunk.right);
+            unsafe { RebalancingChunk::force_write(chunk.right, value, &mut chunk) };
+            chunk.right += 1;
         }
+        debug_invariants!(chunk);
+        chunk
     }
 
     /// Construct a new chunk with one item.
@@ -141,9 +244,10 @@ impl<A, const N: usize> Chunk<A, N> {
             left: 0,
             right: 1,
             data: MaybeUninit::uninit(),
+            policy: PhantomData,
         };
         unsafe {
-            Chunk::force_write(0, value, &mut chunk);
+            RebalancingChunk::force_write(0, value, &mut chunk);
         }
         chunk
     }
@@ -155,10 +259,11 @@ impl<A, const N: usize> Chunk<A, N> {
             left: 0,
             right: 2,
             data: MaybeUninit::uninit(),
+            policy: PhantomData,
         };
         unsafe {
-            Chunk::force_write(0, left, &mut chunk);
-            Chunk::force_write(1, right, &mut chunk);
+            RebalancingChunk::force_write(0, left, &mut chunk);
+            RebalancingChunk::force_write(1, right, &mut chunk);
         }
         chunk
     }
@@ -181,6 +286,7 @@ impl<A, const N: usize> Chunk<A, N> {
     pub fn collect_from<I>(iter: &mut I, mut count: usize) -> Self
     where
         I: Iterator<Item = A>,
+        P: Rebalance,
     {
         let mut chunk = Self::new();
         while count > 0 {
@@ -201,9 +307,10 @@ impl<A, const N: usize> Chunk<A, N> {
         let other_len = other.len();
         debug_assert!(count <= other_len);
         let mut chunk = Self::new();
-        unsafe { Chunk::force_copy_to(other.left, 0, count, other, &mut chunk) };
+        unsafe { RebalancingChunk::force_copy_to(other.left, 0, count, other, &mut chunk) };
         chunk.right = count;
         other.left += count;
+        debug_invariants!(chunk, other);
         chunk
     }
 
@@ -215,9 +322,10 @@ impl<A, const N: usize> Chunk<A, N> {
         let other_len = other.len();
         debug_assert!(count <= other_len);
         let mut chunk = Self::new();
-        unsafe { Chunk::force_copy_to(other.right - count, 0, count, other, &mut chunk) };
+        unsafe { Self::force_copy_to(other.right - count, 0, count, other, &mut chunk) };
         chunk.right = count;
         other.right -= count;
+        debug_invariants!(chunk, other);
         chunk
     }
 
@@ -239,6 +347,30 @@ impl<A, const N: usize> Chunk<A, N> {
         self.left == 0 && self.right == Self::CAPACITY
     }
 
+    /// Check the chunk's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of chunks.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the chunk, so corruption is caught where it
+    /// happens rather than wherever it's first noticed.
+    ///
+    /// Time: O(1)
+    pub fn check_invariants(&self) {
+        assert!(
+            self.left <= self.right,
+            "Chunk::check_invariants: left index {} is past right index {}",
+            self.left,
+            self.right
+        );
+        assert!(
+            self.right <= N,
+            "Chunk::check_invariants: right index {} is past capacity {}",
+            self.right,
+            N
+        );
+    }
+
     #[inline]
     unsafe fn ptr(&self, index: usize) -> *const A {
         (&self.data as *const _ as *const A).add(index)
@@ -263,111 +395,209 @@ impl<A, const N: usize> Chunk<A, N> {
         chunk.mut_ptr(index).write(value)
     }
 
-    /// Copy a range within a chunk
+    /// Copy a range between chunks
     #[inline]
-    unsafe fn force_copy(from: usize, to: usize, count: usize, chunk: &mut Self) {
+    unsafe fn force_copy_to(
+        from: usize,
+        to: usize,
+        count: usize,
+        chunk: &mut Self,
+        other: &mut Self,
+    ) {
         if count > 0 {
-            ptr::copy(chunk.ptr(from), chunk.mut_ptr(to), count)
+            ptr::copy_nonoverlapping(chunk.ptr(from), other.mut_ptr(to), count)
         }
     }
 
-    /// Write values from iterator into range starting at write_index.
+    #[inline]
+    fn raw(&mut self) -> RawChunk<'_, A> {
+        let base = &mut self.data as *mut _ as *mut A;
+        unsafe { RawChunk::new(base, N, &mut self.left, &mut self.right) }
+    }
+
+    /// Borrow the chunk as a [`Chunk`][Chunk], for the iterators which
+    /// don't care about the policy.
     ///
-    /// Will overwrite values at the relevant range without dropping even in case the values were
-    /// already initialized (it is expected they are empty). Does not update the left or right
-    /// index.
+    /// [Chunk]: type.Chunk.html
+    fn as_chunk_mut(&mut self) -> &mut Chunk<A, N> {
+        // The policy is only a `PhantomData`, and the struct is `repr(C)`, so
+        // the layout is the same whatever the policy is.
+        unsafe { &mut *(self as *mut Self).cast() }
+    }
+
+    /// Move the chunk into a [`Chunk`][Chunk], for the iterators which don't
+    /// care about the policy.
     ///
-    /// # Safety
+    /// [Chunk]: type.Chunk.html
+    fn into_chunk(self) -> Chunk<A, N> {
+        let this = ManuallyDrop::new(self);
+        RebalancingChunk {
+            left: this.left,
+            right: this.right,
+            data: unsafe { ptr::read(&this.data) },
+            policy: PhantomData,
+        }
+    }
+
+    /// Move the contents of the chunk so that they start at `new_left`.
+    #[inline]
+    fn realign(&mut self, new_left: usize) {
+        self.raw().realign(new_left)
+    }
+
+    /// Get the number of items which can be pushed to the front of the chunk
+    /// without shifting its contents.
+    #[inline]
+    pub fn front_capacity(&self) -> usize {
+        self.left
+    }
+
+    /// Get the number of items which can be pushed to the back of the chunk
+    /// without shifting its contents.
+    #[inline]
+    pub fn back_capacity(&self) -> usize {
+        N - self.right
+    }
+
+    /// Shift the contents of the chunk to the middle of its storage, splitting
+    /// the free space evenly between the front and the back.
     ///
-    /// Range checks must already have been performed.
+    /// Time: O(n)
+    pub fn recenter(&mut self) {
+        self.realign((N - self.len()) / 2);
+        debug_invariants!(self);
+    }
+
+    /// Shift the contents of the chunk to the start of its storage, leaving
+    /// all the free space at the back.
     ///
-    /// # Panics
+    /// Time: O(n)
+    pub fn align_left(&mut self) {
+        self.realign(0);
+        debug_invariants!(self);
+    }
+
+    /// Shift the contents of the chunk to the end of its storage, leaving all
+    /// the free space at the front.
     ///
-    /// If the iterator panics, the chunk becomes conceptually empty and will leak any previous
-    /// elements (even the ones outside the range).
-    #[inline]
-    unsafe fn write_from_iter<I>(mut write_index: usize, iter: I, chunk: &mut Self)
-    where
-        I: ExactSizeIterator<Item = A>,
-    {
-        // Panic safety. We make the array conceptually empty, so we never ever drop anything that
-        // is unitialized. We do so because we expect to be called when there's a potential "hole"
-        // in the array that makes the space for the new elements to be written. We return it back
-        // to original when everything goes fine, but leak any elements on panic. This is bad, but
-        // better than dropping non-existing stuff.
-        //
-        // Should we worry about some better panic recovery than this?
-        let left = replace(&mut chunk.left, 0);
-        let right = replace(&mut chunk.right, 0);
-        let len = iter.len();
-        let expected_end = write_index + len;
-        for value in iter.take(len) {
-            Chunk::force_write(write_index, value, chunk);
-            write_index += 1;
-        }
-        // Oops, we have a hole in here now. That would be bad, give up.
-        assert_eq!(
-            expected_end, write_index,
-            "ExactSizeIterator yielded fewer values than advertised",
-        );
-        chunk.left = left;
-        chunk.right = right;
+    /// Time: O(n)
+    pub fn align_right(&mut self) {
+        self.realign(N - self.len());
+        debug_invariants!(self);
     }
 
-    /// Copy a range between chunks
-    #[inline]
-    unsafe fn force_copy_to(
-        from: usize,
-        to: usize,
-        count: usize,
-        chunk: &mut Self,
-        other: &mut Self,
-    ) {
-        if count > 0 {
-            ptr::copy_nonoverlapping(chunk.ptr(from), other.mut_ptr(to), count)
+    /// Make sure at least `count` items can be pushed to the front of the
+    /// chunk without shifting its contents, shifting them now if necessary.
+    ///
+    /// Panics if the chunk doesn't have room for `count` more items.
+    ///
+    /// Time: O(n) if the contents had to be shifted, O(1) otherwise
+    pub fn reserve_front(&mut self, count: usize) {
+        if count > N - self.len() {
+            panic!("Chunk::reserve_front: chunk cannot fit {} elements", count);
+        }
+        if self.left < count {
+            self.realign(count);
+        }
+        debug_invariants!(self);
+    }
+
+    /// Make sure at least `count` items can be pushed to the back of the
+    /// chunk without shifting its contents, shifting them now if necessary.
+    ///
+    /// Panics if the chunk doesn't have room for `count` more items.
+    ///
+    /// Time: O(n) if the contents had to be shifted, O(1) otherwise
+    pub fn reserve_back(&mut self, count: usize) {
+        if count > N - self.len() {
+            panic!("Chunk::reserve_back: chunk cannot fit {} elements", count);
+        }
+        if N - self.right < count {
+            self.realign(N - self.len() - count);
         }
+        debug_invariants!(self);
     }
 
     /// Push an item to the front of the chunk.
     ///
     /// Panics if the capacity of the chunk is exceeded.
     ///
+    /// If there's no room at the front, the chunk's policy `P` decides where
+    /// to move the contents. Use [`push_front_with`][push_front_with] to
+    /// choose a different policy for one push.
+    ///
     /// Time: O(1) if there's room at the front, O(n) otherwise
-    pub fn push_front(&mut self, value: A) {
+    ///
+    /// [push_front_with]: #method.push_front_with
+    pub fn push_front(&mut self, value: A)
+    where
+        P: Rebalance,
+    {
+        self.push_front_with::<P>(value)
+    }
+
+    /// Push an item to the back of the chunk.
+    ///
+    /// Panics if the capacity of the chunk is exceeded.
+    ///
+    /// If there's no room at the back, the chunk's policy `P` decides where
+    /// to move the contents. Use [`push_back_with`][push_back_with] to choose
+    /// a different policy for one push.
+    ///
+    /// Time: O(1) if there's room at the back, O(n) otherwise
+    ///
+    /// [push_back_with]: #method.push_back_with
+    pub fn push_back(&mut self, value: A)
+    where
+        P: Rebalance,
+    {
+        self.push_back_with::<P>(value)
+    }
+
+    /// Push an item to the front of the chunk, using the [`Rebalance`][Rebalance]
+    /// policy `Q` to decide where to move the contents if there's no room at
+    /// the front.
+    ///
+    /// Panics if the capacity of the chunk is exceeded.
+    ///
+    /// Time: O(1) if there's room at the front, O(n) otherwise
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::sized_chunk::{Centre, Chunk};
+    /// let mut chunk = Chunk::<i32, 64>::new();
+    /// chunk.push_back_with::<Centre>(1);
+    /// chunk.push_front_with::<Centre>(0);
+    /// // Both ends have room to grow now.
+    /// assert_eq!(31, chunk.front_capacity());
+    /// assert_eq!(31, chunk.back_capacity());
+    /// ```
+    ///
+    /// [Rebalance]: trait.Rebalance.html
+    pub fn push_front_with<Q: Rebalance>(&mut self, value: A) {
         if self.is_full() {
             panic!("Chunk::push_front: can't push to full chunk");
         }
-        if self.is_empty() {
-            self.left = N;
-            self.right = N;
-        } else if self.left == 0 {
-            self.left = N - self.right;
-            unsafe { Chunk::force_copy(0, self.left, self.right, self) };
-            self.right = N;
-        }
-        self.left -= 1;
-        unsafe { Chunk::force_write(self.left, value, self) }
+        unsafe { self.raw().push_front_with::<Q>(value) };
+        debug_invariants!(self);
     }
 
-    /// Push an item to the back of the chunk.
+    /// Push an item to the back of the chunk, using the [`Rebalance`][Rebalance]
+    /// policy `Q` to decide where to move the contents if there's no room at
+    /// the back.
     ///
     /// Panics if the capacity of the chunk is exceeded.
     ///
     /// Time: O(1) if there's room at the back, O(n) otherwise
-    pub fn push_back(&mut self, value: A) {
+    ///
+    /// [Rebalance]: trait.Rebalance.html
+    pub fn push_back_with<Q: Rebalance>(&mut self, value: A) {
         if self.is_full() {
             panic!("Chunk::push_back: can't push to full chunk");
         }
-        if self.is_empty() {
-            self.left = 0;
-            self.right = 0;
-        } else if self.right == N {
-            unsafe { Chunk::force_copy(self.left, 0, self.len(), self) };
-            self.right = N - self.left;
-            self.left = 0;
-        }
-        unsafe { Chunk::force_write(self.right, value, self) }
-        self.right += 1;
+        unsafe { self.raw().push_back_with::<Q>(value) };
+        debug_invariants!(self);
     }
 
     /// Pop an item off the front of the chunk.
@@ -379,8 +609,9 @@ impl<A, const N: usize> Chunk<A, N> {
         if self.is_empty() {
             panic!("Chunk::pop_front: can't pop from empty chunk");
         } else {
-            let value = unsafe { Chunk::force_read(self.left, self) };
+            let value = unsafe { RebalancingChunk::force_read(self.left, self) };
             self.left += 1;
+            debug_invariants!(self);
             value
         }
     }
@@ -395,7 +626,9 @@ impl<A, const N: usize> Chunk<A, N> {
             panic!("Chunk::pop_back: can't pop from empty chunk");
         } else {
             self.right -= 1;
-            unsafe { Chunk::force_read(self.right, self) }
+            let value = unsafe { RebalancingChunk::force_read(self.right, self) };
+            debug_invariants!(self);
+            value
         }
     }
 
@@ -409,6 +642,7 @@ impl<A, const N: usize> Chunk<A, N> {
             unsafe { ptr::drop_in_place(&mut self[..index]) }
             self.left += index;
         }
+        debug_invariants!(self);
     }
 
     /// Discard all items from `index` onward.
@@ -421,6 +655,7 @@ impl<A, const N: usize> Chunk<A, N> {
             unsafe { ptr::drop_in_place(&mut self[index..]) }
             self.right = self.left + index;
         }
+        debug_invariants!(self);
     }
 
     /// Split a chunk into two, the original chunk containing
@@ -440,9 +675,10 @@ impl<A, const N: usize> Chunk<A, N> {
         let mut right_chunk = Self::new();
         let start = self.left + index;
         let len = self.right - start;
-        unsafe { Chunk::force_copy_to(start, 0, len, self, &mut right_chunk) };
+        unsafe { RebalancingChunk::force_copy_to(start, 0, len, self, &mut right_chunk) };
         right_chunk.right = len;
         self.right = start;
+        debug_invariants!(self, right_chunk);
         right_chunk
     }
 
@@ -457,15 +693,60 @@ impl<A, const N: usize> Chunk<A, N> {
         if self_len + other_len > N {
             panic!("Chunk::append: chunk size overflow");
         }
-        if self.right + other_len > N {
-            unsafe { Chunk::force_copy(self.left, 0, self_len, self) };
-            self.right -= self.left;
-            self.left = 0;
+        unsafe { self.raw().take_front_from(&mut other.raw(), other_len) };
+        other.left = 0;
+        other.right = 0;
+        debug_invariants!(self, other);
+    }
+
+    /// Remove all items from `other`, a chunk of any capacity, and append
+    /// them to the back of `self`.
+    ///
+    /// Panics if the capacity of the chunk is exceeded.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn append_from<Q, const M: usize>(&mut self, other: &mut RebalancingChunk<A, Q, M>) {
+        let self_len = self.len();
+        let other_len = other.len();
+        if self_len + other_len > N {
+            panic!("Chunk::append_from: chunk size overflow");
         }
-        unsafe { Chunk::force_copy_to(other.left, self.right, other_len, other, self) };
-        self.right += other_len;
+        unsafe { self.raw().take_front_from(&mut other.raw(), other_len) };
         other.left = 0;
         other.right = 0;
+        debug_invariants!(self, other);
+    }
+
+    /// Move the contents of the chunk into a new chunk with a capacity of
+    /// `M`.
+    ///
+    /// If the items don't fit in the new chunk, you get the old one back as
+    /// the error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::Chunk;
+    /// let small = Chunk::<i32, 4>::from([1, 2, 3]);
+    /// let large: Chunk<i32, 64> = small.into_capacity().unwrap();
+    /// assert_eq!(&[1, 2, 3], large.as_slice());
+    /// let small = large.into_capacity::<2>().unwrap_err();
+    /// assert_eq!(3, small.len());
+    /// ```
+    ///
+    /// Time: O(n)
+    pub fn into_capacity<const M: usize>(mut self) -> Result<RebalancingChunk<A, P, M>, Self> {
+        let len = self.len();
+        if len > M {
+            return Err(self);
+        }
+        let mut out = RebalancingChunk::new();
+        unsafe { ptr::copy_nonoverlapping(self.ptr(self.left), out.mut_ptr(0), len) };
+        out.right = len;
+        self.left = 0;
+        self.right = 0;
+        debug_invariants!(out);
+        Ok(out)
     }
 
     /// Remove `count` items from the front of `other` and append them to the
@@ -480,14 +761,8 @@ impl<A, const N: usize> Chunk<A, N> {
         let other_len = other.len();
         assert!(self_len + count <= N);
         assert!(other_len >= count);
-        if self.right + count > N {
-            unsafe { Chunk::force_copy(self.left, 0, self_len, self) };
-            self.right -= self.left;
-            self.left = 0;
-        }
-        unsafe { Chunk::force_copy_to(other.left, self.right, count, other, self) };
-        self.right += count;
-        other.left += count;
+        unsafe { self.raw().take_front_from(&mut other.raw(), count) };
+        debug_invariants!(self, other);
     }
 
     /// Remove `count` items from the back of `other` and append them to the
@@ -502,14 +777,98 @@ impl<A, const N: usize> Chunk<A, N> {
         let other_len = other.len();
         assert!(self_len + count <= N);
         assert!(other_len >= count);
-        if self.left < count {
-            unsafe { Chunk::force_copy(self.left, N - self_len, self_len, self) };
-            self.left = N - self_len;
-            self.right = N;
+        unsafe { self.raw().take_back_from(&mut other.raw(), count) };
+        debug_invariants!(self, other);
+    }
+
+    /// Insert a value at index `index`, splitting the chunk in two around its
+    /// median if it's full, the way a B-tree node splits.
+    ///
+    /// If there's room, this is just [`insert`][insert], and returns `None`.
+    /// Otherwise, out of the `N + 1` items including the new one, `self` keeps
+    /// the first `(N + 1) / 2`, the next one is taken out as the median, and
+    /// the rest are moved into a new chunk. You get the median and the new
+    /// chunk back, for the parent node to insert.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(n)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::iter::FromIterator;
+    /// # use sized_chunks::Chunk;
+    /// let mut left = Chunk::<i32, 4>::from_iter(vec![1, 2, 4, 5]);
+    /// let (median, right) = left.insert_and_split(2, 3).unwrap();
+    /// assert_eq!(&[1, 2], left.as_slice());
+    /// assert_eq!(3, median);
+    /// assert_eq!(&[4, 5], right.as_slice());
+    /// ```
+    ///
+    /// [insert]: #method.insert
+    pub fn insert_and_split(&mut self, index: usize, value: A) -> Option<(A, Self)>
+    where
+        P: Rebalance,
+    {
+        if index > self.len() {
+            panic!("Chunk::insert_and_split: index out of bounds");
+        }
+        if !self.is_full() {
+            self.insert(index, value);
+            return None;
+        }
+        let middle = (N + 1) / 2;
+        match index.cmp(&middle) {
+            Ordering::Less => {
+                let right = Self::from_back(self, N - middle);
+                let median = self.pop_back();
+                self.insert(index, value);
+                Some((median, right))
+            }
+            Ordering::Equal => Some((value, Self::from_back(self, N - middle))),
+            Ordering::Greater => {
+                let mut right = Self::from_back(self, N - middle - 1);
+                let median = self.pop_back();
+                right.insert(index - middle - 1, value);
+                Some((median, right))
+            }
         }
-        unsafe { Chunk::force_copy_to(other.right - count, self.left - count, count, other, self) };
-        self.left -= count;
-        other.right -= count;
+    }
+
+    /// Move items between `self` and its right hand neighbour `sibling`, so
+    /// that their lengths differ by at most one, with `self` taking the
+    /// extra item if there's an odd number of them.
+    ///
+    /// The order of the items across both chunks is preserved, so this is
+    /// what a B-tree does to a node which has underflowed when its neighbour
+    /// has items to spare.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn rebalance_with(&mut self, sibling: &mut Self) {
+        let self_len = self.len();
+        let target = (self_len + sibling.len() + 1) / 2;
+        match self_len.cmp(&target) {
+            Ordering::Less => self.drain_from_front(sibling, target - self_len),
+            Ordering::Greater => sibling.drain_from_back(self, self_len - target),
+            Ordering::Equal => {}
+        }
+    }
+
+    /// Move every item from `self`'s right hand neighbour `sibling` onto the
+    /// back of `self`, if they fit.
+    ///
+    /// If the two chunks have more than `N` items between them, you get
+    /// `sibling` back untouched as the error.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn merge_with(&mut self, mut sibling: Self) -> Result<(), Self> {
+        let count = sibling.len();
+        if self.len() + count > N {
+            return Err(sibling);
+        }
+        self.drain_from_front(&mut sibling, count);
+        Ok(())
     }
 
     /// Update the value at index `index`, returning the old value.
@@ -524,32 +883,24 @@ impl<A, const N: usize> Chunk<A, N> {
     /// Insert a new value at index `index`, shifting all the following values
     /// to the right.
     ///
+    /// Inserting at either end works like a push, using the chunk's policy
+    /// `P` if there's no room on that side.
+    ///
     /// Panics if the index is out of bounds or the chunk is full.
     ///
     /// Time: O(n) for the number of elements shifted
-    pub fn insert(&mut self, index: usize, value: A) {
+    pub fn insert(&mut self, index: usize, value: A)
+    where
+        P: Rebalance,
+    {
         if self.is_full() {
             panic!("Chunk::insert: chunk is full");
         }
         if index > self.len() {
             panic!("Chunk::insert: index out of bounds");
         }
-        let real_index = index + self.left;
-        let left_size = index;
-        let right_size = self.right - real_index;
-        if self.right == N || (self.left > 0 && left_size < right_size) {
-            unsafe {
-                Chunk::force_copy(self.left, self.left - 1, left_size, self);
-                Chunk::force_write(real_index - 1, value, self);
-            }
-            self.left -= 1;
-        } else {
-            unsafe {
-                Chunk::force_copy(real_index, real_index + 1, right_size, self);
-                Chunk::force_write(real_index, value, self);
-            }
-            self.right += 1;
-        }
+        unsafe { self.raw().insert::<P>(index, value) };
+        debug_invariants!(self);
     }
 
     /// Insert a new value into the chunk in sorted order.
@@ -573,6 +924,7 @@ impl<A, const N: usize> Chunk<A, N> {
     pub fn insert_ordered(&mut self, value: A)
     where
         A: Ord,
+        P: Rebalance,
     {
         if self.is_full() {
             panic!("Chunk::insert: chunk is full");
@@ -592,10 +944,14 @@ impl<A, const N: usize> Chunk<A, N> {
     /// Time: O(m+n) where m is the number of elements inserted and n is the number
     /// of elements following the insertion index. Calling `insert`
     /// repeatedly would be O(m*n).
+    ///
+    /// Inserting at either end works like a push, using the chunk's policy
+    /// `P` if there's no room on that side.
     pub fn insert_from<Iterable, I>(&mut self, index: usize, iter: Iterable)
     where
         Iterable: IntoIterator<Item = A, IntoIter = I>,
         I: ExactSizeIterator<Item = A>,
+        P: Rebalance,
     {
         let iter = iter.into_iter();
         let insert_size = iter.len();
@@ -608,34 +964,8 @@ impl<A, const N: usize> Chunk<A, N> {
         if index > self.len() {
             panic!("Chunk::insert_from: index out of bounds");
         }
-        let real_index = index + self.left;
-        let left_size = index;
-        let right_size = self.right - real_index;
-        if self.right == N || (self.left >= insert_size && left_size < right_size) {
-            unsafe {
-                Chunk::force_copy(self.left, self.left - insert_size, left_size, self);
-                let write_index = real_index - insert_size;
-                Chunk::write_from_iter(write_index, iter, self);
-            }
-            self.left -= insert_size;
-        } else if self.left == 0 || (self.right + insert_size <= Self::CAPACITY) {
-            unsafe {
-                Chunk::force_copy(real_index, real_index + insert_size, right_size, self);
-                let write_index = real_index;
-                Chunk::write_from_iter(write_index, iter, self);
-            }
-            self.right += insert_size;
-        } else {
-            unsafe {
-                Chunk::force_copy(self.left, 0, left_size, self);
-                Chunk::force_copy(real_index, left_size + insert_size, right_size, self);
-                let write_index = left_size;
-                Chunk::write_from_iter(write_index, iter, self);
-            }
-            self.right -= self.left;
-            self.right += insert_size;
-            self.left = 0;
-        }
+        unsafe { self.raw().insert_from::<P, _>(index, iter) };
+        debug_invariants!(self);
     }
 
     /// Remove the value at index `index`, shifting all the following values to
@@ -650,23 +980,16 @@ impl<A, const N: usize> Chunk<A, N> {
         if index >= self.len() {
             panic!("Chunk::remove: index out of bounds");
         }
-        let real_index = index + self.left;
-        let value = unsafe { Chunk::force_read(real_index, self) };
-        let left_size = index;
-        let right_size = self.right - real_index - 1;
-        if left_size < right_size {
-            unsafe { Chunk::force_copy(self.left, self.left + 1, left_size, self) };
-            self.left += 1;
-        } else {
-            unsafe { Chunk::force_copy(real_index + 1, real_index, right_size, self) };
-            self.right -= 1;
-        }
+        let value = unsafe { self.raw().remove(index) };
+        debug_invariants!(self);
         value
     }
 
     /// Construct an iterator that drains values from the front of the chunk.
     pub fn drain(&mut self) -> Drain<'_, A, N> {
-        Drain { chunk: self }
+        Drain {
+            chunk: self.as_chunk_mut(),
+        }
     }
 
     /// Discard the contents of the chunk.
@@ -676,6 +999,93 @@ impl<A, const N: usize> Chunk<A, N> {
         unsafe { ptr::drop_in_place(self.as_mut_slice()) }
         self.left = 0;
         self.right = 0;
+        debug_invariants!(self);
+    }
+
+    /// Get the uninitialised space before the first element of the chunk.
+    ///
+    /// You can write values into the end of this slice and then call
+    /// [`commit_front`][commit_front] to make them part of the chunk, which
+    /// lets you fill it without initialising a temporary buffer first.
+    ///
+    /// [commit_front]: #method.commit_front
+    pub fn spare_capacity_front_mut(&mut self) -> &mut [MaybeUninit<A>] {
+        unsafe { from_raw_parts_mut(self.mut_ptr(0).cast(), self.left) }
+    }
+
+    /// Get the uninitialised space after the last element of the chunk.
+    ///
+    /// You can write values into the start of this slice and then call
+    /// [`commit_back`][commit_back] or [`set_len`][set_len] to make them part
+    /// of the chunk, which lets you fill it without initialising a temporary
+    /// buffer first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::Chunk;
+    /// # use std::mem::MaybeUninit;
+    /// let mut chunk = Chunk::<u8, 64>::new();
+    /// let spare = chunk.spare_capacity_back_mut();
+    /// for (index, slot) in spare.iter_mut().take(4).enumerate() {
+    ///     *slot = MaybeUninit::new(index as u8);
+    /// }
+    /// unsafe { chunk.commit_back(4) };
+    /// assert_eq!(&[0, 1, 2, 3], chunk.as_slice());
+    /// ```
+    ///
+    /// [commit_back]: #method.commit_back
+    /// [set_len]: #method.set_len
+    pub fn spare_capacity_back_mut(&mut self) -> &mut [MaybeUninit<A>] {
+        let right = self.right;
+        unsafe { from_raw_parts_mut(self.mut_ptr(right).cast(), N - right) }
+    }
+
+    /// Extend the chunk at the front over the last `count` slots of
+    /// [`spare_capacity_front_mut`][spare_capacity_front_mut].
+    ///
+    /// # Safety
+    ///
+    /// `count` must be no larger than the length of the front spare capacity,
+    /// and those `count` slots must have been initialised.
+    ///
+    /// [spare_capacity_front_mut]: #method.spare_capacity_front_mut
+    pub unsafe fn commit_front(&mut self, count: usize) {
+        debug_assert!(count <= self.left);
+        self.left -= count;
+        debug_invariants!(self);
+    }
+
+    /// Extend the chunk at the back over the first `count` slots of
+    /// [`spare_capacity_back_mut`][spare_capacity_back_mut].
+    ///
+    /// # Safety
+    ///
+    /// `count` must be no larger than the length of the back spare capacity,
+    /// and those `count` slots must have been initialised.
+    ///
+    /// [spare_capacity_back_mut]: #method.spare_capacity_back_mut
+    pub unsafe fn commit_back(&mut self, count: usize) {
+        debug_assert!(count <= N - self.right);
+        self.right += count;
+        debug_invariants!(self);
+    }
+
+    /// Set the length of the chunk by moving its back edge, without dropping
+    /// or initialising anything.
+    ///
+    /// # Safety
+    ///
+    /// `new_len` must be no larger than the current length plus the length of
+    /// [`spare_capacity_back_mut`][spare_capacity_back_mut], and every element
+    /// up to `new_len` must be initialised. If the chunk shrinks, the
+    /// elements past `new_len` are leaked rather than dropped.
+    ///
+    /// [spare_capacity_back_mut]: #method.spare_capacity_back_mut
+    pub unsafe fn set_len(&mut self, new_len: usize) {
+        debug_assert!(self.left + new_len <= N);
+        self.right = self.left + new_len;
+        debug_invariants!(self);
     }
 
     /// Get a reference to the contents of the chunk as a slice.
@@ -697,15 +1107,68 @@ impl<A, const N: usize> Chunk<A, N> {
             )
         }
     }
+
+    /// Construct a chunk of the same capacity by calling `f` on each item in
+    /// turn, from front to back.
+    ///
+    /// If `f` panics, the items it's already returned are dropped along with
+    /// the ones it hasn't seen yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::Chunk;
+    /// let chunk = Chunk::<i32, 4>::from([1, 2, 3]);
+    /// let strings: Chunk<String, 4> = chunk.map(|i| i.to_string());
+    /// assert_eq!(&["1", "2", "3"], strings.as_slice());
+    /// ```
+    ///
+    /// Time: O(n)
+    pub fn map<B, F>(self, mut f: F) -> RebalancingChunk<B, P, N>
+    where
+        F: FnMut(A) -> B,
+    {
+        match self.try_map(|value| Ok::<_, Infallible>(f(value))) {
+            Ok(chunk) => chunk,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Construct a chunk of the same capacity by calling `f` on each item in
+    /// turn, from front to back, stopping at the first error `f` returns.
+    ///
+    /// On error, or if `f` panics, the items it's already returned are
+    /// dropped along with the ones it hasn't seen yet.
+    ///
+    /// Time: O(n)
+    pub fn try_map<B, E, F>(mut self, mut f: F) -> Result<RebalancingChunk<B, P, N>, E>
+    where
+        F: FnMut(A) -> Result<B, E>,
+    {
+        let mut out = RebalancingChunk::new();
+        out.left = self.left;
+        out.right = self.left;
+        while self.left < self.right {
+            // Move `left` past the value first, so `self` doesn't drop it
+            // again if `f` panics.
+            let value = unsafe { RebalancingChunk::force_read(self.left, &mut self) };
+            self.left += 1;
+            let value = f(value)?;
+            unsafe { RebalancingChunk::force_write(out.right, value, &mut out) };
+            out.right += 1;
+        }
+        debug_invariants!(out);
+        Ok(out)
+    }
 }
 
-impl<A, const N: usize> Default for Chunk<A, N> {
+impl<A, P, const N: usize> Default for RebalancingChunk<A, P, N> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<A, I, const N: usize> Index<I> for Chunk<A, N>
+impl<A, I, P, const N: usize> Index<I> for RebalancingChunk<A, P, N>
 where
     I: SliceIndex<[A]>,
 {
@@ -715,7 +1178,7 @@ where
     }
 }
 
-impl<A, I, const N: usize> IndexMut<I> for Chunk<A, N>
+impl<A, I, P, const N: usize> IndexMut<I> for RebalancingChunk<A, P, N>
 where
     I: SliceIndex<[A]>,
 {
@@ -724,7 +1187,7 @@ where
     }
 }
 
-impl<A, const N: usize> Debug for Chunk<A, N>
+impl<A, P, const N: usize> Debug for RebalancingChunk<A, P, N>
 where
     A: Debug,
 {
@@ -734,7 +1197,7 @@ where
     }
 }
 
-impl<A, const N: usize> Hash for Chunk<A, N>
+impl<A, P, const N: usize> Hash for RebalancingChunk<A, P, N>
 where
     A: Hash,
 {
@@ -748,7 +1211,7 @@ where
     }
 }
 
-impl<A, Slice, const N: usize> PartialEq<Slice> for Chunk<A, N>
+impl<A, Slice, P, const N: usize> PartialEq<Slice> for RebalancingChunk<A, P, N>
 where
     Slice: Borrow<[A]>,
     A: PartialEq,
@@ -758,18 +1221,19 @@ where
     }
 }
 
-impl<A, const N: usize> Eq for Chunk<A, N> where A: Eq {}
+impl<A, P, const N: usize> Eq for RebalancingChunk<A, P, N> where A: Eq {}
 
-impl<A, const N: usize> PartialOrd for Chunk<A, N>
+impl<A, Slice, P, const N: usize> PartialOrd<Slice> for RebalancingChunk<A, P, N>
 where
+    Slice: Borrow<[A]>,
     A: PartialOrd,
 {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.iter().partial_cmp(other.iter())
+    fn partial_cmp(&self, other: &Slice) -> Option<Ordering> {
+        self.iter().partial_cmp(other.borrow().iter())
     }
 }
 
-impl<A, const N: usize> Ord for Chunk<A, N>
+impl<A, P, const N: usize> Ord for RebalancingChunk<A, P, N>
 where
     A: Ord,
 {
@@ -779,7 +1243,10 @@ where
 }
 
 #[cfg(feature = "std")]
-impl<const N: usize> io::Write for Chunk<u8, N> {
+impl<P, const N: usize> io::Write for RebalancingChunk<u8, P, N>
+where
+    P: Rebalance,
+{
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         let old_len = self.len();
         self.extend(buf.iter().cloned().take(N - old_len));
@@ -792,7 +1259,7 @@ impl<const N: usize> io::Write for Chunk<u8, N> {
 }
 
 #[cfg(feature = "std")]
-impl<const N: usize> std::io::Read for Chunk<u8, N> {
+impl<P, const N: usize> std::io::Read for RebalancingChunk<u8, P, N> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         let read_size = buf.len().min(self.len());
         if read_size == 0 {
@@ -806,14 +1273,31 @@ impl<const N: usize> std::io::Read for Chunk<u8, N> {
     }
 }
 
-impl<A, T, const N: usize> From<InlineArray<A, T>> for Chunk<A, N> {
+impl<P, const N: usize> fmt::Write for RebalancingChunk<u8, P, N>
+where
+    P: Rebalance,
+{
+    /// Append the UTF-8 bytes of a string to the back of the chunk.
+    ///
+    /// Fails without writing anything if the chunk doesn't have room for the
+    /// whole string.
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.len() > N - self.len() {
+            return Err(Error);
+        }
+        self.extend(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<A, T, P, const N: usize> From<InlineArray<A, T>> for RebalancingChunk<A, P, N> {
     #[inline]
     fn from(mut array: InlineArray<A, T>) -> Self {
         Self::from(&mut array)
     }
 }
 
-impl<'a, A, T, const N: usize> From<&'a mut InlineArray<A, T>> for Chunk<A, N> {
+impl<A, T, P, const N: usize> From<&mut InlineArray<A, T>> for RebalancingChunk<A, P, N> {
     fn from(array: &mut InlineArray<A, T>) -> Self {
         // The first capacity comparison is to help optimize it out
         assert!(
@@ -831,31 +1315,119 @@ impl<'a, A, T, const N: usize> From<&'a mut InlineArray<A, T>> for Chunk<A, N> {
     }
 }
 
-impl<A, const N: usize> Borrow<[A]> for Chunk<A, N> {
+impl<A, P, const M: usize, const N: usize> From<[A; M]> for RebalancingChunk<A, P, N> {
+    /// Construct a chunk from an array of up to `N` items.
+    ///
+    /// This fails to compile if `M` is larger than `N`.
+    ///
+    /// ```rust,compile_fail
+    /// # use sized_chunks::Chunk;
+    /// let chunk: Chunk<i32, 2> = Chunk::from([1, 2, 3]);
+    /// ```
+    fn from(array: [A; M]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Fits::<M, N>::CHECK;
+        let array = ManuallyDrop::new(array);
+        let mut out = Self::new();
+        unsafe { ptr::copy_nonoverlapping(array.as_ptr(), out.mut_ptr(0), M) };
+        out.right = M;
+        out
+    }
+}
+
+impl<A, P, const N: usize> TryFrom<RebalancingChunk<A, P, N>> for [A; N] {
+    type Error = RebalancingChunk<A, P, N>;
+
+    /// Move the contents of a full chunk into an array.
+    ///
+    /// If the chunk isn't full, you get it back as the error.
+    fn try_from(chunk: RebalancingChunk<A, P, N>) -> Result<Self, Self::Error> {
+        if !chunk.is_full() {
+            return Err(chunk);
+        }
+        let chunk = ManuallyDrop::new(chunk);
+        Ok(unsafe { ptr::read(chunk.data.as_ptr()) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, P, const N: usize> TryFrom<Vec<A>> for RebalancingChunk<A, P, N> {
+    type Error = Vec<A>;
+
+    /// Move the contents of a `Vec` into a chunk.
+    ///
+    /// If the `Vec` has more than `N` items, you get it back as the error.
+    fn try_from(mut vec: Vec<A>) -> Result<Self, Self::Error> {
+        if vec.len() > N {
+            return Err(vec);
+        }
+        let mut chunk = Self::new();
+        unsafe {
+            ptr::copy_nonoverlapping(vec.as_ptr(), chunk.mut_ptr(0), vec.len());
+            chunk.right = vec.len();
+            vec.set_len(0);
+        }
+        Ok(chunk)
+    }
+}
+
+impl<'a, A: Clone, P, const N: usize> TryFrom<&'a [A]> for RebalancingChunk<A, P, N>
+where
+    P: Rebalance,
+{
+    type Error = &'a [A];
+
+    /// Clone the contents of a slice into a chunk.
+    ///
+    /// If the slice has more than `N` items, you get it back as the error.
+    fn try_from(slice: &'a [A]) -> Result<Self, Self::Error> {
+        if slice.len() > N {
+            return Err(slice);
+        }
+        Ok(slice.iter().cloned().collect())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, P, const N: usize> From<RebalancingChunk<A, P, N>> for Vec<A> {
+    fn from(mut chunk: RebalancingChunk<A, P, N>) -> Self {
+        let len = chunk.len();
+        let mut vec = Vec::with_capacity(len);
+        unsafe {
+            ptr::copy_nonoverlapping(chunk.ptr(chunk.left), vec.as_mut_ptr(), len);
+            vec.set_len(len);
+        }
+        chunk.left = 0;
+        chunk.right = 0;
+        vec
+    }
+}
+
+impl<A, P, const N: usize> Borrow<[A]> for RebalancingChunk<A, P, N> {
     fn borrow(&self) -> &[A] {
         self.as_slice()
     }
 }
 
-impl<A, const N: usize> BorrowMut<[A]> for Chunk<A, N> {
+impl<A, P, const N: usize> BorrowMut<[A]> for RebalancingChunk<A, P, N> {
     fn borrow_mut(&mut self) -> &mut [A] {
         self.as_mut_slice()
     }
 }
 
-impl<A, const N: usize> AsRef<[A]> for Chunk<A, N> {
+impl<A, P, const N: usize> AsRef<[A]> for RebalancingChunk<A, P, N> {
     fn as_ref(&self) -> &[A] {
         self.as_slice()
     }
 }
 
-impl<A, const N: usize> AsMut<[A]> for Chunk<A, N> {
+impl<A, P, const N: usize> AsMut<[A]> for RebalancingChunk<A, P, N> {
     fn as_mut(&mut self) -> &mut [A] {
         self.as_mut_slice()
     }
 }
 
-impl<A, const N: usize> Deref for Chunk<A, N> {
+impl<A, P, const N: usize> Deref for RebalancingChunk<A, P, N> {
     type Target = [A];
 
     fn deref(&self) -> &Self::Target {
@@ -863,13 +1435,16 @@ impl<A, const N: usize> Deref for Chunk<A, N> {
     }
 }
 
-impl<A, const N: usize> DerefMut for Chunk<A, N> {
+impl<A, P, const N: usize> DerefMut for RebalancingChunk<A, P, N> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.as_mut_slice()
     }
 }
 
-impl<A, const N: usize> FromIterator<A> for Chunk<A, N> {
+impl<A, P, const N: usize> FromIterator<A> for RebalancingChunk<A, P, N>
+where
+    P: Rebalance,
+{
     fn from_iter<I>(it: I) -> Self
     where
         I: IntoIterator<Item = A>,
@@ -882,7 +1457,7 @@ impl<A, const N: usize> FromIterator<A> for Chunk<A, N> {
     }
 }
 
-impl<'a, A, const N: usize> IntoIterator for &'a Chunk<A, N> {
+impl<'a, A, P, const N: usize> IntoIterator for &'a RebalancingChunk<A, P, N> {
     type Item = &'a A;
     type IntoIter = SliceIter<'a, A>;
     fn into_iter(self) -> Self::IntoIter {
@@ -890,7 +1465,7 @@ impl<'a, A, const N: usize> IntoIterator for &'a Chunk<A, N> {
     }
 }
 
-impl<'a, A, const N: usize> IntoIterator for &'a mut Chunk<A, N> {
+impl<'a, A, P, const N: usize> IntoIterator for &'a mut RebalancingChunk<A, P, N> {
     type Item = &'a mut A;
     type IntoIter = SliceIterMut<'a, A>;
     fn into_iter(self) -> Self::IntoIter {
@@ -898,7 +1473,10 @@ impl<'a, A, const N: usize> IntoIterator for &'a mut Chunk<A, N> {
     }
 }
 
-impl<A, const N: usize> Extend<A> for Chunk<A, N> {
+impl<A, P, const N: usize> Extend<A> for RebalancingChunk<A, P, N>
+where
+    P: Rebalance,
+{
     /// Append the contents of the iterator to the back of the chunk.
     ///
     /// Panics if the chunk exceeds its capacity.
@@ -914,9 +1492,10 @@ impl<A, const N: usize> Extend<A> for Chunk<A, N> {
     }
 }
 
-impl<'a, A, const N: usize> Extend<&'a A> for Chunk<A, N>
+impl<'a, A, P, const N: usize> Extend<&'a A> for RebalancingChunk<A, P, N>
 where
     A: 'a + Copy,
+    P: Rebalance,
 {
     /// Append the contents of the iterator to the back of the chunk.
     ///
@@ -933,17 +1512,20 @@ where
     }
 }
 
-impl<A, const N: usize> IntoIterator for Chunk<A, N> {
+impl<A, P, const N: usize> IntoIterator for RebalancingChunk<A, P, N> {
     type Item = A;
     type IntoIter = Iter<A, N>;
 
     fn into_iter(self) -> Self::IntoIter {
-        Iter { chunk: self }
+        Iter {
+            chunk: self.into_chunk(),
+        }
     }
 }
 
 #[cfg(test)]
 #[rustfmt::skip]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
     use super::*;
 
@@ -1039,9 +1621,11 @@ mod test {
     fn issue_11_testcase3b() {
         let _ = std::panic::catch_unwind(|| {
             let mut chunk = Chunk::<DropDetector, 5>::new();
-            chunk.push_back(DropDetector::new(1));
-            chunk.push_back(DropDetector::new(2));
-            chunk.push_back(DropDetector::new(3));
+            // The chunk is dropped while unwinding, so it must only hold values which are allowed
+            // to be dropped.
+            chunk.push_back(DropDetector::new(42));
+            chunk.push_back(DropDetector::new(43));
+            chunk.push_back(DropDetector::new(43));
 
             chunk.insert_from(
                 1,
@@ -1209,6 +1793,51 @@ mod test {
         assert_eq!(should_vec, out_vec);
     }
 
+    #[test]
+    fn insert_and_split() {
+        for &capacity_is_odd in &[false, true] {
+            let len = if capacity_is_odd { 5 } else { 4 };
+            for index in 0..=len {
+                let mut expected: Vec<i32> = (0..len as i32).map(|i| i * 10).collect();
+                expected.insert(index, -1);
+                let (left, median, right): (Vec<i32>, i32, Vec<i32>) = if capacity_is_odd {
+                    let mut left = Chunk::<i32, 5>::from_iter((0..5).map(|i| i * 10));
+                    let (median, right) = left.insert_and_split(index, -1).unwrap();
+                    (left.into_iter().collect(), median, right.into_iter().collect())
+                } else {
+                    let mut left = Chunk::<i32, 4>::from_iter((0..4).map(|i| i * 10));
+                    let (median, right) = left.insert_and_split(index, -1).unwrap();
+                    (left.into_iter().collect(), median, right.into_iter().collect())
+                };
+                let middle = (len + 1) / 2;
+                assert_eq!(expected[..middle], left[..]);
+                assert_eq!(expected[middle], median);
+                assert_eq!(expected[middle + 1..], right[..]);
+            }
+        }
+        let mut chunk = Chunk::<i32, 4>::from_iter(0..3);
+        assert!(chunk.insert_and_split(1, 7).is_none());
+        assert_eq!(&[0, 7, 1, 2], chunk.as_slice());
+    }
+
+    #[test]
+    fn rebalance_and_merge() {
+        let mut left = Chunk::<i32, 8>::from_iter(0..7);
+        let mut right = Chunk::<i32, 8>::from_iter(7..8);
+        left.rebalance_with(&mut right);
+        assert_eq!(&[0, 1, 2, 3], left.as_slice());
+        assert_eq!(&[4, 5, 6, 7], right.as_slice());
+        left.drop_left(3);
+        left.rebalance_with(&mut right);
+        assert_eq!(&[3, 4, 5], left.as_slice());
+        assert_eq!(&[6, 7], right.as_slice());
+        let full = Chunk::<i32, 8>::from_iter(10..16);
+        let full = left.merge_with(full).unwrap_err();
+        assert_eq!(6, full.len());
+        left.merge_with(right).unwrap();
+        assert_eq!(&[3, 4, 5, 6, 7], left.as_slice());
+    }
+
     #[test]
     fn ref_iter() {
         let mut chunk = Chunk::<_, 64>::new();
@@ -1295,9 +1924,20 @@ mod test {
         assert_eq!(should_vec, out_vec);
     }
 
-    use crate::tests::DropTest;
+    use crate::tests::{DropTest, FragileClone, PanickingIter};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
     use std::sync::atomic::{AtomicUsize, Ordering};
 
+    #[test]
+    #[should_panic(expected = "Chunk::check_invariants: left index 3 is past right index 2")]
+    fn check_invariants() {
+        // Don't try to drop the broken chunk while unwinding.
+        let mut chunk = ManuallyDrop::new(Chunk::<i32, 4>::from([1, 2]));
+        chunk.check_invariants();
+        chunk.left = 3;
+        chunk.check_invariants();
+    }
+
     #[test]
     fn dropping() {
         let counter = AtomicUsize::new(0);
@@ -1318,6 +1958,321 @@ mod test {
         assert_eq!(0, counter.load(Ordering::Relaxed));
     }
 
+    /// Insert from an iterator that panics halfway, into a chunk of `len`
+    /// items with `front` free slots before them.
+    fn panicking_insert_from(front: usize, len: usize, index: usize) {
+        let counter = AtomicUsize::new(0);
+        let mut chunk: Chunk<DropTest<'_>, 16> = Chunk::new();
+        for _ in 0..front + len {
+            chunk.push_back(DropTest::new(&counter));
+        }
+        for _ in 0..front {
+            chunk.pop_front();
+        }
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let iter = PanickingIter {
+                counter: &counter,
+                len: 3,
+                panic_after: 1,
+            };
+            chunk.insert_from(index, iter);
+        }));
+        assert!(result.is_err());
+        assert_eq!(len + 1, chunk.len());
+        assert_eq!(len + 1, counter.load(Ordering::Relaxed));
+        drop(chunk);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn insert_from_panic_safety() {
+        // Shifting the left side to the left
+        panicking_insert_from(5, 6, 1);
+        // Shifting the right side to the right
+        panicking_insert_from(0, 4, 2);
+        // Shifting both sides to make room at the left edge
+        panicking_insert_from(2, 12, 10);
+    }
+
+    #[test]
+    fn clone_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut chunk: Chunk<_, 8> = Chunk::new();
+        chunk.push_back(FragileClone::new(&counter, false));
+        chunk.push_back(FragileClone::new(&counter, false));
+        chunk.push_back(FragileClone::new(&counter, true));
+        chunk.push_back(FragileClone::new(&counter, false));
+        assert!(catch_unwind(AssertUnwindSafe(|| chunk.clone())).is_err());
+        assert_eq!(4, counter.load(Ordering::Relaxed));
+        drop(chunk);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn collect_from_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut iter = PanickingIter {
+            counter: &counter,
+            len: 5,
+            panic_after: 2,
+        };
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            Chunk::<_, 8>::collect_from(&mut iter, 5)
+        }));
+        assert!(result.is_err());
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn const_constructors() {
+        static EMPTY: Chunk<u8, 16> = Chunk::new();
+        const ARRAY: Chunk<u8, 3> = Chunk::from_array([1, 2, 3]);
+        assert!(EMPTY.is_empty());
+        assert_eq!(&[1, 2, 3], ARRAY.as_slice());
+        assert!(ARRAY.is_full());
+    }
+
+    #[test]
+    fn array_conversions() {
+        let chunk: Chunk<i32, 4> = Chunk::from([1, 2, 3]);
+        assert_eq!(&[1, 2, 3], chunk.as_slice());
+        let chunk: Chunk<i32, 4> = Chunk::from([]);
+        assert!(chunk.is_empty());
+
+        let chunk: Chunk<i32, 3> = Chunk::from([1, 2, 3]);
+        assert_eq!(Ok([1, 2, 3]), <[i32; 3]>::try_from(chunk));
+        let mut chunk: Chunk<i32, 3> = Chunk::from([1, 2]);
+        chunk = <[i32; 3]>::try_from(chunk).unwrap_err();
+        assert_eq!(&[1, 2], chunk.as_slice());
+
+        // A full chunk that's been shifted off the left edge
+        let mut chunk: Chunk<i32, 3> = Chunk::from([1, 2, 3]);
+        chunk.pop_front();
+        chunk.push_back(4);
+        assert_eq!(Ok([2, 3, 4]), <[i32; 3]>::try_from(chunk));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vec_conversions() {
+        let vec = vec!["a".to_string(), "b".to_string()];
+        let chunk = Chunk::<String, 2>::try_from(vec).unwrap();
+        assert_eq!(&["a", "b"], chunk.as_slice());
+        let vec = Vec::from(chunk);
+        assert_eq!(vec!["a", "b"], vec);
+
+        let vec = Chunk::<String, 1>::try_from(vec).unwrap_err();
+        assert_eq!(vec!["a", "b"], vec);
+
+        let mut chunk = Chunk::<i32, 8>::from([1, 2, 3, 4]);
+        chunk.pop_front();
+        assert_eq!(vec![2, 3, 4], Vec::from(chunk));
+    }
+
+    #[test]
+    fn slice_conversions() {
+        let slice: &[_] = &[1, 2, 3];
+        let chunk = Chunk::<i32, 4>::try_from(slice).unwrap();
+        assert_eq!(slice, chunk.as_slice());
+        assert_eq!(Err(slice), Chunk::<i32, 2>::try_from(slice).map(|_| ()));
+    }
+
+    #[test]
+    fn capacity_changes() {
+        let mut small = Chunk::<String, 4>::new();
+        small.push_front("b".to_string());
+        small.push_front("a".to_string());
+        let mut large: Chunk<String, 8> = small.into_capacity().unwrap();
+        assert_eq!(&["a", "b"], large.as_slice());
+
+        let mut other: Chunk<String, 2> = ["c", "d"].iter().map(|s| s.to_string()).collect();
+        large.append_from(&mut other);
+        assert!(other.is_empty());
+        assert_eq!(&["a", "b", "c", "d"], large.as_slice());
+
+        let large = large.into_capacity::<3>().unwrap_err();
+        assert_eq!(4, large.len());
+        let exact: Chunk<String, 4> = large.into_capacity().unwrap();
+        assert!(exact.is_full());
+        let copy: Chunk<String, 16> = exact.iter().cloned().collect();
+        assert_eq!(exact, copy);
+
+        let mut chunk = Chunk::<i32, 4>::from([1, 2, 3, 4]);
+        chunk.pop_front();
+        chunk.pop_front();
+        chunk.append_from(&mut Chunk::<i32, 2>::from([5, 6]));
+        assert_eq!(&[3, 4, 5, 6], chunk.as_slice());
+    }
+
+    #[test]
+    #[should_panic(expected = "Chunk::append_from: chunk size overflow")]
+    fn append_from_too_much() {
+        let mut chunk = Chunk::<i32, 4>::from([1, 2, 3]);
+        chunk.append_from(&mut Chunk::<i32, 8>::from([4, 5]));
+    }
+
+    #[test]
+    fn map() {
+        let mut chunk = Chunk::<i32, 8>::new();
+        chunk.push_front(2);
+        chunk.push_front(1);
+        let mapped = chunk.map(|i| i.to_string());
+        assert_eq!(&["1", "2"], mapped.as_slice());
+        assert_eq!(6, mapped.front_capacity());
+
+        let chunk = Chunk::<i32, 8>::from([1, 2, 3]);
+        assert_eq!(Err(2), chunk.try_map(|i| if i < 2 { Ok(i) } else { Err(i) }));
+    }
+
+    #[test]
+    fn map_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        crate::tests::map_panic_safety(
+            &counter,
+            |counter| (0..8).map(|_| DropTest::new(counter)).collect::<Chunk<_, 8>>(),
+            |chunk, f| {
+                let _ = chunk.map(f);
+            },
+        );
+    }
+
+    #[test]
+    fn from_fn() {
+        let chunk = Chunk::<String, 3>::from_fn(|i| i.to_string());
+        assert_eq!(&["0", "1", "2"], chunk.as_slice());
+        let chunk = Chunk::<String, 0>::from_fn(|_| unreachable!());
+        assert!(chunk.is_empty());
+    }
+
+    #[test]
+    fn rebalance_policies() {
+        let mut chunk = Chunk::<i32, 16>::new();
+        chunk.push_back(0);
+        chunk.push_front_with::<ShiftToEdge>(-1);
+        assert_eq!((14, 0), (chunk.front_capacity(), chunk.back_capacity()));
+
+        let mut chunk = Chunk::<i32, 16>::new();
+        chunk.push_back(0);
+        chunk.push_front_with::<Centre>(-1);
+        assert_eq!((7, 7), (chunk.front_capacity(), chunk.back_capacity()));
+
+        let mut chunk = Chunk::<i32, 16>::new();
+        chunk.push_back(0);
+        chunk.push_front_with::<Proportional<75>>(-1);
+        assert_eq!((11, 3), (chunk.front_capacity(), chunk.back_capacity()));
+        assert_eq!(&[-1, 0], chunk.as_slice());
+
+        // Alternating pushes with `Centre` never need to shift more than a
+        // logarithmic number of times.
+        let mut chunk = Chunk::<usize, 64>::new();
+        let mut shifts = 0;
+        for i in 0..64 {
+            let before = (chunk.front_capacity(), chunk.back_capacity());
+            if i % 2 == 0 {
+                chunk.push_back_with::<Centre>(i);
+                if before.1 == 0 {
+                    shifts += 1;
+                }
+            } else {
+                chunk.push_front_with::<Centre>(i);
+                if before.0 == 0 {
+                    shifts += 1;
+                }
+            }
+        }
+        assert!(shifts <= 8);
+        let expected: Vec<usize> = (1..64).step_by(2).rev().chain((0..64).step_by(2)).collect();
+        assert_eq!(expected, chunk.as_slice());
+    }
+
+    #[test]
+    fn type_level_rebalance_policy() {
+        let mut chunk = RebalancingChunk::<i32, Centre, 16>::new();
+        chunk.push_back(0);
+        assert_eq!((8, 7), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.align_right();
+        chunk.push_back(1);
+        assert_eq!((7, 7), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.align_left();
+        chunk.push_front(-1);
+        assert_eq!((6, 7), (chunk.front_capacity(), chunk.back_capacity()));
+        assert_eq!(&[-1, 0, 1], chunk.as_slice());
+
+        chunk.align_left();
+        chunk.insert(0, -2);
+        assert_eq!((6, 6), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.align_right();
+        chunk.insert_from(chunk.len(), vec![2, 3]);
+        assert_eq!((6, 4), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.align_right();
+        chunk.extend(4..6);
+        assert_eq!((5, 3), (chunk.front_capacity(), chunk.back_capacity()));
+        assert_eq!(&[-2, -1, 0, 1, 2, 3, 4, 5], chunk.as_slice());
+
+        let chunk: RebalancingChunk<i32, Centre, 16> = (0..4).collect();
+        assert_eq!((8, 4), (chunk.front_capacity(), chunk.back_capacity()));
+    }
+
+    #[test]
+    fn realigning() {
+        let mut chunk = Chunk::<i32, 10>::new();
+        chunk.extend(0..4);
+        chunk.align_right();
+        assert_eq!((6, 0), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.recenter();
+        assert_eq!((3, 3), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.align_left();
+        assert_eq!((0, 6), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.reserve_front(2);
+        assert_eq!((2, 4), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.reserve_back(5);
+        assert_eq!((1, 5), (chunk.front_capacity(), chunk.back_capacity()));
+        assert_eq!(&[0, 1, 2, 3], chunk.as_slice());
+    }
+
+    #[test]
+    #[should_panic(expected = "Chunk::reserve_front: chunk cannot fit 7 elements")]
+    fn reserve_too_much() {
+        let mut chunk = Chunk::<i32, 10>::new();
+        chunk.extend(0..4);
+        chunk.reserve_front(7);
+    }
+
+    #[test]
+    fn spare_capacity() {
+        let mut chunk = Chunk::<u32, 8>::new();
+        chunk.push_front(3);
+        chunk.push_front(2);
+        assert_eq!(0, chunk.spare_capacity_back_mut().len());
+        let front = chunk.spare_capacity_front_mut();
+        assert_eq!(6, front.len());
+        front[4] = MaybeUninit::new(0);
+        front[5] = MaybeUninit::new(1);
+        unsafe { chunk.commit_front(2) };
+        assert_eq!(&[0, 1, 2, 3], chunk.as_slice());
+
+        let mut chunk = Chunk::<u32, 8>::new();
+        chunk.push_back(0);
+        for (index, slot) in chunk.spare_capacity_back_mut().iter_mut().enumerate() {
+            *slot = MaybeUninit::new(index as u32 + 1);
+        }
+        unsafe { chunk.set_len(5) };
+        assert_eq!(&[0, 1, 2, 3, 4], chunk.as_slice());
+        unsafe { chunk.commit_back(3) };
+        assert_eq!(&[0, 1, 2, 3, 4, 5, 6, 7], chunk.as_slice());
+        assert!(chunk.is_full());
+    }
+
+    #[test]
+    fn fmt_write() {
+        use core::fmt::Write;
+        let mut chunk = Chunk::<u8, 16>::new();
+        write!(chunk, "{}-{}", 123, 456).unwrap();
+        assert_eq!(b"123-456", chunk.as_slice());
+        assert!(chunk.write_str("0123456789").is_err());
+        assert_eq!(b"123-456", chunk.as_slice());
+    }
+
     #[test]
     #[should_panic(expected = "assertion failed: Self::CAPACITY >= 1")]
     fn unit_on_empty() {
@@ -0,0 +1,311 @@
+use core::ptr;
+
+use super::Rebalance;
+
+/// A chunk's storage and the indices of the items in it, borrowed from either
+/// a `Chunk` or a `ChunkView`, so the two can share the code which moves items
+/// around.
+///
+/// Nothing here checks its arguments. The callers do that, so their panics
+/// can name the right type.
+pub(crate) struct RawChunk<'a, A> {
+    base: *mut A,
+    capacity: usize,
+    left: &'a mut usize,
+    right: &'a mut usize,
+}
+
+impl<'a, A> RawChunk<'a, A> {
+    /// # Safety
+    ///
+    /// `base` must point to storage for `capacity` items, and the items from
+    /// `left` up to but not including `right` must be initialised.
+    #[inline]
+    pub(crate) unsafe fn new(
+        base: *mut A,
+        capacity: usize,
+        left: &'a mut usize,
+        right: &'a mut usize,
+    ) -> Self {
+        RawChunk {
+            base,
+            capacity,
+            left,
+            right,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        *self.right - *self.left
+    }
+
+    /// Copy a range within the storage.
+    #[inline]
+    unsafe fn copy(&mut self, from: usize, to: usize, count: usize) {
+        if count > 0 {
+            ptr::copy(self.base.add(from), self.base.add(to), count)
+        }
+    }
+
+    /// Move the contents of the chunk so that they start at `new_left`.
+    #[inline]
+    pub(crate) fn realign(&mut self, new_left: usize) {
+        let len = self.len();
+        debug_assert!(new_left + len <= self.capacity);
+        if new_left != *self.left {
+            unsafe { self.copy(*self.left, new_left, len) };
+        }
+        *self.left = new_left;
+        *self.right = new_left + len;
+    }
+
+    /// Make room for `count` items at the front, using the policy `P` to
+    /// decide where to move the contents if there isn't enough already.
+    ///
+    /// An empty chunk is always moved, so the policy decides where the first
+    /// items go.
+    fn make_room_front<P: Rebalance>(&mut self, count: usize) {
+        if *self.left == *self.right || *self.left < count {
+            let free = self.capacity - self.len();
+            self.realign(P::reserve_for_push(free).clamp(count, free));
+        }
+    }
+
+    /// Make room for `count` items at the back, using the policy `P` to
+    /// decide where to move the contents if there isn't enough already.
+    ///
+    /// An empty chunk is always moved, so the policy decides where the first
+    /// items go.
+    fn make_room_back<P: Rebalance>(&mut self, count: usize) {
+        if *self.left == *self.right || *self.right + count > self.capacity {
+            let free = self.capacity - self.len();
+            self.realign(free - P::reserve_for_push(free).clamp(count, free));
+        }
+    }
+
+    /// Push an item to the front, using the policy `P` to decide where to
+    /// move the contents if there's no room at the front.
+    ///
+    /// # Safety
+    ///
+    /// The chunk mustn't be full.
+    pub(crate) unsafe fn push_front_with<P: Rebalance>(&mut self, value: A) {
+        self.make_room_front::<P>(1);
+        *self.left -= 1;
+        self.base.add(*self.left).write(value)
+    }
+
+    /// Push an item to the back, using the policy `P` to decide where to move
+    /// the contents if there's no room at the back.
+    ///
+    /// # Safety
+    ///
+    /// The chunk mustn't be full.
+    pub(crate) unsafe fn push_back_with<P: Rebalance>(&mut self, value: A) {
+        self.make_room_back::<P>(1);
+        self.base.add(*self.right).write(value);
+        *self.right += 1;
+    }
+
+    /// Move `count` items from the front of `other` onto the back of `self`,
+    /// shifting the contents of `self` to the front if there isn't room
+    /// behind them.
+    ///
+    /// # Safety
+    ///
+    /// `self` must have room for `count` more items, and `other` must have at
+    /// least `count` items.
+    pub(crate) unsafe fn take_front_from(&mut self, other: &mut RawChunk<'_, A>, count: usize) {
+        if *self.right + count > self.capacity {
+            self.realign(0);
+        }
+        if count > 0 {
+            ptr::copy_nonoverlapping(
+                other.base.add(*other.left),
+                self.base.add(*self.right),
+                count,
+            );
+        }
+        *self.right += count;
+        *other.left += count;
+    }
+
+    /// Move `count` items from the back of `other` onto the front of `self`,
+    /// shifting the contents of `self` to the back if there isn't room in
+    /// front of them.
+    ///
+    /// # Safety
+    ///
+    /// `self` must have room for `count` more items, and `other` must have at
+    /// least `count` items.
+    pub(crate) unsafe fn take_back_from(&mut self, other: &mut RawChunk<'_, A>, count: usize) {
+        if *self.left < count {
+            self.realign(self.capacity - self.len());
+        }
+        if count > 0 {
+            ptr::copy_nonoverlapping(
+                other.base.add(*other.right - count),
+                self.base.add(*self.left - count),
+                count,
+            );
+        }
+        *self.left -= count;
+        *other.right -= count;
+    }
+
+    /// Insert a value at index `index`, shifting whichever side of it is
+    /// shorter and has room.
+    ///
+    /// Inserting at either end is a push, so it uses the policy `P` to make
+    /// room there.
+    ///
+    /// # Safety
+    ///
+    /// The chunk mustn't be full, and `index` must be no greater than its
+    /// length.
+    pub(crate) unsafe fn insert<P: Rebalance>(&mut self, index: usize, value: A) {
+        if index == 0 {
+            return self.push_front_with::<P>(value);
+        }
+        if index == self.len() {
+            return self.push_back_with::<P>(value);
+        }
+        let real_index = index + *self.left;
+        let left_size = index;
+        let right_size = *self.right - real_index;
+        if *self.right == self.capacity || (*self.left > 0 && left_size < right_size) {
+            self.copy(*self.left, *self.left - 1, left_size);
+            self.base.add(real_index - 1).write(value);
+            *self.left -= 1;
+        } else {
+            self.copy(real_index, real_index + 1, right_size);
+            self.base.add(real_index).write(value);
+            *self.right += 1;
+        }
+    }
+
+    /// Insert the values from `iter` at index `index`.
+    ///
+    /// Inserting at either end is a push, so it uses the policy `P` to make
+    /// room there.
+    ///
+    /// # Safety
+    ///
+    /// The chunk must have room for every value in `iter`, and `index` must
+    /// be no greater than its length.
+    ///
+    /// # Panics
+    ///
+    /// If the iterator panics, or yields fewer values than it advertised, the
+    /// values after the range are moved back over the part of it that wasn't
+    /// written, so the chunk is left holding its original values and whatever
+    /// the iterator managed to yield.
+    pub(crate) unsafe fn insert_from<P, I>(&mut self, index: usize, iter: I)
+    where
+        P: Rebalance,
+        I: ExactSizeIterator<Item = A>,
+    {
+        let insert_size = iter.len();
+        if insert_size > 0 {
+            if index == 0 {
+                self.make_room_front::<P>(insert_size);
+            } else if index == self.len() {
+                self.make_room_back::<P>(insert_size);
+            }
+        }
+        let real_index = index + *self.left;
+        let left_size = index;
+        let right_size = *self.right - real_index;
+        let write_index = if *self.right == self.capacity
+            || (*self.left >= insert_size && left_size < right_size)
+        {
+            self.copy(*self.left, *self.left - insert_size, left_size);
+            *self.left -= insert_size;
+            real_index - insert_size
+        } else if *self.left == 0 || (*self.right + insert_size <= self.capacity) {
+            self.copy(real_index, real_index + insert_size, right_size);
+            *self.right += insert_size;
+            real_index
+        } else {
+            self.copy(*self.left, 0, left_size);
+            self.copy(real_index, left_size + insert_size, right_size);
+            *self.right -= *self.left;
+            *self.right += insert_size;
+            *self.left = 0;
+            left_size
+        };
+        self.write_from_iter(write_index, iter);
+    }
+
+    /// Write values from iterator into range starting at write_index.
+    ///
+    /// Will overwrite values at the relevant range without dropping even in case the values were
+    /// already initialized (it is expected they are empty). The left and right indices must
+    /// already cover the range being written.
+    ///
+    /// # Safety
+    ///
+    /// Range checks must already have been performed.
+    #[inline]
+    unsafe fn write_from_iter<I>(&mut self, write_index: usize, iter: I)
+    where
+        I: ExactSizeIterator<Item = A>,
+    {
+        // Panic safety: until the hole between `next` and `end` is filled, the chunk would drop
+        // uninitialised values, so if we unwind out of here we shift the right hand side of the
+        // chunk back over it.
+        struct Hole<'b, 'a, A> {
+            chunk: &'b mut RawChunk<'a, A>,
+            next: usize,
+            end: usize,
+        }
+
+        impl<'b, 'a, A> Drop for Hole<'b, 'a, A> {
+            fn drop(&mut self) {
+                let unfilled = self.end - self.next;
+                if unfilled > 0 {
+                    let count = *self.chunk.right - self.end;
+                    unsafe { self.chunk.copy(self.end, self.next, count) };
+                    *self.chunk.right -= unfilled;
+                }
+            }
+        }
+
+        let len = iter.len();
+        let mut hole = Hole {
+            chunk: self,
+            next: write_index,
+            end: write_index + len,
+        };
+        for value in iter.take(len) {
+            hole.chunk.base.add(hole.next).write(value);
+            hole.next += 1;
+        }
+        assert_eq!(
+            hole.end, hole.next,
+            "ExactSizeIterator yielded fewer values than advertised",
+        );
+    }
+
+    /// Remove the value at index `index`, shifting whichever side of it is
+    /// shorter.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than the chunk's length.
+    pub(crate) unsafe fn remove(&mut self, index: usize) -> A {
+        let real_index = index + *self.left;
+        let value = self.base.add(real_index).read();
+        let left_size = index;
+        let right_size = *self.right - real_index - 1;
+        if left_size < right_size {
+            self.copy(*self.left, *self.left + 1, left_size);
+            *self.left += 1;
+        } else {
+            self.copy(real_index + 1, real_index, right_size);
+            *self.right -= 1;
+        }
+        value
+    }
+}
@@ -0,0 +1,83 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// A policy for where a [`Chunk`][Chunk] moves its contents when a push runs
+/// into the edge of its storage while there's still free space on the other
+/// side.
+///
+/// The policy is the `P` type parameter of a
+/// [`RebalancingChunk`][RebalancingChunk], which the plain
+/// [`push_front`][push_front], [`push_back`][push_back], `insert` and
+/// `extend` methods use whenever they need to make room. [`Chunk`][Chunk]
+/// uses [`ShiftToEdge`][ShiftToEdge]. You can also pick a policy for a single
+/// push with [`push_front_with`][push_front_with] and
+/// [`push_back_with`][push_back_with].
+///
+/// [Chunk]: type.Chunk.html
+/// [RebalancingChunk]: struct.RebalancingChunk.html
+/// [push_front_with]: struct.RebalancingChunk.html#method.push_front_with
+/// [push_back_with]: struct.RebalancingChunk.html#method.push_back_with
+/// [push_front]: struct.RebalancingChunk.html#method.push_front
+/// [push_back]: struct.RebalancingChunk.html#method.push_back
+/// [ShiftToEdge]: struct.ShiftToEdge.html
+pub trait Rebalance {
+    /// Given the number of `free` slots in the chunk, decide how many of them
+    /// should end up on the side that's being pushed to. The rest go to the
+    /// opposite side.
+    ///
+    /// The result is clamped so there's always room on that side for the
+    /// values being pushed or inserted.
+    fn reserve_for_push(free: usize) -> usize;
+}
+
+/// Move the contents all the way over to the opposite edge, leaving all the
+/// free space on the side being pushed to.
+///
+/// This gives amortised constant time pushes as long as you keep pushing to
+/// the same side, which suits stack-like usage. It's the policy used by
+/// [`Chunk`][Chunk].
+///
+/// [Chunk]: type.Chunk.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ShiftToEdge;
+
+impl Rebalance for ShiftToEdge {
+    #[inline]
+    fn reserve_for_push(free: usize) -> usize {
+        free
+    }
+}
+
+/// Move the contents into the middle of the chunk, splitting the free space
+/// evenly between the two sides.
+///
+/// This gives amortised constant time pushes when you push to both sides,
+/// which suits deque-like usage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Centre;
+
+impl Rebalance for Centre {
+    #[inline]
+    fn reserve_for_push(free: usize) -> usize {
+        free - free / 2
+    }
+}
+
+/// Leave `PERCENT` percent of the free space on the side being pushed to, and
+/// the rest on the opposite side.
+///
+/// `Proportional<100>` behaves like [`ShiftToEdge`][ShiftToEdge] and
+/// `Proportional<50>` like [`Centre`][Centre].
+///
+/// [ShiftToEdge]: struct.ShiftToEdge.html
+/// [Centre]: struct.Centre.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Proportional<const PERCENT: usize>;
+
+impl<const PERCENT: usize> Rebalance for Proportional<PERCENT> {
+    #[inline]
+    fn reserve_for_push(free: usize) -> usize {
+        (free * PERCENT + 99) / 100
+    }
+}
@@ -1,10 +1,11 @@
-use core::mem::MaybeUninit;
+use core::mem::{self, MaybeUninit};
 
-use ::refpool::{PoolClone, PoolDefault};
+use ::refpool::{Pool, PoolBox, PoolClone, PoolDefault};
 
-use crate::Chunk;
+use crate::sized_chunk::RebalancingChunk;
+use crate::util::DropOnUnwind;
 
-impl<A, const N: usize> PoolDefault for Chunk<A, N> {
+impl<A, P, const N: usize> PoolDefault for RebalancingChunk<A, P, N> {
     unsafe fn default_uninit(target: &mut MaybeUninit<Self>) {
         let ptr = target.as_mut_ptr();
         let left_ptr: *mut usize = &mut (*ptr).left;
@@ -14,7 +15,7 @@ impl<A, const N: usize> PoolDefault for Chunk<A, N> {
     }
 }
 
-impl<A, const N: usize> PoolClone for Chunk<A, N>
+impl<A, P, const N: usize> PoolClone for RebalancingChunk<A, P, N>
 where
     A: Clone,
 {
@@ -25,18 +26,51 @@ where
         let data_ptr: *mut _ = &mut (*ptr).data;
         let data_ptr: *mut A = (*data_ptr).as_mut_ptr().cast();
         left_ptr.write(self.left);
-        right_ptr.write(self.right);
+        right_ptr.write(self.left);
+        // Panic safety: `target` holds a valid chunk of the items cloned so
+        // far at every step, which the guard drops if a clone panics.
+        let guard = DropOnUnwind(ptr);
         for index in self.left..self.right {
             data_ptr.add(index).write((*self.ptr(index)).clone());
+            *right_ptr += 1;
         }
+        mem::forget(guard);
+    }
+}
+
+impl<A, P, const N: usize> RebalancingChunk<A, P, N> {
+    /// Construct an empty chunk in a `PoolBox` allocated from `pool`.
+    ///
+    /// The chunk is initialised in place, so unlike `Chunk::new()` it's
+    /// never built on the stack first, which makes this safe to use for
+    /// chunks too large to fit on the stack.
+    pub fn new_pooled(pool: &Pool<Self>) -> PoolBox<Self> {
+        PoolBox::default(pool)
+    }
+
+    /// Clone the chunk into a `PoolBox` allocated from `pool`.
+    ///
+    /// The clone is written in place, so like
+    /// [`Chunk::new_pooled`][new_pooled], this works for chunks too large to
+    /// fit on the stack. Cloning the resulting `PoolBox` does the same.
+    ///
+    /// [new_pooled]: #method.new_pooled
+    pub fn clone_pooled(&self, pool: &Pool<Self>) -> PoolBox<Self>
+    where
+        A: Clone,
+    {
+        PoolBox::clone_from(pool, self)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::*;
+    use crate::tests::FragileClone;
+    use crate::Chunk;
     use ::refpool::{Pool, PoolRef};
     use std::iter::FromIterator;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn default_and_clone() {
@@ -58,4 +92,51 @@ mod test {
         assert_eq!(ref2, ref3);
         assert!(!PoolRef::ptr_eq(&ref1, &ref2));
     }
+
+    #[test]
+    fn large_chunk_without_stack_copy() {
+        type Large = Chunk<u8, 262_144>;
+        // The thread's stack is a quarter the size of a chunk, so this checks
+        // that `new_pooled`, `clone_pooled` and cloning a `PoolBox` write the
+        // chunk in place. The pool is filled first because an empty pool
+        // allocates with `Box::new(MaybeUninit::uninit())`, which puts the
+        // uninitialised chunk on the stack in debug builds, while
+        // `Pool::fill` allocates directly.
+        std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                let pool: Pool<Large> = Pool::new(4).filled();
+                let mut chunk = Chunk::new_pooled(&pool);
+                chunk.push_back(1);
+                chunk.push_back(2);
+                let mut copy = chunk.clone_pooled(&pool);
+                copy.push_front(0);
+                let copy2 = copy.clone();
+                assert_eq!(&[1, 2], chunk.as_slice());
+                assert_eq!(&[0, 1, 2], copy.as_slice());
+                assert_eq!(copy, copy2);
+                assert_eq!(1, pool.get_pool_size());
+                drop((chunk, copy, copy2));
+                assert_eq!(4, pool.get_pool_size());
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn clone_uninit_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut chunk: Chunk<_, 8> = Chunk::new();
+        chunk.push_back(FragileClone::new(&counter, false));
+        chunk.push_back(FragileClone::new(&counter, false));
+        chunk.push_back(FragileClone::new(&counter, true));
+        chunk.pop_front();
+        let pool = Pool::new(4);
+        let result = catch_unwind(AssertUnwindSafe(|| PoolRef::clone_from(&pool, &chunk)));
+        assert!(result.is_err());
+        assert_eq!(2, counter.load(Ordering::Relaxed));
+        drop(chunk);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
 }
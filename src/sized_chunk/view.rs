@@ -0,0 +1,783 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use core::borrow::{Borrow, BorrowMut};
+use core::cmp::Ordering;
+use core::fmt::{Debug, Error, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+use core::slice::{from_raw_parts, from_raw_parts_mut, Iter as SliceIter, IterMut as SliceIterMut};
+
+use super::raw::RawChunk;
+use super::{Rebalance, ShiftToEdge};
+
+/// A chunk over a borrowed buffer.
+///
+/// This works like a [`Chunk`][Chunk], with the same double ended, shifting
+/// layout, except that instead of an inline array it keeps its items in a
+/// slice of `MaybeUninit<A>` you lend it, like an arena slice, a page you've
+/// mapped, or some other region of memory the chunk can't own. The capacity
+/// is the length of the slice.
+///
+/// The view owns the items it holds, and drops them when it's dropped. Use
+/// [`into_raw_parts`][into_raw_parts] to hand them back to the buffer
+/// instead, and [`from_raw_parts`][from_raw_parts] to pick them up again.
+///
+/// It has all of `Chunk`'s operations, and moves items around with the same
+/// code. The one difference is [`split_off`][split_off], which needs a second
+/// buffer to put the split off items in.
+///
+/// # Examples
+///
+/// ```rust
+/// # use core::mem::MaybeUninit;
+/// # use sized_chunks::ChunkView;
+/// let mut buffer: Vec<MaybeUninit<i32>> = (0..8).map(|_| MaybeUninit::uninit()).collect();
+/// let mut chunk = ChunkView::new(&mut buffer);
+/// chunk.push_back(2);
+/// chunk.push_front(1);
+/// chunk.insert(1, 3);
+/// assert_eq!(&[1, 3, 2], chunk.as_slice());
+/// assert_eq!(8, chunk.capacity());
+/// ```
+///
+/// [Chunk]: type.Chunk.html
+/// [into_raw_parts]: #method.into_raw_parts
+/// [from_raw_parts]: #method.from_raw_parts
+/// [split_off]: #method.split_off
+pub struct ChunkView<'a, A> {
+    left: usize,
+    right: usize,
+    data: &'a mut [MaybeUninit<A>],
+}
+
+impl<'a, A> Drop for ChunkView<'a, A> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+impl<'a, A> ChunkView<'a, A> {
+    /// Construct a new empty chunk over `buffer`.
+    ///
+    /// The capacity of the chunk is the length of the buffer.
+    pub fn new(buffer: &'a mut [MaybeUninit<A>]) -> Self {
+        ChunkView {
+            left: 0,
+            right: 0,
+            data: buffer,
+        }
+    }
+
+    /// Construct a chunk over `buffer` holding the items from index `left`
+    /// up to but not including index `right`.
+    ///
+    /// # Safety
+    ///
+    /// `left..right` must be in bounds for the buffer, and the items in it
+    /// must be initialised. The chunk takes ownership of them, and will drop
+    /// them unless you take them back with
+    /// [`into_raw_parts`][into_raw_parts].
+    ///
+    /// [into_raw_parts]: #method.into_raw_parts
+    pub unsafe fn from_raw_parts(
+        buffer: &'a mut [MaybeUninit<A>],
+        left: usize,
+        right: usize,
+    ) -> Self {
+        let chunk = ChunkView {
+            left,
+            right,
+            data: buffer,
+        };
+        debug_invariants!(chunk);
+        chunk
+    }
+
+    /// Give the buffer back without dropping the chunk's items, along with
+    /// the `left` and `right` indices of the items in it.
+    ///
+    /// The items stay initialised in the buffer, and it's up to you to drop
+    /// them, or pass them to [`from_raw_parts`][from_raw_parts] to get the
+    /// chunk back.
+    ///
+    /// [from_raw_parts]: #method.from_raw_parts
+    pub fn into_raw_parts(self) -> (&'a mut [MaybeUninit<A>], usize, usize) {
+        let this = ManuallyDrop::new(self);
+        let data = unsafe { ptr::read(&this.data) };
+        (data, this.left, this.right)
+    }
+
+    /// Get the capacity of the chunk, which is the length of its buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the length of the chunk.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.right - self.left
+    }
+
+    /// Test if the chunk is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.left == self.right
+    }
+
+    /// Test if the chunk is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Check the chunk's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of chunks.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the chunk.
+    ///
+    /// Time: O(1)
+    pub fn check_invariants(&self) {
+        assert!(
+            self.left <= self.right,
+            "ChunkView::check_invariants: left index {} is past right index {}",
+            self.left,
+            self.right
+        );
+        assert!(
+            self.right <= self.capacity(),
+            "ChunkView::check_invariants: right index {} is past capacity {}",
+            self.right,
+            self.capacity()
+        );
+    }
+
+    #[inline]
+    fn base(&mut self) -> *mut A {
+        self.data.as_mut_ptr().cast()
+    }
+
+    #[inline]
+    fn raw(&mut self) -> RawChunk<'_, A> {
+        let capacity = self.data.len();
+        let base = self.data.as_mut_ptr().cast();
+        unsafe { RawChunk::new(base, capacity, &mut self.left, &mut self.right) }
+    }
+
+    /// Get the number of items which can be pushed to the front of the chunk
+    /// without shifting its contents.
+    #[inline]
+    pub fn front_capacity(&self) -> usize {
+        self.left
+    }
+
+    /// Get the number of items which can be pushed to the back of the chunk
+    /// without shifting its contents.
+    #[inline]
+    pub fn back_capacity(&self) -> usize {
+        self.capacity() - self.right
+    }
+
+    /// Push an item to the front of the chunk.
+    ///
+    /// Panics if the capacity of the chunk is exceeded.
+    ///
+    /// If there's no room at the front, the contents are shifted all the way
+    /// to the back, as with the [`ShiftToEdge`][ShiftToEdge] policy.
+    ///
+    /// Time: O(1) if there's room at the front, O(n) otherwise
+    ///
+    /// [ShiftToEdge]: struct.ShiftToEdge.html
+    pub fn push_front(&mut self, value: A) {
+        self.push_front_with::<ShiftToEdge>(value)
+    }
+
+    /// Push an item to the back of the chunk.
+    ///
+    /// Panics if the capacity of the chunk is exceeded.
+    ///
+    /// If there's no room at the back, the contents are shifted all the way
+    /// to the front, as with the [`ShiftToEdge`][ShiftToEdge] policy.
+    ///
+    /// Time: O(1) if there's room at the back, O(n) otherwise
+    ///
+    /// [ShiftToEdge]: struct.ShiftToEdge.html
+    pub fn push_back(&mut self, value: A) {
+        self.push_back_with::<ShiftToEdge>(value)
+    }
+
+    /// Push an item to the front of the chunk, using the
+    /// [`Rebalance`][Rebalance] policy `P` to decide where to move the
+    /// contents if there's no room at the front.
+    ///
+    /// Panics if the capacity of the chunk is exceeded.
+    ///
+    /// Time: O(1) if there's room at the front, O(n) otherwise
+    ///
+    /// [Rebalance]: trait.Rebalance.html
+    pub fn push_front_with<P: Rebalance>(&mut self, value: A) {
+        if self.is_full() {
+            panic!("ChunkView::push_front: can't push to full chunk");
+        }
+        unsafe { self.raw().push_front_with::<P>(value) };
+        debug_invariants!(self);
+    }
+
+    /// Push an item to the back of the chunk, using the
+    /// [`Rebalance`][Rebalance] policy `P` to decide where to move the
+    /// contents if there's no room at the back.
+    ///
+    /// Panics if the capacity of the chunk is exceeded.
+    ///
+    /// Time: O(1) if there's room at the back, O(n) otherwise
+    ///
+    /// [Rebalance]: trait.Rebalance.html
+    pub fn push_back_with<P: Rebalance>(&mut self, value: A) {
+        if self.is_full() {
+            panic!("ChunkView::push_back: can't push to full chunk");
+        }
+        unsafe { self.raw().push_back_with::<P>(value) };
+        debug_invariants!(self);
+    }
+
+    /// Pop an item off the front of the chunk.
+    ///
+    /// Panics if the chunk is empty.
+    ///
+    /// Time: O(1)
+    pub fn pop_front(&mut self) -> A {
+        if self.is_empty() {
+            panic!("ChunkView::pop_front: can't pop from empty chunk");
+        }
+        let value = unsafe { self.base().add(self.left).read() };
+        self.left += 1;
+        debug_invariants!(self);
+        value
+    }
+
+    /// Pop an item off the back of the chunk.
+    ///
+    /// Panics if the chunk is empty.
+    ///
+    /// Time: O(1)
+    pub fn pop_back(&mut self) -> A {
+        if self.is_empty() {
+            panic!("ChunkView::pop_back: can't pop from empty chunk");
+        }
+        self.right -= 1;
+        let value = unsafe { self.base().add(self.right).read() };
+        debug_invariants!(self);
+        value
+    }
+
+    /// Discard all items up to but not including `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(n) for the number of items dropped
+    pub fn drop_left(&mut self, index: usize) {
+        if index > self.len() {
+            panic!("ChunkView::drop_left: index out of bounds");
+        }
+        if index > 0 {
+            let start = self.left;
+            // Move the index first, so a panicking destructor leaks the rest
+            // of the dropped items rather than dropping them twice.
+            self.left += index;
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.base().add(start), index))
+            }
+        }
+        debug_invariants!(self);
+    }
+
+    /// Discard all items from `index` onward.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(n) for the number of items dropped
+    pub fn drop_right(&mut self, index: usize) {
+        if index > self.len() {
+            panic!("ChunkView::drop_right: index out of bounds");
+        }
+        let start = self.left + index;
+        let count = self.right - start;
+        self.right = start;
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.base().add(start), count)) }
+        debug_invariants!(self);
+    }
+
+    /// Split a chunk into two, the original chunk containing everything up
+    /// to `index` and the returned chunk, over `buffer`, containing
+    /// everything from `index` onwards.
+    ///
+    /// Panics if `index` is out of bounds, or if `buffer` is too short to
+    /// hold the items being moved into it.
+    ///
+    /// Time: O(n) for the number of items in the new chunk
+    pub fn split_off<'b>(
+        &mut self,
+        index: usize,
+        buffer: &'b mut [MaybeUninit<A>],
+    ) -> ChunkView<'b, A> {
+        if index > self.len() {
+            panic!("ChunkView::split_off: index out of bounds");
+        }
+        let count = self.len() - index;
+        if count > buffer.len() {
+            panic!("ChunkView::split_off: buffer too short");
+        }
+        let mut right_chunk = ChunkView::new(buffer);
+        unsafe { right_chunk.raw().take_back_from(&mut self.raw(), count) };
+        debug_invariants!(self, right_chunk);
+        right_chunk
+    }
+
+    /// Remove all items from `other` and append them to the back of `self`.
+    ///
+    /// Panics if the capacity of the chunk is exceeded.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn append(&mut self, other: &mut ChunkView<'_, A>) {
+        let other_len = other.len();
+        if self.len() + other_len > self.capacity() {
+            panic!("ChunkView::append: chunk size overflow");
+        }
+        unsafe { self.raw().take_front_from(&mut other.raw(), other_len) };
+        other.left = 0;
+        other.right = 0;
+        debug_invariants!(self, other);
+    }
+
+    /// Remove `count` items from the front of `other` and append them to the
+    /// back of `self`.
+    ///
+    /// Panics if `self` doesn't have `count` items left, or if `other` has
+    /// fewer than `count` items.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn drain_from_front(&mut self, other: &mut ChunkView<'_, A>, count: usize) {
+        assert!(self.len() + count <= self.capacity());
+        assert!(other.len() >= count);
+        unsafe { self.raw().take_front_from(&mut other.raw(), count) };
+        debug_invariants!(self, other);
+    }
+
+    /// Remove `count` items from the back of `other` and append them to the
+    /// front of `self`.
+    ///
+    /// Panics if `self` doesn't have `count` items left, or if `other` has
+    /// fewer than `count` items.
+    ///
+    /// Time: O(n) for the number of items moved
+    pub fn drain_from_back(&mut self, other: &mut ChunkView<'_, A>, count: usize) {
+        assert!(self.len() + count <= self.capacity());
+        assert!(other.len() >= count);
+        unsafe { self.raw().take_back_from(&mut other.raw(), count) };
+        debug_invariants!(self, other);
+    }
+
+    /// Update the value at index `index`, returning the old value.
+    ///
+    /// Panics if `index` is out of bounds.
+    ///
+    /// Time: O(1)
+    pub fn set(&mut self, index: usize, value: A) -> A {
+        mem::replace(&mut self[index], value)
+    }
+
+    /// Insert a new value at index `index`, shifting all the following values
+    /// to the right.
+    ///
+    /// Panics if the index is out of bounds or the chunk is full.
+    ///
+    /// Time: O(n) for the number of elements shifted
+    pub fn insert(&mut self, index: usize, value: A) {
+        if self.is_full() {
+            panic!("ChunkView::insert: chunk is full");
+        }
+        if index > self.len() {
+            panic!("ChunkView::insert: index out of bounds");
+        }
+        unsafe { self.raw().insert::<ShiftToEdge>(index, value) };
+        debug_invariants!(self);
+    }
+
+    /// Insert a new value into the chunk in sorted order.
+    ///
+    /// This assumes every element of the chunk is already in sorted order.
+    /// If not, the value will still be inserted but the ordering is not
+    /// guaranteed.
+    ///
+    /// Time: O(log n) to find the insert position, then O(n) for the number
+    /// of elements shifted.
+    pub fn insert_ordered(&mut self, value: A)
+    where
+        A: Ord,
+    {
+        if self.is_full() {
+            panic!("ChunkView::insert: chunk is full");
+        }
+        match self.binary_search(&value) {
+            Ok(index) => self.insert(index, value),
+            Err(index) => self.insert(index, value),
+        }
+    }
+
+    /// Insert multiple values at index `index`, shifting all the following
+    /// values to the right.
+    ///
+    /// Panics if the index is out of bounds or the chunk doesn't have room
+    /// for all the values.
+    ///
+    /// Time: O(m+n) where m is the number of elements inserted and n is the
+    /// number of elements following the insertion index.
+    pub fn insert_from<Iterable, I>(&mut self, index: usize, iter: Iterable)
+    where
+        Iterable: IntoIterator<Item = A, IntoIter = I>,
+        I: ExactSizeIterator<Item = A>,
+    {
+        let iter = iter.into_iter();
+        let insert_size = iter.len();
+        if self.len() + insert_size > self.capacity() {
+            panic!(
+                "ChunkView::insert_from: chunk cannot fit {} elements",
+                insert_size
+            );
+        }
+        if index > self.len() {
+            panic!("ChunkView::insert_from: index out of bounds");
+        }
+        unsafe { self.raw().insert_from::<ShiftToEdge, _>(index, iter) };
+        debug_invariants!(self);
+    }
+
+    /// Remove the value at index `index`, shifting all the following values
+    /// to the left.
+    ///
+    /// Returns the removed value.
+    ///
+    /// Panics if the index is out of bounds.
+    ///
+    /// Time: O(n) for the number of items shifted
+    pub fn remove(&mut self, index: usize) -> A {
+        if index >= self.len() {
+            panic!("ChunkView::remove: index out of bounds");
+        }
+        let value = unsafe { self.raw().remove(index) };
+        debug_invariants!(self);
+        value
+    }
+
+    /// Construct an iterator that drains values from the front of the chunk.
+    pub fn drain(&mut self) -> ViewDrain<'_, 'a, A> {
+        ViewDrain { chunk: self }
+    }
+
+    /// Discard the contents of the chunk.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        self.drop_right(0);
+        self.left = 0;
+        self.right = 0;
+        debug_invariants!(self);
+    }
+
+    /// Get a reference to the contents of the chunk as a slice.
+    pub fn as_slice(&self) -> &[A] {
+        unsafe { from_raw_parts(self.data.as_ptr().cast::<A>().add(self.left), self.len()) }
+    }
+
+    /// Get a reference to the contents of the chunk as a mutable slice.
+    pub fn as_mut_slice(&mut self) -> &mut [A] {
+        let len = self.len();
+        unsafe { from_raw_parts_mut(self.base().add(self.left), len) }
+    }
+}
+
+/// A draining iterator over a [`ChunkView`][ChunkView].
+///
+/// As the iterator yields each element, it's removed from the chunk, and
+/// when it terminates, the chunk will be empty.
+///
+/// [ChunkView]: struct.ChunkView.html
+pub struct ViewDrain<'b, 'a, A> {
+    chunk: &'b mut ChunkView<'a, A>,
+}
+
+impl<'b, 'a, A> Iterator for ViewDrain<'b, 'a, A> {
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.chunk.is_empty() {
+            None
+        } else {
+            Some(self.chunk.pop_front())
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.chunk.len(), Some(self.chunk.len()))
+    }
+}
+
+impl<'b, 'a, A> DoubleEndedIterator for ViewDrain<'b, 'a, A> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.chunk.is_empty() {
+            None
+        } else {
+            Some(self.chunk.pop_back())
+        }
+    }
+}
+
+impl<'b, 'a, A> ExactSizeIterator for ViewDrain<'b, 'a, A> {}
+
+impl<'b, 'a, A> FusedIterator for ViewDrain<'b, 'a, A> {}
+
+impl<'a, A> Deref for ChunkView<'a, A> {
+    type Target = [A];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<'a, A> DerefMut for ChunkView<'a, A> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, A> Borrow<[A]> for ChunkView<'a, A> {
+    fn borrow(&self) -> &[A] {
+        self.as_slice()
+    }
+}
+
+impl<'a, A> BorrowMut<[A]> for ChunkView<'a, A> {
+    fn borrow_mut(&mut self) -> &mut [A] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, A> AsRef<[A]> for ChunkView<'a, A> {
+    fn as_ref(&self) -> &[A] {
+        self.as_slice()
+    }
+}
+
+impl<'a, A> AsMut<[A]> for ChunkView<'a, A> {
+    fn as_mut(&mut self) -> &mut [A] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, A> Debug for ChunkView<'a, A>
+where
+    A: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("ChunkView")?;
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, A> Hash for ChunkView<'a, A>
+where
+    A: Hash,
+{
+    fn hash<H>(&self, hasher: &mut H)
+    where
+        H: Hasher,
+    {
+        for item in self.iter() {
+            item.hash(hasher)
+        }
+    }
+}
+
+impl<'a, A, Slice> PartialEq<Slice> for ChunkView<'a, A>
+where
+    Slice: Borrow<[A]>,
+    A: PartialEq,
+{
+    fn eq(&self, other: &Slice) -> bool {
+        self.as_slice() == other.borrow()
+    }
+}
+
+impl<'a, A> Eq for ChunkView<'a, A> where A: Eq {}
+
+impl<'a, A, Slice> PartialOrd<Slice> for ChunkView<'a, A>
+where
+    Slice: Borrow<[A]>,
+    A: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Slice) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.borrow())
+    }
+}
+
+impl<'a, A> Ord for ChunkView<'a, A>
+where
+    A: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<'a, A> Extend<A> for ChunkView<'a, A> {
+    /// Append the contents of the iterator to the back of the chunk.
+    ///
+    /// Panics if the chunk exceeds its capacity.
+    ///
+    /// Time: O(n) for the length of the iterator
+    fn extend<I>(&mut self, it: I)
+    where
+        I: IntoIterator<Item = A>,
+    {
+        for item in it {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<'b, 'a, A> IntoIterator for &'b ChunkView<'a, A> {
+    type Item = &'b A;
+    type IntoIter = SliceIter<'b, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'b, 'a, A> IntoIterator for &'b mut ChunkView<'a, A> {
+    type Item = &'b mut A;
+    type IntoIter = SliceIterMut<'b, A>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::sized_chunk::Centre;
+    use crate::tests::{buffer, DropTest};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::vec::Vec;
+
+    #[test]
+    fn shift_to_edge() {
+        let mut buffer = buffer(4);
+        let mut chunk = ChunkView::new(&mut buffer);
+        chunk.push_front(1);
+        assert_eq!((3, 0), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.push_back(2);
+        assert_eq!((0, 2), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.push_front(0);
+        assert_eq!((1, 0), (chunk.front_capacity(), chunk.back_capacity()));
+        assert_eq!(&[0, 1, 2], chunk.as_slice());
+    }
+
+    #[test]
+    fn push_with_centre() {
+        let mut buffer = buffer(8);
+        let mut chunk = ChunkView::new(&mut buffer);
+        chunk.push_back_with::<Centre>(1);
+        assert_eq!((4, 3), (chunk.front_capacity(), chunk.back_capacity()));
+        chunk.push_front_with::<Centre>(0);
+        assert_eq!((3, 3), (chunk.front_capacity(), chunk.back_capacity()));
+        assert_eq!(&[0, 1], chunk.as_slice());
+    }
+
+    #[test]
+    fn split_off_append_drain_from() {
+        let mut left_buffer = buffer(8);
+        let mut right_buffer = buffer(4);
+        let mut left = ChunkView::new(&mut left_buffer);
+        left.extend(0..6);
+        let mut right = left.split_off(3, &mut right_buffer);
+        assert_eq!(&[0, 1, 2], left.as_slice());
+        assert_eq!(&[3, 4, 5], right.as_slice());
+        left.append(&mut right);
+        assert_eq!(&[0, 1, 2, 3, 4, 5], left.as_slice());
+        assert!(right.is_empty());
+        right.drain_from_front(&mut left, 2);
+        right.drain_from_back(&mut left, 2);
+        left.check_invariants();
+        right.check_invariants();
+        assert_eq!(&[2, 3], left.as_slice());
+        assert_eq!(&[4, 5, 0, 1], right.as_slice());
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkView::split_off: buffer too short")]
+    fn split_off_into_short_buffer() {
+        let mut left_buffer = buffer(8);
+        let mut right_buffer = buffer(2);
+        let mut left = ChunkView::new(&mut left_buffer);
+        left.extend(0..6);
+        left.split_off(3, &mut right_buffer);
+    }
+
+    #[test]
+    fn insert_from_set_drain() {
+        let mut buffer = buffer(8);
+        let mut chunk = ChunkView::new(&mut buffer);
+        chunk.extend(vec![1, 5]);
+        chunk.insert_from(1, vec![2, 3, 4]);
+        chunk.insert_ordered(0);
+        assert_eq!(&[0, 1, 2, 3, 4, 5], chunk.as_slice());
+        assert_eq!(0, chunk.set(0, 6));
+        assert_eq!(vec![6, 1, 2, 3, 4, 5], chunk.drain().collect::<Vec<_>>());
+        assert!(chunk.is_empty());
+    }
+
+    #[test]
+    fn raw_parts() {
+        let mut buffer = buffer(4);
+        let mut chunk = ChunkView::new(&mut buffer);
+        chunk.extend(vec![1, 2, 3]);
+        chunk.pop_front();
+        let (buffer, left, right) = chunk.into_raw_parts();
+        assert_eq!((1, 3), (left, right));
+        let chunk = unsafe { ChunkView::from_raw_parts(buffer, left, right) };
+        assert_eq!(&[2, 3], chunk.as_slice());
+    }
+
+    #[test]
+    #[should_panic(expected = "ChunkView::push_back: can't push to full chunk")]
+    fn push_to_full() {
+        let mut buffer = buffer(2);
+        let mut chunk = ChunkView::new(&mut buffer);
+        chunk.extend(0..3);
+    }
+
+    #[test]
+    fn dropping() {
+        let counter = AtomicUsize::new(0);
+        let mut buffer = buffer(16);
+        {
+            let mut chunk = ChunkView::new(&mut buffer);
+            for _ in 0..12 {
+                chunk.push_back(DropTest::new(&counter));
+            }
+            chunk.drop_left(2);
+            chunk.drop_right(8);
+            assert_eq!(8, counter.load(Ordering::Relaxed));
+        }
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+}
@@ -69,7 +69,7 @@ where
     pub(crate) chunk: SparseChunk<A, N>,
 }
 
-impl<'a, A, const N: usize> Iterator for Drain<A, N>
+impl<A, const N: usize> Iterator for Drain<A, N>
 where
     BitsImpl<N>: Bits,
 {
@@ -176,7 +176,7 @@ where
     pub(crate) chunk: SparseChunk<A, N>,
 }
 
-impl<'a, A, const N: usize> Iterator for OptionDrain<A, N>
+impl<A, const N: usize> Iterator for OptionDrain<A, N>
 where
     BitsImpl<N>: Bits,
 {
@@ -201,6 +201,7 @@ where
 }
 
 #[cfg(test)]
+#[allow(clippy::option_filter_map)]
 mod test {
     use super::*;
     use std::iter::FromIterator;
@@ -6,6 +6,7 @@
 //!
 //! See [`SparseChunk`](struct.SparseChunk.html)
 
+use core::convert::Infallible;
 use core::fmt::{Debug, Error, Formatter};
 use core::iter::FromIterator;
 use core::mem::{self, MaybeUninit};
@@ -14,8 +15,12 @@ use core::ops::IndexMut;
 use core::ptr;
 use core::slice::{from_raw_parts, from_raw_parts_mut};
 
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "alloc")]
+use core::convert::TryFrom;
 #[cfg(feature = "std")]
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
 
 use bitmaps::{Bitmap, Bits, BitsImpl, Iter as BitmapIter};
 
@@ -26,6 +31,9 @@ pub use self::iter::{Drain, Iter, IterMut, OptionDrain, OptionIter, OptionIterMu
 #[cfg(feature = "refpool")]
 mod refpool;
 
+mod view;
+pub use self::view::{SparseChunkView, ViewDrain};
+
 /// A fixed capacity sparse array.
 ///
 /// An inline sparse array of up to `N` items of type `A`. You can think of it as an array
@@ -103,13 +111,13 @@ where
     /// Copy the value at an index, discarding ownership of the copied value
     #[inline]
     unsafe fn force_read(index: usize, chunk: &Self) -> A {
-        ptr::read(&chunk.values()[index as usize])
+        ptr::read(&chunk.values()[index])
     }
 
     /// Write a value at an index without trying to drop what's already there
     #[inline]
     unsafe fn force_write(index: usize, value: A, chunk: &mut Self) {
-        ptr::write(&mut chunk.values_mut()[index as usize], value)
+        ptr::write(&mut chunk.values_mut()[index], value)
     }
 
     /// Construct a new empty chunk.
@@ -135,6 +143,36 @@ where
         chunk
     }
 
+    /// Construct a chunk by calling `f` with each index from `0` to `N - 1`
+    /// in turn, storing the values it returns `Some` for and leaving the
+    /// rest of the indices empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::SparseChunk;
+    /// let evens = SparseChunk::<usize, 8>::from_fn(|i| if i % 2 == 0 { Some(i) } else { None });
+    /// assert_eq!(4, evens.len());
+    /// assert_eq!(Some(&6), evens.get(6));
+    /// assert_eq!(None, evens.get(7));
+    /// ```
+    ///
+    /// Time: O(n)
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(usize) -> Option<A>,
+    {
+        let mut chunk = Self::new();
+        for index in 0..N {
+            if let Some(value) = f(index) {
+                unsafe { SparseChunk::force_write(index, value, &mut chunk) };
+                chunk.map.set(index, true);
+            }
+        }
+        debug_invariants!(chunk);
+        chunk
+    }
+
     /// Get the length of the chunk.
     #[inline]
     pub fn len(&self) -> usize {
@@ -153,6 +191,32 @@ where
         self.len() == N
     }
 
+    /// Check the chunk's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// The chunk's bitmap is the only record of which indices hold values, so
+    /// this can only check that it doesn't mark anything out of bounds. A
+    /// bitmap which disagrees with the values actually stored shows up as
+    /// values being leaked or dropped twice, which a drop counting element
+    /// type will catch.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of chunks.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the chunk, so corruption is caught where it
+    /// happens rather than wherever it's first noticed.
+    ///
+    /// Time: O(1)
+    pub fn check_invariants(&self) {
+        if let Some(index) = self.map.last_index() {
+            assert!(
+                index < N,
+                "SparseChunk::check_invariants: index {} is past capacity {}",
+                index,
+                N
+            );
+        }
+    }
+
     /// Insert a new value at a given index.
     ///
     /// Returns the previous value at that index, if any.
@@ -160,12 +224,14 @@ where
         if index >= N {
             panic!("SparseChunk::insert: index out of bounds");
         }
-        if self.map.set(index, true) {
+        let result = if self.map.set(index, true) {
             Some(mem::replace(&mut self.values_mut()[index], value))
         } else {
             unsafe { SparseChunk::force_write(index, value, self) };
             None
-        }
+        };
+        debug_invariants!(self);
+        result
     }
 
     /// Remove the value at a given index.
@@ -175,11 +241,86 @@ where
         if index >= N {
             panic!("SparseChunk::remove: index out of bounds");
         }
-        if self.map.set(index, false) {
+        let result = if self.map.set(index, false) {
             Some(unsafe { SparseChunk::force_read(index, self) })
         } else {
             None
+        };
+        debug_invariants!(self);
+        result
+    }
+
+    /// Move every value out of `other`, a chunk of any capacity, into the
+    /// same index in `self`, dropping any value `self` already had there.
+    ///
+    /// Panics if `other` has a value at an index that's out of bounds for
+    /// `self`, in which case neither chunk is changed.
+    ///
+    /// Time: O(n) for the number of values moved
+    pub fn append_from<const M: usize>(&mut self, other: &mut SparseChunk<A, M>)
+    where
+        BitsImpl<M>: Bits,
+    {
+        if other.map.last_index().map_or(false, |index| index >= N) {
+            panic!("SparseChunk::append_from: index out of bounds");
         }
+        let incoming = resize_bitmap::<M, N>(&other.map);
+        let overlap = self.map & incoming;
+        // Clear the overlapping bits before dropping anything, so a panicking
+        // destructor leaks the rest of the old values rather than dropping
+        // them twice.
+        self.map &= !overlap;
+        for index in &overlap {
+            unsafe { ptr::drop_in_place(&mut self.values_mut()[index]) };
+        }
+        other.map = Bitmap::new();
+        let mut next = incoming.first_index();
+        while let Some(start) = next {
+            let end = incoming.next_false_index(start).unwrap_or(N);
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    other.values().as_ptr().add(start),
+                    self.values_mut().as_mut_ptr().add(start),
+                    end - start,
+                )
+            };
+            next = if end < N {
+                incoming.next_index(end)
+            } else {
+                None
+            };
+        }
+        self.map |= incoming;
+        debug_invariants!(self, other);
+    }
+
+    /// Move the contents of the chunk into a new chunk with a capacity of
+    /// `M`, keeping every value at the same index.
+    ///
+    /// If any value is at an index that's out of bounds for the new chunk,
+    /// you get the old one back as the error.
+    ///
+    /// Time: O(n)
+    pub fn into_capacity<const M: usize>(mut self) -> Result<SparseChunk<A, M>, Self>
+    where
+        BitsImpl<M>: Bits,
+    {
+        let last_index = match self.map.last_index() {
+            Some(index) if index >= M => return Err(self),
+            Some(index) => index,
+            None => return Ok(SparseChunk::new()),
+        };
+        let mut out = SparseChunk::new();
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.values().as_ptr(),
+                out.values_mut().as_mut_ptr(),
+                last_index + 1,
+            )
+        };
+        out.map = resize_bitmap(&mem::take(&mut self.map));
+        debug_invariants!(out);
+        Ok(out)
     }
 
     /// Remove the first value present in the array.
@@ -243,6 +384,11 @@ where
         self.map.first_index()
     }
 
+    /// Find the last index which contains a value.
+    pub fn last_index(&self) -> Option<usize> {
+        self.map.last_index()
+    }
+
     /// Make an iterator of references to the values contained in the array.
     pub fn iter(&self) -> Iter<'_, A, N> {
         Iter {
@@ -303,6 +449,82 @@ where
             index: 0,
         }
     }
+
+    /// Construct a chunk of the same capacity by calling `f` on each value in
+    /// turn, keeping each result at the same index as the value it came from.
+    ///
+    /// If `f` panics, the values it's already returned are dropped along with
+    /// the ones it hasn't seen yet.
+    ///
+    /// Time: O(n)
+    pub fn map<B, F>(self, mut f: F) -> SparseChunk<B, N>
+    where
+        F: FnMut(A) -> B,
+    {
+        self.map_indexed(|_, value| f(value))
+    }
+
+    /// Construct a chunk of the same capacity by calling `f` with the index
+    /// and the value of each entry in turn, keeping each result at the same
+    /// index as the value it came from.
+    ///
+    /// If `f` panics, the values it's already returned are dropped along with
+    /// the ones it hasn't seen yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use sized_chunks::SparseChunk;
+    /// let chunk = SparseChunk::<i32, 8>::pair(2, 10, 5, 20);
+    /// let mapped = chunk.map_indexed(|index, value| index as i32 + value);
+    /// assert_eq!(Some(&12), mapped.get(2));
+    /// assert_eq!(Some(&25), mapped.get(5));
+    /// ```
+    ///
+    /// Time: O(n)
+    pub fn map_indexed<B, F>(self, mut f: F) -> SparseChunk<B, N>
+    where
+        F: FnMut(usize, A) -> B,
+    {
+        match self.try_map_indexed(|index, value| Ok::<_, Infallible>(f(index, value))) {
+            Ok(chunk) => chunk,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Construct a chunk of the same capacity by calling `f` on each value in
+    /// turn, keeping each result at the same index as the value it came from,
+    /// and stopping at the first error `f` returns.
+    ///
+    /// On error, or if `f` panics, the values it's already returned are
+    /// dropped along with the ones it hasn't seen yet.
+    ///
+    /// Time: O(n)
+    pub fn try_map<B, E, F>(self, mut f: F) -> Result<SparseChunk<B, N>, E>
+    where
+        F: FnMut(A) -> Result<B, E>,
+    {
+        self.try_map_indexed(|_, value| f(value))
+    }
+
+    fn try_map_indexed<B, E, F>(mut self, mut f: F) -> Result<SparseChunk<B, N>, E>
+    where
+        F: FnMut(usize, A) -> Result<B, E>,
+    {
+        let mut out = SparseChunk::new();
+        let indices = self.map;
+        for index in &indices {
+            // Clear the index first, so `self` doesn't drop the value again if
+            // `f` panics.
+            self.map.set(index, false);
+            let value = unsafe { SparseChunk::force_read(index, &self) };
+            let value = f(index, value)?;
+            unsafe { SparseChunk::force_write(index, value, &mut out) };
+            out.map.set(index, true);
+        }
+        debug_invariants!(out);
+        Ok(out)
+    }
 }
 
 impl<A, const N: usize> Default for SparseChunk<A, N>
@@ -367,13 +589,14 @@ where
     }
 }
 
-impl<A, const N: usize> PartialEq for SparseChunk<A, N>
+impl<A, const M: usize, const N: usize> PartialEq<SparseChunk<A, M>> for SparseChunk<A, N>
 where
     A: PartialEq,
+    BitsImpl<M>: Bits,
     BitsImpl<N>: Bits,
 {
-    fn eq(&self, other: &Self) -> bool {
-        if self.map != other.map {
+    fn eq(&self, other: &SparseChunk<A, M>) -> bool {
+        if self.len() != other.len() {
             return false;
         }
         for index in self.indices() {
@@ -385,7 +608,7 @@ where
     }
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "alloc")]
 impl<A, const N: usize> PartialEq<BTreeMap<usize, A>> for SparseChunk<A, N>
 where
     A: PartialEq,
@@ -404,6 +627,43 @@ where
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<A, const N: usize> TryFrom<BTreeMap<usize, A>> for SparseChunk<A, N>
+where
+    BitsImpl<N>: Bits,
+{
+    type Error = BTreeMap<usize, A>;
+
+    /// Move the entries of a `BTreeMap` into a sparse chunk, using the keys as
+    /// indices.
+    ///
+    /// If any key is `N` or larger, you get the map back as the error.
+    fn try_from(map: BTreeMap<usize, A>) -> Result<Self, Self::Error> {
+        if map.keys().next_back().map_or(false, |&index| index >= N) {
+            return Err(map);
+        }
+        let mut chunk = Self::new();
+        for (index, value) in map {
+            chunk.insert(index, value);
+        }
+        Ok(chunk)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, const N: usize> From<SparseChunk<A, N>> for BTreeMap<usize, A>
+where
+    BitsImpl<N>: Bits,
+{
+    fn from(mut chunk: SparseChunk<A, N>) -> Self {
+        let mut map = BTreeMap::new();
+        while let Some(index) = chunk.first_index() {
+            map.insert(index, chunk.remove(index).unwrap());
+        }
+        map
+    }
+}
+
 #[cfg(feature = "std")]
 impl<A, const N: usize> PartialEq<HashMap<usize, A>> for SparseChunk<A, N>
 where
@@ -441,6 +701,21 @@ where
     }
 }
 
+/// Copy the bits of a bitmap into a bitmap of a different size.
+///
+/// Every set bit must fit in the new bitmap.
+fn resize_bitmap<const M: usize, const N: usize>(map: &Bitmap<M>) -> Bitmap<N>
+where
+    BitsImpl<M>: Bits,
+    BitsImpl<N>: Bits,
+{
+    let mut out = Bitmap::new();
+    for index in map {
+        out.set(index, true);
+    }
+    out
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -458,6 +733,10 @@ mod test {
         assert_eq!(4, chunk.len());
         let indices: Vec<_> = chunk.indices().collect();
         assert_eq!(vec![1, 5, 22, 31], indices);
+        assert_eq!(
+            (Some(1), Some(31)),
+            (chunk.first_index(), chunk.last_index())
+        );
         let values: Vec<_> = chunk.into_iter().collect();
         assert_eq!(vec![1, 5, 22, 31], values);
     }
@@ -483,6 +762,67 @@ mod test {
     use crate::tests::DropTest;
     use std::sync::atomic::{AtomicUsize, Ordering};
 
+    #[test]
+    #[should_panic(expected = "SparseChunk::check_invariants: index 7 is past capacity 5")]
+    fn check_invariants() {
+        let mut chunk = SparseChunk::<i32, 5>::pair(1, 1, 4, 4);
+        chunk.check_invariants();
+        // The bitmap's storage has room for three more bits than we can use.
+        chunk.map = Bitmap::from_value(0b1000_0000);
+        chunk.check_invariants();
+    }
+
+    #[test]
+    fn bitmap_matches_values() {
+        let counter = AtomicUsize::new(0);
+        let mut chunk: SparseChunk<DropTest<'_>, 32> = SparseChunk::new();
+        for step in 0..96 {
+            let index = (step * 7) % 32;
+            if step % 3 == 2 {
+                chunk.remove(index);
+            } else {
+                chunk.insert(index, DropTest::new(&counter));
+            }
+            chunk.check_invariants();
+            assert_eq!(chunk.len(), counter.load(Ordering::Relaxed));
+        }
+        let mut small: SparseChunk<DropTest<'_>, 8> = SparseChunk::new();
+        small.insert(3, DropTest::new(&counter));
+        small.insert(5, DropTest::new(&counter));
+        chunk.append_from(&mut small);
+        chunk.pop();
+        let chunk: SparseChunk<_, 64> = chunk.into_capacity().ok().unwrap();
+        chunk.check_invariants();
+        assert_eq!(chunk.len(), counter.load(Ordering::Relaxed));
+        drop(chunk);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn append_from_runs() {
+        let counter = AtomicUsize::new(0);
+        let mut chunk = SparseChunk::<_, 8>::from_fn(|i| {
+            if i % 3 == 0 {
+                Some(DropTest::new(&counter))
+            } else {
+                None
+            }
+        });
+        let mut other = SparseChunk::<_, 16>::from_fn(|i| {
+            if i == 1 || (4..8).contains(&i) {
+                Some(DropTest::new(&counter))
+            } else {
+                None
+            }
+        });
+        chunk.append_from(&mut other);
+        assert!(other.is_empty());
+        assert!(chunk.indices().eq(vec![0, 1, 3, 4, 5, 6, 7]));
+        assert_eq!(7, counter.load(Ordering::Relaxed));
+        drop(chunk);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+
     #[test]
     fn dropping() {
         let counter = AtomicUsize::new(0);
@@ -518,4 +858,109 @@ mod test {
         }
         assert_ne!(c1, c2);
     }
+
+    #[test]
+    fn capacity_changes() {
+        let small = SparseChunk::<String, 8>::pair(2, "a".to_string(), 5, "b".to_string());
+        let mut large: SparseChunk<String, 64> = small.into_capacity().unwrap();
+        assert_eq!(Some(&"a".to_string()), large.get(2));
+        assert_eq!(Some(&"b".to_string()), large.get(5));
+        assert_eq!(2, large.len());
+
+        let mut other = SparseChunk::<String, 32>::pair(5, "c".to_string(), 20, "d".to_string());
+        large.append_from(&mut other);
+        assert!(other.is_empty());
+        let entries: Vec<_> = large.entries().map(|(i, v)| (i, v.as_str())).collect();
+        assert_eq!(vec![(2, "a"), (5, "c"), (20, "d")], entries);
+
+        let large = large.into_capacity::<20>().unwrap_err();
+        let exact: SparseChunk<String, 21> = large.into_capacity().unwrap();
+        assert_eq!(3, exact.len());
+        let copy: SparseChunk<String, 128> = exact.clone().into_capacity().unwrap();
+        assert_eq!(exact, copy);
+        assert_ne!(exact, SparseChunk::<String, 8>::new());
+
+        let empty = SparseChunk::<String, 8>::new().into_capacity::<1>().unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "SparseChunk::append_from: index out of bounds")]
+    fn append_from_out_of_bounds() {
+        let mut chunk = SparseChunk::<i32, 8>::new();
+        chunk.append_from(&mut SparseChunk::<i32, 16>::unit(8, 0));
+    }
+
+    #[test]
+    fn map() {
+        let chunk = SparseChunk::<i32, 16>::pair(3, 1, 9, 2);
+        let mapped = chunk.map(|value| value.to_string());
+        let indices: Vec<_> = mapped.indices().collect();
+        assert_eq!(vec![3, 9], indices);
+        assert_eq!(Some(&"2".to_string()), mapped.get(9));
+
+        let chunk = SparseChunk::<i32, 16>::pair(3, 1, 9, 2);
+        let result = chunk.try_map(|value| if value < 2 { Ok(value) } else { Err(value) });
+        assert_eq!(Err(2), result.map(|_| ()));
+    }
+
+    #[test]
+    fn map_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        crate::tests::map_panic_safety(
+            &counter,
+            |counter| {
+                SparseChunk::<_, 16>::from_fn(|i| {
+                    if i % 2 == 0 {
+                        Some(DropTest::new(counter))
+                    } else {
+                        None
+                    }
+                })
+            },
+            |chunk, f| {
+                let _ = chunk.map_indexed(|_, value| f(value));
+            },
+        );
+    }
+
+    #[test]
+    fn from_fn() {
+        let chunk = SparseChunk::<String, 16>::from_fn(|i| {
+            if i % 3 == 0 {
+                Some(i.to_string())
+            } else {
+                None
+            }
+        });
+        assert_eq!(6, chunk.len());
+        let entries: Vec<_> = chunk.entries().map(|(i, v)| (i, v.as_str())).collect();
+        assert_eq!(vec![(0, "0"), (3, "3"), (6, "6"), (9, "9"), (12, "12"), (15, "15")], entries);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn btree_map_conversions() {
+        let mut map = BTreeMap::new();
+        map.insert(1, "one");
+        map.insert(7, "seven");
+        let chunk = SparseChunk::<_, 8>::try_from(map.clone()).unwrap();
+        assert_eq!(chunk, map);
+        assert_eq!(map, BTreeMap::from(chunk));
+
+        let map = SparseChunk::<_, 7>::try_from(map).unwrap_err();
+        assert_eq!(2, map.len());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn equality_with_btree_map() {
+        let chunk: SparseChunk<usize, 64> = (0..64)
+            .map(|i| if i % 3 == 0 { Some(i) } else { None })
+            .collect();
+        let mut map: BTreeMap<usize, usize> = (0..64).step_by(3).map(|i| (i, i)).collect();
+        assert_eq!(chunk, map);
+        map.insert(1, 1);
+        assert_ne!(chunk, map);
+    }
 }
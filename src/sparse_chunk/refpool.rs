@@ -1,9 +1,10 @@
-use core::mem::MaybeUninit;
+use core::mem::{self, MaybeUninit};
 
 use bitmaps::{Bitmap, Bits, BitsImpl};
 
 use ::refpool::{PoolClone, PoolDefault};
 
+use crate::util::DropOnUnwind;
 use crate::SparseChunk;
 
 impl<A, const N: usize> PoolDefault for SparseChunk<A, N>
@@ -27,17 +28,25 @@ where
         let map_ptr: *mut Bitmap<N> = &mut (*ptr).map;
         let data_ptr: *mut _ = &mut (*ptr).data;
         let data_ptr: *mut A = (*data_ptr).as_mut_ptr().cast();
-        map_ptr.write(self.map);
+        map_ptr.write(Bitmap::new());
+        // Panic safety: `target` holds a valid chunk of the values cloned so
+        // far at every step, which the guard drops if a clone panics.
+        let guard = DropOnUnwind(ptr);
         for index in &self.map {
             data_ptr.add(index).write(self[index].clone());
+            (*map_ptr).set(index, true);
         }
+        mem::forget(guard);
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::tests::FragileClone;
     use ::refpool::{Pool, PoolRef};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn default_and_clone() {
@@ -53,4 +62,19 @@ mod test {
         assert_eq!(ref1, ref2);
         assert!(!PoolRef::ptr_eq(&ref1, &ref2));
     }
+
+    #[test]
+    fn clone_uninit_panic_safety() {
+        let counter = AtomicUsize::new(0);
+        let mut chunk: SparseChunk<_, 8> = SparseChunk::new();
+        chunk.insert(1, FragileClone::new(&counter, false));
+        chunk.insert(4, FragileClone::new(&counter, true));
+        chunk.insert(6, FragileClone::new(&counter, false));
+        let pool = Pool::new(4);
+        let result = catch_unwind(AssertUnwindSafe(|| PoolRef::clone_from(&pool, &chunk)));
+        assert!(result.is_err());
+        assert_eq!(3, counter.load(Ordering::Relaxed));
+        drop(chunk);
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
 }
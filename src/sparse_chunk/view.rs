@@ -0,0 +1,480 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use core::fmt::{Debug, Error, Formatter};
+use core::iter::FusedIterator;
+use core::mem::{self, ManuallyDrop, MaybeUninit};
+use core::ops::{Index, IndexMut};
+use core::ptr;
+
+use bitmaps::{Bitmap, Bits, BitsImpl, Iter as BitmapIter};
+
+/// A sparse chunk over a borrowed buffer.
+///
+/// This works like a [`SparseChunk`][SparseChunk], with its bitmap of
+/// occupied indices kept inline, except that the values live in a slice of
+/// `MaybeUninit<A>` you lend it rather than in an inline array. The capacity
+/// is still `N`, and the buffer must be at least that long.
+///
+/// The view owns the values it holds, and drops them when it's dropped. Use
+/// [`into_raw_parts`][into_raw_parts] to hand them back to the buffer
+/// instead, and [`from_raw_parts`][from_raw_parts] to pick them up again.
+///
+/// # Examples
+///
+/// ```rust
+/// # use core::mem::MaybeUninit;
+/// # use sized_chunks::SparseChunkView;
+/// let mut buffer: Vec<MaybeUninit<i32>> = (0..20).map(|_| MaybeUninit::uninit()).collect();
+/// let mut chunk = SparseChunkView::<i32, 20>::new(&mut buffer);
+/// chunk.insert(18, 5);
+/// chunk.insert(5, 23);
+/// assert_eq!(chunk.len(), 2);
+/// assert_eq!(chunk.get(5), Some(&23));
+/// assert_eq!(chunk.get(6), None);
+/// ```
+///
+/// [SparseChunk]: struct.SparseChunk.html
+/// [into_raw_parts]: #method.into_raw_parts
+/// [from_raw_parts]: #method.from_raw_parts
+pub struct SparseChunkView<'a, A, const N: usize>
+where
+    BitsImpl<N>: Bits,
+{
+    map: Bitmap<N>,
+    data: &'a mut [MaybeUninit<A>],
+}
+
+impl<'a, A, const N: usize> Drop for SparseChunkView<'a, A, N>
+where
+    BitsImpl<N>: Bits,
+{
+    fn drop(&mut self) {
+        if mem::needs_drop::<A>() {
+            let bits = self.map;
+            for index in &bits {
+                unsafe { ptr::drop_in_place(self.data[index].as_mut_ptr()) }
+            }
+        }
+    }
+}
+
+impl<'a, A, const N: usize> SparseChunkView<'a, A, N>
+where
+    BitsImpl<N>: Bits,
+{
+    /// The maximum number of elements a `SparseChunkView` can contain.
+    pub const CAPACITY: usize = N;
+
+    /// Construct a new empty chunk over `buffer`.
+    ///
+    /// Panics if the buffer is shorter than `N`. Anything in the buffer past
+    /// index `N` is left alone.
+    pub fn new(buffer: &'a mut [MaybeUninit<A>]) -> Self {
+        if buffer.len() < N {
+            panic!("SparseChunkView::new: buffer is shorter than the capacity");
+        }
+        SparseChunkView {
+            map: Bitmap::new(),
+            data: buffer,
+        }
+    }
+
+    /// Construct a chunk over `buffer` holding the values at the indices set
+    /// in `map`.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must be at least `N` long, and the value at every index set
+    /// in `map` must be initialised. The chunk takes ownership of them, and
+    /// will drop them unless you take them back with
+    /// [`into_raw_parts`][into_raw_parts].
+    ///
+    /// [into_raw_parts]: #method.into_raw_parts
+    pub unsafe fn from_raw_parts(buffer: &'a mut [MaybeUninit<A>], map: Bitmap<N>) -> Self {
+        let chunk = SparseChunkView { map, data: buffer };
+        debug_invariants!(chunk);
+        chunk
+    }
+
+    /// Give the buffer back without dropping the chunk's values, along with
+    /// the bitmap of the indices which hold them.
+    ///
+    /// The values stay initialised in the buffer, and it's up to you to drop
+    /// them, or pass them to [`from_raw_parts`][from_raw_parts] to get the
+    /// chunk back.
+    ///
+    /// [from_raw_parts]: #method.from_raw_parts
+    pub fn into_raw_parts(self) -> (&'a mut [MaybeUninit<A>], Bitmap<N>) {
+        let this = ManuallyDrop::new(self);
+        let data = unsafe { ptr::read(&this.data) };
+        (data, this.map)
+    }
+
+    /// Get the length of the chunk.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Test if the chunk is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.len() == 0
+    }
+
+    /// Test if the chunk is at capacity.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Check the chunk's internal invariants, panicking if any of them don't
+    /// hold.
+    ///
+    /// This is meant for tests and fuzzing of code built on top of chunks.
+    /// With the `debug-invariants` feature enabled, it's called after every
+    /// method which changes the chunk.
+    ///
+    /// Time: O(1)
+    pub fn check_invariants(&self) {
+        assert!(
+            self.data.len() >= N,
+            "SparseChunkView::check_invariants: buffer length {} is less than capacity {}",
+            self.data.len(),
+            N
+        );
+        if let Some(index) = self.map.last_index() {
+            assert!(
+                index < N,
+                "SparseChunkView::check_invariants: index {} is past capacity {}",
+                index,
+                N
+            );
+        }
+    }
+
+    /// Insert a new value at a given index.
+    ///
+    /// Returns the previous value at that index, if any.
+    pub fn insert(&mut self, index: usize, value: A) -> Option<A> {
+        if index >= N {
+            panic!("SparseChunkView::insert: index out of bounds");
+        }
+        let slot = self.data[index].as_mut_ptr();
+        let result = if self.map.set(index, true) {
+            Some(unsafe { ptr::replace(slot, value) })
+        } else {
+            unsafe { slot.write(value) };
+            None
+        };
+        debug_invariants!(self);
+        result
+    }
+
+    /// Remove the value at a given index.
+    ///
+    /// Returns the value, or `None` if the index had no value.
+    pub fn remove(&mut self, index: usize) -> Option<A> {
+        if index >= N {
+            panic!("SparseChunkView::remove: index out of bounds");
+        }
+        let result = if self.map.set(index, false) {
+            Some(unsafe { self.data[index].as_ptr().read() })
+        } else {
+            None
+        };
+        debug_invariants!(self);
+        result
+    }
+
+    /// Remove the first value present in the array.
+    ///
+    /// Returns the value that was removed, or `None` if the array was empty.
+    pub fn pop(&mut self) -> Option<A> {
+        self.first_index().and_then(|index| self.remove(index))
+    }
+
+    /// Discard the contents of the chunk.
+    ///
+    /// Time: O(n)
+    pub fn clear(&mut self) {
+        let bits = mem::take(&mut self.map);
+        if mem::needs_drop::<A>() {
+            for index in &bits {
+                unsafe { ptr::drop_in_place(self.data[index].as_mut_ptr()) }
+            }
+        }
+    }
+
+    /// Get the value at a given index.
+    pub fn get(&self, index: usize) -> Option<&A> {
+        if index < N && self.map.get(index) {
+            Some(unsafe { &*self.data[index].as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the value at a given index.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut A> {
+        if index < N && self.map.get(index) {
+            Some(unsafe { &mut *self.data[index].as_mut_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Make an iterator over the indices which contain values.
+    pub fn indices(&self) -> BitmapIter<'_, N> {
+        self.map.into_iter()
+    }
+
+    /// Find the first index which contains a value.
+    pub fn first_index(&self) -> Option<usize> {
+        self.map.first_index()
+    }
+
+    /// Find the last index which contains a value.
+    pub fn last_index(&self) -> Option<usize> {
+        self.map.last_index()
+    }
+
+    /// Make an iterator of references to the values contained in the array.
+    pub fn iter(&self) -> impl Iterator<Item = &A> {
+        self.entries().map(|(_, value)| value)
+    }
+
+    /// Make an iterator of mutable references to the values contained in the
+    /// array.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut A> {
+        let map = self.map;
+        self.data[..N]
+            .iter_mut()
+            .enumerate()
+            .filter(move |(index, _)| map.get(*index))
+            .map(|(_, value)| unsafe { &mut *value.as_mut_ptr() })
+    }
+
+    /// Make an iterator of the indices and references to the values contained
+    /// in the array.
+    pub fn entries(&self) -> impl Iterator<Item = (usize, &A)> {
+        let data = &*self.data;
+        self.indices()
+            .map(move |index| (index, unsafe { &*data[index].as_ptr() }))
+    }
+
+    /// Make an iterator of `Option`s of references to the values contained in
+    /// the array.
+    ///
+    /// Iterates over every index in the chunk, from zero to its full capacity,
+    /// returning an `Option<&A>` for each index.
+    pub fn option_iter(&self) -> impl Iterator<Item = Option<&A>> {
+        (0..N).map(move |index| self.get(index))
+    }
+
+    /// Construct an iterator that drains values from the chunk in index
+    /// order.
+    ///
+    /// Any values left when the iterator is dropped stay in the chunk.
+    pub fn drain(&mut self) -> ViewDrain<'_, 'a, A, N> {
+        ViewDrain { chunk: self }
+    }
+}
+
+/// A draining iterator over a [`SparseChunkView`][SparseChunkView].
+///
+/// As the iterator yields each value, it's removed from the chunk, and when
+/// it terminates, the chunk will be empty.
+///
+/// [SparseChunkView]: struct.SparseChunkView.html
+pub struct ViewDrain<'b, 'a, A, const N: usize>
+where
+    BitsImpl<N>: Bits,
+{
+    chunk: &'b mut SparseChunkView<'a, A, N>,
+}
+
+impl<'b, 'a, A, const N: usize> Iterator for ViewDrain<'b, 'a, A, N>
+where
+    BitsImpl<N>: Bits,
+{
+    type Item = A;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunk.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.chunk.len(), Some(self.chunk.len()))
+    }
+}
+
+impl<'b, 'a, A, const N: usize> ExactSizeIterator for ViewDrain<'b, 'a, A, N> where BitsImpl<N>: Bits
+{}
+
+impl<'b, 'a, A, const N: usize> FusedIterator for ViewDrain<'b, 'a, A, N> where BitsImpl<N>: Bits {}
+
+impl<'a, A, const N: usize> Index<usize> for SparseChunkView<'a, A, N>
+where
+    BitsImpl<N>: Bits,
+{
+    type Output = A;
+
+    #[inline]
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl<'a, A, const N: usize> IndexMut<usize> for SparseChunkView<'a, A, N>
+where
+    BitsImpl<N>: Bits,
+{
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+impl<'a, A, const N: usize> Extend<(usize, A)> for SparseChunkView<'a, A, N>
+where
+    BitsImpl<N>: Bits,
+{
+    /// Insert each value from the iterator at its index, dropping any value
+    /// already there.
+    ///
+    /// Panics if an index is out of bounds.
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (usize, A)>,
+    {
+        for (index, value) in iter {
+            self.insert(index, value);
+        }
+    }
+}
+
+impl<'a, A, const N: usize> PartialEq for SparseChunkView<'a, A, N>
+where
+    A: PartialEq,
+    BitsImpl<N>: Bits,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map && self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, A, const N: usize> Eq for SparseChunkView<'a, A, N>
+where
+    A: Eq,
+    BitsImpl<N>: Bits,
+{
+}
+
+impl<'a, A, const N: usize> Debug for SparseChunkView<'a, A, N>
+where
+    A: Debug,
+    BitsImpl<N>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str("SparseChunkView")?;
+        f.debug_map().entries(self.entries()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tests::{buffer, DropTest};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::vec::Vec;
+
+    #[test]
+    fn insert_remove_iterate() {
+        let mut buffer = buffer(32);
+        let mut chunk = SparseChunkView::<usize, 32>::new(&mut buffer);
+        let mut model = BTreeMap::new();
+        for step in 0..200 {
+            let index = (step * 7) % 32;
+            if step % 3 == 0 {
+                assert_eq!(model.remove(&index), chunk.remove(index));
+            } else {
+                assert_eq!(model.insert(index, step), chunk.insert(index, step));
+            }
+            chunk.check_invariants();
+            assert_eq!(model.len(), chunk.len());
+            assert!(chunk.entries().eq(model.iter().map(|(k, v)| (*k, v))));
+        }
+        for value in chunk.iter_mut() {
+            *value += 1;
+        }
+        assert!(chunk
+            .iter()
+            .eq(model.values().map(|v| v + 1).collect::<Vec<_>>().iter()));
+    }
+
+    #[test]
+    fn option_iter_and_drain() {
+        let mut buffer = buffer(8);
+        let mut chunk = SparseChunkView::<i32, 8>::new(&mut buffer);
+        assert_eq!((None, None), (chunk.first_index(), chunk.last_index()));
+        chunk.extend(vec![(5, 50), (1, 10), (6, 60)]);
+        assert_eq!(
+            (Some(1), Some(6)),
+            (chunk.first_index(), chunk.last_index())
+        );
+        assert!(chunk.option_iter().eq(vec![
+            None,
+            Some(&10),
+            None,
+            None,
+            None,
+            Some(&50),
+            Some(&60),
+            None
+        ]));
+        assert_eq!(Some(10), chunk.drain().next());
+        assert_eq!(2, chunk.len());
+        assert_eq!(vec![50, 60], chunk.drain().collect::<Vec<_>>());
+        assert!(chunk.is_empty());
+    }
+
+    #[test]
+    fn raw_parts() {
+        let mut buffer = buffer(8);
+        let mut chunk = SparseChunkView::<i32, 8>::new(&mut buffer);
+        chunk.extend(vec![(1, 10), (6, 60)]);
+        let (buffer, map) = chunk.into_raw_parts();
+        assert!(map.get(1) && map.get(6));
+        let chunk = unsafe { SparseChunkView::<i32, 8>::from_raw_parts(buffer, map) };
+        assert_eq!(Some(&60), chunk.get(6));
+        assert_eq!(2, chunk.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "SparseChunkView::new: buffer is shorter than the capacity")]
+    fn short_buffer() {
+        let mut buffer = buffer::<i32>(4);
+        SparseChunkView::<i32, 8>::new(&mut buffer);
+    }
+
+    #[test]
+    fn dropping() {
+        let counter = AtomicUsize::new(0);
+        let mut buffer = buffer(64);
+        {
+            let mut chunk = SparseChunkView::<_, 64>::new(&mut buffer);
+            for index in 0..20 {
+                chunk.insert(index * 3, DropTest::new(&counter));
+            }
+            chunk.insert(0, DropTest::new(&counter));
+            assert_eq!(20, counter.load(Ordering::Relaxed));
+            chunk.remove(3);
+            assert_eq!(19, counter.load(Ordering::Relaxed));
+        }
+        assert_eq!(0, counter.load(Ordering::Relaxed));
+    }
+}
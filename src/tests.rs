@@ -1,4 +1,7 @@
+use std::mem::MaybeUninit;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::vec::Vec;
 
 pub(crate) struct DropTest<'a> {
     counter: &'a AtomicUsize,
@@ -16,3 +19,86 @@ impl<'a> Drop for DropTest<'a> {
         self.counter.fetch_sub(1, Ordering::Relaxed);
     }
 }
+
+/// An uninitialised buffer for the view types to borrow.
+pub(crate) fn buffer<A>(capacity: usize) -> Vec<MaybeUninit<A>> {
+    (0..capacity).map(|_| MaybeUninit::uninit()).collect()
+}
+
+/// A `DropTest` whose `Clone` impl panics if it was made with `fragile` set.
+pub(crate) struct FragileClone<'a> {
+    drop_test: DropTest<'a>,
+    fragile: bool,
+}
+
+impl<'a> FragileClone<'a> {
+    pub(crate) fn new(counter: &'a AtomicUsize, fragile: bool) -> Self {
+        FragileClone {
+            drop_test: DropTest::new(counter),
+            fragile,
+        }
+    }
+}
+
+impl<'a> Clone for FragileClone<'a> {
+    fn clone(&self) -> Self {
+        if self.fragile {
+            panic!("FragileClone::clone: fragile value");
+        }
+        FragileClone::new(self.drop_test.counter, false)
+    }
+}
+
+/// An iterator which claims to have `len` `DropTest`s in it, but panics after
+/// yielding `panic_after` of them.
+pub(crate) struct PanickingIter<'a> {
+    pub(crate) counter: &'a AtomicUsize,
+    pub(crate) len: usize,
+    pub(crate) panic_after: usize,
+}
+
+impl<'a> Iterator for PanickingIter<'a> {
+    type Item = DropTest<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.panic_after == 0 {
+            panic!("PanickingIter::next: out of patience");
+        }
+        self.panic_after -= 1;
+        self.len -= 1;
+        Some(DropTest::new(self.counter))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a> ExactSizeIterator for PanickingIter<'a> {}
+
+/// Check that a collection's `map` drops everything exactly once when the
+/// mapping function panics part way through.
+///
+/// `make` builds the collection with eight `DropTest`s in it, and `map` should
+/// call the collection's `map` with the function it's given, which panics on
+/// the fourth item.
+pub(crate) fn map_panic_safety<'a, C, Make, Map>(counter: &'a AtomicUsize, make: Make, map: Map)
+where
+    Make: FnOnce(&'a AtomicUsize) -> C,
+    Map: FnOnce(C, &mut dyn FnMut(DropTest<'a>) -> DropTest<'a>),
+{
+    let collection = make(counter);
+    assert_eq!(8, counter.load(Ordering::Relaxed));
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let mut seen = 0;
+        map(collection, &mut |value| {
+            seen += 1;
+            if seen == 4 {
+                panic!("boom");
+            }
+            value
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!(0, counter.load(Ordering::Relaxed));
+}
@@ -0,0 +1,74 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Internal helpers shared between the container types.
+
+/// Call `check_invariants()` on each of the given containers, but only if the
+/// `debug-invariants` feature is enabled. Put this after any code which
+/// changes a container's layout.
+macro_rules! debug_invariants {
+    ($($container:expr),+) => {
+        if cfg!(feature = "debug-invariants") {
+            $($container.check_invariants();)+
+        }
+    };
+}
+
+/// A compile time check that `M` items fit in a capacity of `N`.
+///
+/// Evaluating `Fits::<M, N>::CHECK` where `M > N` is a const evaluation error,
+/// which gets reported when the function using it is monomorphised.
+pub(crate) struct Fits<const M: usize, const N: usize>;
+
+impl<const M: usize, const N: usize> Fits<M, N> {
+    // `assert!` isn't allowed in constants on our minimum Rust version, but an
+    // out of bounds index is, and it fails the build just the same.
+    pub(crate) const CHECK: () = [()][(M > N) as usize];
+}
+
+/// Drops the value behind a pointer when it goes out of scope, unless it's
+/// disarmed with `mem::forget` first.
+///
+/// Code that builds a value in place can keep it valid at every step and hold
+/// one of these while calling into user code, so that a panic drops what's
+/// been built so far instead of leaking it.
+#[cfg(feature = "refpool")]
+pub(crate) struct DropOnUnwind<T>(pub(crate) *mut T);
+
+#[cfg(feature = "refpool")]
+impl<T> Drop for DropOnUnwind<T> {
+    fn drop(&mut self) {
+        unsafe { core::ptr::drop_in_place(self.0) }
+    }
+}
+
+/// Allocate uninitialised heap memory for a `T`, without ever placing a `T` on
+/// the stack.
+///
+/// `Box::new(Chunk::new())` builds the chunk on the stack and then moves it
+/// into the box, which overflows the stack for a large enough capacity. The
+/// boxed constructors use this instead, and write only the fields which need
+/// initialising, leaving the item storage uninitialised until items are added.
+///
+/// This is `Box::new_uninit()`, which isn't available on our minimum Rust
+/// version.
+#[cfg(feature = "alloc")]
+pub(crate) fn new_uninit_box<T>() -> alloc::boxed::Box<core::mem::MaybeUninit<T>> {
+    use alloc::alloc::{alloc, handle_alloc_error, Layout};
+
+    let layout = Layout::new::<T>();
+    // Even an untaken `Box::new(MaybeUninit::uninit())` here would reserve
+    // room for a `T` in this function's stack frame, so zero sized types get
+    // a dangling pointer, which is what `Box` uses for them anyway.
+    if layout.size() == 0 {
+        return unsafe { alloc::boxed::Box::from_raw(core::ptr::NonNull::dangling().as_ptr()) };
+    }
+    unsafe {
+        let ptr = alloc(layout);
+        if ptr.is_null() {
+            handle_alloc_error(layout);
+        }
+        alloc::boxed::Box::from_raw(ptr.cast())
+    }
+}